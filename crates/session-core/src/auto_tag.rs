@@ -0,0 +1,171 @@
+use rayon::prelude::*;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::data_dir;
+use crate::metadata;
+use crate::models::message::DisplayContentBlock;
+use crate::provider::{claude, codex};
+use crate::stats;
+
+/// `.session-viewer/auto-tags.json` in the data directory: a JSON object
+/// mapping a regex to the tag it adds, e.g. `{ "(?i)\\bsql\\b": "database" }`
+fn rules_path() -> Result<PathBuf, String> {
+    Ok(data_dir::data_dir()?
+        .join(".session-viewer")
+        .join("auto-tags.json"))
+}
+
+/// Compiled auto-tag rules sorted by pattern; empty when no rules file exists
+pub fn load_auto_tag_rules() -> Result<Vec<(Regex, String)>, String> {
+    let path = rules_path()?;
+    let content = match fs::read_to_string(&path) {
+        Ok(c) => c,
+        Err(_) => return Ok(Vec::new()),
+    };
+    let rules: BTreeMap<String, String> =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+    rules
+        .into_iter()
+        .map(|(pattern, tag)| {
+            Regex::new(&pattern)
+                .map(|re| (re, tag))
+                .map_err(|e| format!("Invalid auto-tag pattern {:?}: {}", pattern, e))
+        })
+        .collect()
+}
+
+/// Tag the sessions of a project whose message text matches an auto-tag rule.
+///
+/// Tags are added through `metadata::bulk_add_tags`, so sessions that already
+/// carry a rule's tag are left alone and re-running is a no-op. Returns the
+/// number of sessions that gained a tag (or would, with `dry_run`).
+pub fn apply_auto_tag_rules(
+    source: &str,
+    project_id: &str,
+    dry_run: bool,
+) -> Result<usize, String> {
+    let rules = load_auto_tag_rules()?;
+    if rules.is_empty() {
+        return Ok(0);
+    }
+    let sessions = stats::list_sessions(source, Some(project_id))?;
+
+    let additions: HashMap<String, Vec<String>> = sessions
+        .into_par_iter()
+        .filter_map(|session| {
+            let text = session_text(source, Path::new(&session.file_path))?;
+            let tags: Vec<String> = rules
+                .iter()
+                .filter(|(re, _)| re.is_match(&text))
+                .map(|(_, tag)| tag.clone())
+                .collect();
+            (!tags.is_empty()).then_some((session.session_id, tags))
+        })
+        .collect();
+
+    // Codex metadata is a single file shared by every cwd
+    let meta_project = if source == "codex" { "" } else { project_id };
+    metadata::bulk_add_tags(source, meta_project, &additions, dry_run)
+}
+
+/// All message text of a session, one block per line (tool calls excluded)
+fn session_text(source: &str, path: &Path) -> Option<String> {
+    let messages = match source {
+        "claude" => claude::parse_all_messages(path),
+        _ => codex::parse_all_messages(path),
+    }
+    .ok()?;
+    let text: Vec<&str> = messages
+        .iter()
+        .flat_map(|m| m.content.iter())
+        .filter_map(|block| match block {
+            DisplayContentBlock::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect();
+    Some(text.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{claude_assistant, claude_user, write_lines, TestHome};
+
+    const PROJECT: &str = "-home-user-project";
+
+    fn setup() -> TestHome {
+        let home = TestHome::new();
+        home.claude_session(
+            PROJECT,
+            "sql",
+            &[
+                claude_user("u1", "why is this SQL query slow?"),
+                claude_assistant("a1", "add an index", "claude-sonnet-4-6"),
+            ],
+        );
+        home.claude_session(
+            PROJECT,
+            "both",
+            &[
+                claude_user("u1", "write a migration"),
+                claude_assistant(
+                    "a1",
+                    "here is the sql, plus a Dockerfile",
+                    "claude-sonnet-4-6",
+                ),
+            ],
+        );
+        home.claude_session(PROJECT, "none", &[claude_user("u1", "hello")]);
+        write_lines(
+            &home.path().join(".session-viewer/auto-tags.json"),
+            &[r#"{ "(?i)\\bsql\\b": "database", "Dockerfile": "docker" }"#.to_string()],
+        );
+        home
+    }
+
+    fn tags(session_id: &str) -> Vec<String> {
+        metadata::load_metadata("claude", PROJECT)
+            .sessions
+            .get(session_id)
+            .map(|m| m.tags.clone())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn matching_sessions_are_tagged_once() {
+        let _home = setup();
+
+        assert_eq!(apply_auto_tag_rules("claude", PROJECT, false).unwrap(), 2);
+        assert_eq!(tags("sql"), ["database"]);
+        assert_eq!(tags("both"), ["database", "docker"]);
+        assert!(tags("none").is_empty());
+
+        assert_eq!(apply_auto_tag_rules("claude", PROJECT, false).unwrap(), 0);
+        assert_eq!(tags("both"), ["database", "docker"]);
+    }
+
+    #[test]
+    fn dry_run_counts_without_writing() {
+        let _home = setup();
+
+        assert_eq!(apply_auto_tag_rules("claude", PROJECT, true).unwrap(), 2);
+        assert!(tags("sql").is_empty());
+        assert!(tags("both").is_empty());
+    }
+
+    #[test]
+    fn missing_rules_tag_nothing_and_bad_patterns_are_errors() {
+        let home = setup();
+        let rules = home.path().join(".session-viewer/auto-tags.json");
+
+        fs::remove_file(&rules).unwrap();
+        assert_eq!(apply_auto_tag_rules("claude", PROJECT, false).unwrap(), 0);
+
+        write_lines(&rules, &[r#"{ "(unclosed": "x" }"#.to_string()]);
+        let err = apply_auto_tag_rules("claude", PROJECT, false).unwrap_err();
+        assert!(err.starts_with("Invalid auto-tag pattern"), "{}", err);
+    }
+}
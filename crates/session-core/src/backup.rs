@@ -0,0 +1,432 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter};
+use std::path::{Component, Path, PathBuf};
+
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::bookmarks;
+use crate::data_dir;
+use crate::parser::path_encoder::get_projects_dir;
+use crate::provider::codex;
+
+/// Archive entry holding the `BackupManifest`
+const MANIFEST_NAME: &str = "manifest.json";
+
+/// Archive entry holding the (shared) bookmarks file
+const BOOKMARKS_NAME: &str = "bookmarks/.session-viewer-bookmarks.json";
+
+/// Result of `backup_all`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSummary {
+    pub source: String,
+    pub archive_path: String,
+    pub file_count: usize,
+    /// Uncompressed size of all archived files
+    pub total_bytes: u64,
+    /// Size of the archive on disk
+    pub archive_bytes: u64,
+}
+
+/// Result of `restore_all`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreSummary {
+    pub source: String,
+    pub file_count: usize,
+    pub total_bytes: u64,
+    /// Backup of the data taken just before restoring
+    pub safety_backup_path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupManifest {
+    version: u32,
+    source: String,
+    created: String,
+}
+
+/// Archive prefixes and the directories or files they map to for a source.
+///
+/// Claude: `~/.claude/projects` (sessions + per-project metadata).
+/// Codex: `~/.codex/sessions` plus `~/.codex/.session-viewer-meta.json`.
+/// Both include the bookmarks file, which is shared by the sources, so a
+/// restore only brings back the archived source's bookmarks.
+fn backup_roots(source: &str) -> Result<Vec<(&'static str, PathBuf)>, String> {
+    let mut roots = match source {
+        "claude" => vec![(
+            "claude/projects",
+            get_projects_dir().ok_or("Could not find Claude projects directory")?,
+        )],
+        "codex" => {
+            let sessions_dir =
+                codex::get_sessions_dir().ok_or("Could not find Codex sessions directory")?;
+            let meta_path = sessions_dir
+                .parent()
+                .ok_or("Could not find Codex home directory")?
+                .join(".session-viewer-meta.json");
+            vec![
+                ("codex/sessions", sessions_dir),
+                ("codex/.session-viewer-meta.json", meta_path),
+            ]
+        }
+        _ => return Err(format!("Unknown source: {}", source)),
+    };
+    roots.push((BOOKMARKS_NAME, bookmarks::bookmarks_path()?));
+    Ok(roots)
+}
+
+/// Zip a provider's sessions, viewer metadata and bookmarks into `dest_path`.
+///
+/// Files are streamed into the archive one at a time, and the archive is
+/// written to a `.tmp` sibling first so a failed backup never leaves a
+/// truncated file at `dest_path`.
+pub fn backup_all(source: &str, dest_path: &str) -> Result<BackupSummary, String> {
+    let roots = backup_roots(source)?;
+    let dest = Path::new(dest_path);
+    if let Some(parent) = dest.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+    }
+
+    let tmp_path = dest.with_extension("zip.tmp");
+    let file = File::create(&tmp_path).map_err(|e| format!("Failed to create archive: {}", e))?;
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+
+    let result = write_archive(&mut zip, source, &roots);
+    let finished = result.and_then(|counts| {
+        zip.finish()
+            .map_err(|e| format!("Failed to finalize archive: {}", e))?;
+        Ok(counts)
+    });
+    let (file_count, total_bytes) = match finished {
+        Ok(counts) => counts,
+        Err(e) => {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(e);
+        }
+    };
+
+    fs::rename(&tmp_path, dest).map_err(|e| format!("Failed to rename archive: {}", e))?;
+    let archive_bytes = fs::metadata(dest).map(|m| m.len()).unwrap_or(0);
+
+    Ok(BackupSummary {
+        source: source.to_string(),
+        archive_path: dest.to_string_lossy().to_string(),
+        file_count,
+        total_bytes,
+        archive_bytes,
+    })
+}
+
+/// Write the manifest and every file under `roots`; returns (file count, bytes)
+fn write_archive<W: io::Write + io::Seek>(
+    zip: &mut ZipWriter<W>,
+    source: &str,
+    roots: &[(&'static str, PathBuf)],
+) -> Result<(usize, u64), String> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let manifest = BackupManifest {
+        version: 1,
+        source: source.to_string(),
+        created: chrono::Utc::now().to_rfc3339(),
+    };
+    zip.start_file(MANIFEST_NAME, options)
+        .map_err(|e| format!("Failed to write manifest: {}", e))?;
+    serde_json::to_writer_pretty(&mut *zip, &manifest)
+        .map_err(|e| format!("Failed to write manifest: {}", e))?;
+
+    let mut file_count = 0;
+    let mut total_bytes = 0;
+    for (prefix, root) in roots {
+        let mut files = Vec::new();
+        if root.is_dir() {
+            collect_files(root, &mut files);
+        } else if root.is_file() {
+            files.push(root.clone());
+        }
+
+        for path in files {
+            // Skip in-flight atomic writes
+            if path.extension().map(|e| e == "tmp").unwrap_or(false) {
+                continue;
+            }
+            let name = match path.strip_prefix(root) {
+                Ok(rel) if !rel.as_os_str().is_empty() => {
+                    format!("{}/{}", prefix, rel.to_string_lossy().replace('\\', "/"))
+                }
+                _ => prefix.to_string(),
+            };
+
+            let mut input = match File::open(&path) {
+                Ok(f) => BufReader::new(f),
+                Err(e) => {
+                    eprintln!("Warning: skipping {} in backup: {}", path.display(), e);
+                    continue;
+                }
+            };
+            let len = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            zip.start_file(name, options.large_file(len >= u32::MAX as u64))
+                .map_err(|e| format!("Failed to add {}: {}", path.display(), e))?;
+            let copied = io::copy(&mut input, zip)
+                .map_err(|e| format!("Failed to archive {}: {}", path.display(), e))?;
+
+            file_count += 1;
+            total_bytes += copied;
+        }
+    }
+
+    Ok((file_count, total_bytes))
+}
+
+/// Recursively collect regular files under `dir`
+fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(e) => e,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, out);
+        } else if path.is_file() {
+            out.push(path);
+        }
+    }
+}
+
+/// Unpack an archive made by `backup_all` back into place.
+///
+/// The current data is first backed up to
+/// `.session-viewer-backups/pre-restore-<source>-<timestamp>.zip` in the data
+/// directory. Archived files replace their originals through a `.tmp`
+/// sibling; files not in the archive are left alone. Of the bookmarks, only
+/// the archived source's entries are replaced.
+pub fn restore_all(archive_path: &str) -> Result<RestoreSummary, String> {
+    let file = File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let mut archive = ZipArchive::new(BufReader::new(file))
+        .map_err(|e| format!("Failed to read archive: {}", e))?;
+
+    let manifest: BackupManifest = {
+        let entry = archive
+            .by_name(MANIFEST_NAME)
+            .map_err(|_| "Not a session-viewer backup (missing manifest)".to_string())?;
+        serde_json::from_reader(entry).map_err(|e| format!("Invalid backup manifest: {}", e))?
+    };
+    let source = manifest.source;
+    let roots = backup_roots(&source)?;
+
+    let safety_path = data_dir::data_dir()?
+        .join(".session-viewer-backups")
+        .join(format!(
+            "pre-restore-{}-{}.zip",
+            source,
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+    let safety = backup_all(&source, &safety_path.to_string_lossy())?;
+
+    let mut file_count = 0;
+    let mut total_bytes = 0;
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read archive entry: {}", e))?;
+        if entry.is_dir() || entry.name() == MANIFEST_NAME {
+            continue;
+        }
+        // Rejects absolute paths and `..` components
+        let name = match entry.enclosed_name() {
+            Some(n) => n.to_string_lossy().replace('\\', "/"),
+            None => {
+                eprintln!("Warning: skipping unsafe archive entry {}", entry.name());
+                continue;
+            }
+        };
+        if name == BOOKMARKS_NAME {
+            let archived: bookmarks::BookmarksFile = serde_json::from_reader(&mut entry)
+                .map_err(|e| format!("Invalid bookmarks in archive: {}", e))?;
+            bookmarks::restore_source_bookmarks(&source, archived.bookmarks)?;
+            total_bytes += entry.size();
+            file_count += 1;
+            continue;
+        }
+        let target = match restore_target(&roots, &name) {
+            Some(t) => t,
+            None => {
+                eprintln!("Warning: skipping unknown archive entry {}", name);
+                continue;
+            }
+        };
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        total_bytes += write_atomically(&target, &mut entry)?;
+        file_count += 1;
+    }
+
+    Ok(RestoreSummary {
+        source,
+        file_count,
+        total_bytes,
+        safety_backup_path: safety.archive_path,
+    })
+}
+
+/// Copy `input` into a `.tmp` sibling of `target`, then rename it into place,
+/// so a failed restore never leaves a truncated file. Returns the bytes written.
+fn write_atomically(target: &Path, input: &mut impl io::Read) -> Result<u64, String> {
+    let file_name = target
+        .file_name()
+        .ok_or_else(|| format!("Invalid restore target: {}", target.display()))?;
+    let mut tmp_name = file_name.to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = target.with_file_name(tmp_name);
+
+    let result = (|| {
+        let file = File::create(&tmp_path)
+            .map_err(|e| format!("Failed to create {}: {}", tmp_path.display(), e))?;
+        let mut output = BufWriter::new(file);
+        let copied = io::copy(input, &mut output)
+            .map_err(|e| format!("Failed to restore {}: {}", target.display(), e))?;
+        output
+            .into_inner()
+            .map_err(|e| format!("Failed to restore {}: {}", target.display(), e))?;
+        Ok(copied)
+    })();
+    let copied = match result {
+        Ok(n) => n,
+        Err(e) => {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(e);
+        }
+    };
+    fs::rename(&tmp_path, target)
+        .map_err(|e| format!("Failed to rename {}: {}", target.display(), e))?;
+    Ok(copied)
+}
+
+/// Map an archive entry name back to its path on disk. The part below the
+/// root may only hold plain names, so it can never point outside the root.
+fn restore_target(roots: &[(&'static str, PathBuf)], name: &str) -> Option<PathBuf> {
+    roots.iter().find_map(|(prefix, root)| {
+        if name == *prefix {
+            return Some(root.clone());
+        }
+        let rel = Path::new(name.strip_prefix(prefix)?.strip_prefix('/')?);
+        if !rel.components().all(|c| matches!(c, Component::Normal(_))) {
+            return None;
+        }
+        Some(root.join(rel))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bookmarks::Bookmark;
+    use crate::test_support::{claude_assistant, claude_user, TestHome};
+    use std::io::Write;
+
+    const PROJECT: &str = "-home-user-project";
+
+    fn bookmark(source: &str, id: &str) -> Bookmark {
+        Bookmark {
+            id: id.to_string(),
+            source: source.to_string(),
+            project_id: PROJECT.to_string(),
+            session_id: "s1".to_string(),
+            file_path: String::new(),
+            message_id: None,
+            preview: String::new(),
+            session_title: "s1".to_string(),
+            project_name: "project".to_string(),
+            created_at: String::new(),
+            folder: None,
+        }
+    }
+
+    fn save_bookmarks(bookmarks: Vec<Bookmark>) {
+        let file = bookmarks::BookmarksFile {
+            version: 1,
+            bookmarks,
+        };
+        fs::write(
+            bookmarks::bookmarks_path().unwrap(),
+            serde_json::to_string(&file).unwrap(),
+        )
+        .unwrap();
+    }
+
+    fn bookmark_ids() -> Vec<String> {
+        let mut ids: Vec<String> = bookmarks::load_bookmarks()
+            .bookmarks
+            .into_iter()
+            .map(|b| b.id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn restore_brings_back_the_backed_up_state() {
+        let home = TestHome::new();
+        let session = home.claude_session(
+            PROJECT,
+            "s1",
+            &[
+                claude_user("u1", "question"),
+                claude_assistant("a1", "answer", "claude-sonnet-4-6"),
+            ],
+        );
+        let original = fs::read_to_string(&session).unwrap();
+        save_bookmarks(vec![bookmark("claude", "c1"), bookmark("codex", "x1")]);
+        let archive = home.path().join("backup.zip");
+        let summary = backup_all("claude", archive.to_str().unwrap()).unwrap();
+        assert_eq!(summary.file_count, 2);
+
+        fs::write(&session, "overwritten\n").unwrap();
+        let added = home.claude_session(PROJECT, "s2", &[claude_user("u2", "new")]);
+        save_bookmarks(vec![bookmark("claude", "c2"), bookmark("codex", "x2")]);
+
+        let restored = restore_all(archive.to_str().unwrap()).unwrap();
+        assert_eq!(restored.file_count, 2);
+        assert_eq!(fs::read_to_string(&session).unwrap(), original);
+        assert!(added.exists());
+        // Claude bookmarks come back; the Codex ones are left as they were
+        assert_eq!(bookmark_ids(), ["c1", "x2"]);
+        assert!(Path::new(&restored.safety_backup_path).is_file());
+        assert!(!session.with_file_name("s1.jsonl.tmp").exists());
+    }
+
+    #[test]
+    fn entries_escaping_their_root_are_skipped() {
+        let home = TestHome::new();
+        let projects_dir = home.claude_project(PROJECT).parent().unwrap().to_path_buf();
+        let archive = home.path().join("evil.zip");
+        let mut zip = ZipWriter::new(File::create(&archive).unwrap());
+        let options = SimpleFileOptions::default();
+        let manifest = r#"{"version": 1, "source": "claude", "created": "2025-01-15T10:00:00Z"}"#;
+        for (name, content) in [
+            (MANIFEST_NAME, manifest),
+            ("claude/projects/../../evil.txt", "evil"),
+            ("../outside.txt", "evil"),
+            ("claude/projects/-home-user-project/s1.jsonl", "{}\n"),
+        ] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let restored = restore_all(archive.to_str().unwrap()).unwrap();
+        assert_eq!(restored.file_count, 1);
+        assert!(projects_dir.join(PROJECT).join("s1.jsonl").is_file());
+        assert!(!home.path().join(".claude/evil.txt").exists());
+        assert!(!home.path().join("evil.txt").exists());
+        assert!(!home.path().parent().unwrap().join("outside.txt").exists());
+    }
+}
@@ -1,6 +1,11 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use crate::data_dir;
+use crate::models::message::{DisplayContentBlock, DisplayMessage};
+use crate::provider::{claude, codex};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -22,11 +27,31 @@ pub struct Bookmark {
     pub session_title: String,
     pub project_name: String,
     pub created_at: String,
+    /// Folder the bookmark is filed under; `None` is the top level
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+}
+
+/// Whether a bookmark still points at something
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BookmarkStatus {
+    Ok,
+    /// The session file is gone
+    FileMissing,
+    /// The file exists but the bookmarked message isn't in it
+    MessageMissing,
 }
 
-fn bookmarks_path() -> Result<PathBuf, String> {
-    let home = dirs::home_dir().ok_or("Cannot determine home directory")?;
-    Ok(home.join(".session-viewer-bookmarks.json"))
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookmarkAudit {
+    pub bookmark: Bookmark,
+    pub status: BookmarkStatus,
+}
+
+pub(crate) fn bookmarks_path() -> Result<PathBuf, String> {
+    Ok(data_dir::data_dir()?.join(".session-viewer-bookmarks.json"))
 }
 
 pub fn load_bookmarks() -> BookmarksFile {
@@ -67,7 +92,13 @@ fn generate_id() -> String {
     format!("{:x}", ts)
 }
 
-pub fn add_bookmark(bookmark: Bookmark) -> Result<Bookmark, String> {
+/// Add a bookmark. With `verify`, the session file must exist and, for a
+/// message bookmark, the message must be present in it.
+pub fn add_bookmark(bookmark: Bookmark, verify: bool) -> Result<Bookmark, String> {
+    if verify {
+        verify_bookmark(&bookmark)?;
+    }
+
     let mut file = load_bookmarks();
 
     // Deduplicate: same session + message_id
@@ -93,6 +124,147 @@ pub fn add_bookmark(bookmark: Bookmark) -> Result<Bookmark, String> {
     Ok(bm)
 }
 
+/// Reject bookmarks that would dangle as soon as they are created
+fn verify_bookmark(bookmark: &Bookmark) -> Result<(), String> {
+    let path = Path::new(&bookmark.file_path);
+    if !path.is_file() {
+        return Err(format!("Session file not found: {}", bookmark.file_path));
+    }
+
+    let message_id = match bookmark.message_id.as_deref() {
+        Some(id) if !id.is_empty() => id,
+        _ => return Ok(()),
+    };
+    let messages = load_messages(&bookmark.source, path)?;
+    if find_message(&messages, message_id).is_none() {
+        return Err(format!("Message not found in session: {}", message_id));
+    }
+    Ok(())
+}
+
+pub(crate) fn load_messages(source: &str, path: &Path) -> Result<Vec<DisplayMessage>, String> {
+    match source {
+        "claude" => claude::parse_all_messages(path),
+        "codex" => codex::parse_all_messages(path),
+        _ => Err(format!("Unknown source: {}", source)),
+    }
+}
+
+/// Index of a bookmarked message. Messages without a uuid are bookmarked by
+/// position ("user-<index>").
+pub(crate) fn find_message(messages: &[DisplayMessage], message_id: &str) -> Option<usize> {
+    messages
+        .iter()
+        .position(|m| m.uuid.as_deref() == Some(message_id))
+        .or_else(|| {
+            message_id
+                .strip_prefix("user-")
+                .and_then(|i| i.parse::<usize>().ok())
+                .filter(|i| *i < messages.len())
+        })
+}
+
+/// Look up a bookmark by id
+pub fn resolve_bookmark(id: &str) -> Result<Bookmark, String> {
+    load_bookmarks()
+        .bookmarks
+        .into_iter()
+        .find(|b| b.id == id)
+        .ok_or_else(|| "Bookmark not found".to_string())
+}
+
+/// Render a bookmarked message with up to `before`/`after` surrounding
+/// messages as Markdown. The window is clamped to the session; a session-level
+/// bookmark starts at the first message. If the message is gone, the stored
+/// preview is returned with a note instead of an error.
+pub fn export_bookmark_context(
+    id: &str,
+    before: usize,
+    after: usize,
+    source: &str,
+) -> Result<String, String> {
+    let bookmark = resolve_bookmark(id)?;
+    if bookmark.source != source {
+        return Err("Bookmark not found".to_string());
+    }
+
+    let mut out = String::new();
+    let title = if bookmark.session_title.is_empty() {
+        &bookmark.session_id
+    } else {
+        &bookmark.session_title
+    };
+    out.push_str(&format!("## {}\n\n", title));
+    if !bookmark.project_name.is_empty() {
+        out.push_str(&format!("_{}_\n\n", bookmark.project_name));
+    }
+
+    let path = Path::new(&bookmark.file_path);
+    let messages = if path.is_file() {
+        load_messages(source, path)?
+    } else {
+        Vec::new()
+    };
+    let target = match bookmark.message_id.as_deref().filter(|m| !m.is_empty()) {
+        Some(message_id) => find_message(&messages, message_id),
+        None if !messages.is_empty() => Some(0),
+        None => None,
+    };
+    let target = match target {
+        Some(i) => i,
+        None => {
+            out.push_str("> The bookmarked message is no longer in this session.\n\n");
+            out.push_str(&bookmark.preview);
+            out.push('\n');
+            return Ok(out);
+        }
+    };
+
+    let start = target.saturating_sub(before);
+    let end = target.saturating_add(after).min(messages.len() - 1);
+    for (i, msg) in messages.iter().enumerate().take(end + 1).skip(start) {
+        let marker = if i == target && bookmark.message_id.is_some() {
+            " (bookmarked)"
+        } else {
+            ""
+        };
+        out.push_str(&format!("### {}{}", capitalize(msg.role.as_str()), marker));
+        if let Some(ts) = &msg.timestamp {
+            out.push_str(&format!(" · {}", ts));
+        }
+        out.push_str("\n\n");
+        out.push_str(&message_markdown(msg));
+        out.push_str("\n\n");
+    }
+    Ok(out.trim_end().to_string() + "\n")
+}
+
+/// Text blocks verbatim; tool traffic reduced to one-line notes
+fn message_markdown(msg: &DisplayMessage) -> String {
+    let parts: Vec<String> = msg
+        .content
+        .iter()
+        .filter_map(|block| match block {
+            DisplayContentBlock::Text { text } => Some(text.trim().to_string()),
+            DisplayContentBlock::ToolUse { name, .. } => Some(format!("_[tool: {}]_", name)),
+            DisplayContentBlock::FunctionCall { name, .. } => Some(format!("_[tool: {}]_", name)),
+            DisplayContentBlock::ToolResult { .. }
+            | DisplayContentBlock::FunctionCallOutput { .. } => Some("_[tool result]_".to_string()),
+            _ => None,
+        })
+        .filter(|s| !s.is_empty())
+        .collect();
+    parts.join("\n\n")
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
 pub fn remove_bookmark(id: &str) -> Result<(), String> {
     let mut file = load_bookmarks();
     let len_before = file.bookmarks.len();
@@ -104,6 +276,87 @@ pub fn remove_bookmark(id: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Result of `move_bookmarks`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveSummary {
+    pub moved: usize,
+    /// Requested ids with no bookmark, which were skipped
+    pub missing: Vec<String>,
+}
+
+/// Move every listed bookmark into `folder` (`None` or blank for the top
+/// level) with a single save. Ids that don't exist are skipped and reported
+/// in `missing`.
+pub fn move_bookmarks(ids: &[String], folder: Option<&str>) -> Result<MoveSummary, String> {
+    let folder = folder.map(str::trim).filter(|f| !f.is_empty());
+    let mut file = load_bookmarks();
+
+    let mut summary = MoveSummary::default();
+    for id in ids {
+        match file.bookmarks.iter_mut().find(|b| &b.id == id) {
+            Some(bookmark) => {
+                bookmark.folder = folder.map(str::to_string);
+                summary.moved += 1;
+            }
+            None => summary.missing.push(id.clone()),
+        }
+    }
+
+    if summary.moved > 0 {
+        save_bookmarks(&file)?;
+    }
+    Ok(summary)
+}
+
+/// Replace the bookmarks of one source with `restored` (their entries of that
+/// source only), leaving other sources' bookmarks as they are. Used when a
+/// per-source backup is restored; returns how many bookmarks were restored.
+pub(crate) fn restore_source_bookmarks(
+    source: &str,
+    restored: Vec<Bookmark>,
+) -> Result<usize, String> {
+    let mut file = load_bookmarks();
+    file.bookmarks.retain(|b| b.source != source);
+    let before = file.bookmarks.len();
+    file.bookmarks
+        .extend(restored.into_iter().filter(|b| b.source == source));
+    let count = file.bookmarks.len() - before;
+    save_bookmarks(&file)?;
+    Ok(count)
+}
+
+/// Classify every bookmark as ok, file-missing or message-missing, so
+/// dangling ones can be cleaned up by reason. Each session file is parsed
+/// once however many bookmarks point into it.
+pub fn audit_bookmarks() -> Vec<BookmarkAudit> {
+    let mut parsed: HashMap<String, Option<Vec<DisplayMessage>>> = HashMap::new();
+    load_bookmarks()
+        .bookmarks
+        .into_iter()
+        .map(|bookmark| {
+            let path = Path::new(&bookmark.file_path);
+            let status = if !path.is_file() {
+                BookmarkStatus::FileMissing
+            } else {
+                match bookmark.message_id.as_deref().filter(|id| !id.is_empty()) {
+                    None => BookmarkStatus::Ok,
+                    Some(message_id) => {
+                        let messages = parsed
+                            .entry(bookmark.file_path.clone())
+                            .or_insert_with(|| load_messages(&bookmark.source, path).ok());
+                        match messages {
+                            Some(m) if find_message(m, message_id).is_some() => BookmarkStatus::Ok,
+                            _ => BookmarkStatus::MessageMissing,
+                        }
+                    }
+                }
+            };
+            BookmarkAudit { bookmark, status }
+        })
+        .collect()
+}
+
 pub fn list_bookmarks(source: Option<&str>) -> Vec<Bookmark> {
     let file = load_bookmarks();
     match source {
@@ -111,3 +364,163 @@ pub fn list_bookmarks(source: Option<&str>) -> Vec<Bookmark> {
         None => file.bookmarks,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{claude_assistant, claude_user, TestHome};
+
+    const PROJECT: &str = "-home-user-project";
+
+    fn bookmark(file_path: &str, message_id: Option<&str>) -> Bookmark {
+        Bookmark {
+            id: String::new(),
+            source: "claude".to_string(),
+            project_id: PROJECT.to_string(),
+            session_id: "s1".to_string(),
+            file_path: file_path.to_string(),
+            message_id: message_id.map(str::to_string),
+            preview: "first answer".to_string(),
+            session_title: "s1".to_string(),
+            project_name: "project".to_string(),
+            created_at: String::new(),
+            folder: None,
+        }
+    }
+
+    fn session(home: &TestHome) -> String {
+        home.claude_session(
+            PROJECT,
+            "s1",
+            &[
+                claude_user("u1", "first question"),
+                claude_assistant("a1", "first answer", "claude-sonnet-4-6"),
+            ],
+        )
+        .to_string_lossy()
+        .into_owned()
+    }
+
+    #[test]
+    fn bookmark_on_an_existing_message_is_added() {
+        let home = TestHome::new();
+        let path = session(&home);
+
+        let added = add_bookmark(bookmark(&path, Some("a1")), true).unwrap();
+        assert!(!added.id.is_empty());
+        let listed = list_bookmarks(Some("claude"));
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].message_id.as_deref(), Some("a1"));
+        // A whole-session bookmark only needs the file
+        add_bookmark(bookmark(&path, None), true).unwrap();
+    }
+
+    #[test]
+    fn bookmark_on_a_missing_file_is_rejected() {
+        let home = TestHome::new();
+        let missing = home.path().join("nope.jsonl");
+        let missing = missing.to_str().unwrap();
+
+        let err = add_bookmark(bookmark(missing, None), true).unwrap_err();
+        assert!(err.starts_with("Session file not found"), "{}", err);
+        assert!(list_bookmarks(None).is_empty());
+    }
+
+    #[test]
+    fn bookmark_on_a_missing_message_is_rejected() {
+        let home = TestHome::new();
+        let path = session(&home);
+
+        let err = add_bookmark(bookmark(&path, Some("zz9")), true).unwrap_err();
+        assert_eq!(err, "Message not found in session: zz9");
+        assert!(list_bookmarks(None).is_empty());
+    }
+
+    #[test]
+    fn unverified_bookmark_skips_the_checks() {
+        let home = TestHome::new();
+        let missing = home.path().join("nope.jsonl");
+
+        add_bookmark(bookmark(missing.to_str().unwrap(), Some("zz9")), false).unwrap();
+        assert_eq!(list_bookmarks(None).len(), 1);
+    }
+
+    #[test]
+    fn audit_classifies_each_bookmark() {
+        let home = TestHome::new();
+        let path = session(&home);
+        let gone = home
+            .path()
+            .join("gone.jsonl")
+            .to_string_lossy()
+            .into_owned();
+        let cases = [
+            (&path, Some("a1"), BookmarkStatus::Ok),
+            (&path, None, BookmarkStatus::Ok),
+            (&path, Some("zz9"), BookmarkStatus::MessageMissing),
+            (&gone, Some("a1"), BookmarkStatus::FileMissing),
+            (&gone, None, BookmarkStatus::FileMissing),
+        ];
+        for (file_path, message_id, _) in &cases {
+            // Bookmarks are unique per session and message
+            let session_id = if *file_path == &path { "s1" } else { "gone" };
+            let bookmark = Bookmark {
+                session_id: session_id.to_string(),
+                ..bookmark(file_path, *message_id)
+            };
+            add_bookmark(bookmark, false).unwrap();
+        }
+
+        let audited: Vec<_> = audit_bookmarks()
+            .into_iter()
+            .map(|a| (a.bookmark.file_path, a.bookmark.message_id, a.status))
+            .collect();
+        let expected: Vec<_> = cases
+            .iter()
+            .map(|(file, id, status)| (file.to_string(), id.map(str::to_string), *status))
+            .collect();
+        assert_eq!(audited, expected);
+    }
+
+    #[test]
+    fn move_bookmarks_refiles_the_listed_ones() {
+        let home = TestHome::new();
+        let path = session(&home);
+        let ids: Vec<String> = ["s1", "s2", "s3", "s4"]
+            .iter()
+            .map(|session_id| {
+                let bookmark = Bookmark {
+                    session_id: session_id.to_string(),
+                    ..bookmark(&path, None)
+                };
+                add_bookmark(bookmark, false).unwrap().id
+            })
+            .collect();
+
+        let mut to_move = ids[..3].to_vec();
+        to_move.push("no-such-id".to_string());
+        assert_eq!(
+            move_bookmarks(&to_move, Some(" research ")).unwrap(),
+            MoveSummary {
+                moved: 3,
+                missing: vec!["no-such-id".to_string()],
+            }
+        );
+
+        let folders: Vec<_> = list_bookmarks(None)
+            .into_iter()
+            .map(|b| (b.session_id, b.folder))
+            .collect();
+        let research = Some("research".to_string());
+        assert!(folders.contains(&("s1".to_string(), research.clone())));
+        assert!(folders.contains(&("s2".to_string(), research.clone())));
+        assert!(folders.contains(&("s3".to_string(), research)));
+        assert!(folders.contains(&("s4".to_string(), None)));
+
+        assert_eq!(move_bookmarks(&ids[..1], None).unwrap().moved, 1);
+        let unknown = ["no-such-id".to_string()];
+        let summary = move_bookmarks(&unknown, None).unwrap();
+        assert_eq!(summary.moved, 0);
+        assert_eq!(summary.missing, unknown);
+    }
+}
@@ -1,6 +1,11 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::process::Command;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// Upper bound on how long incremental discovery waits for version probes
+pub const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -10,27 +15,12 @@ pub struct CliInstallation {
     pub cli_type: String, // "claude"
 }
 
-/// Find the Claude CLI binary path.
+/// Find the Claude CLI binary path: the first of `candidate_paths`.
 pub fn find_cli(_cli_type: &str) -> Result<String, String> {
-    let binary_name = if cfg!(windows) {
-        "claude.exe"
-    } else {
-        "claude"
-    };
-
-    // Try system lookup first (which/where)
-    if let Some(path) = which_binary(binary_name) {
-        return Ok(path);
-    }
-
-    // Try known paths
-    for candidate in known_paths() {
-        if candidate.exists() {
-            return Ok(candidate.to_string_lossy().to_string());
-        }
-    }
-
-    Err("Claude CLI not found. Please install it first.".to_string())
+    candidate_paths()
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Claude CLI not found. Please install it first.".to_string())
 }
 
 /// Discover installed Claude CLI.
@@ -49,6 +39,83 @@ pub fn discover_installations() -> Vec<CliInstallation> {
     installations
 }
 
+/// Discover every Claude CLI binary on this machine, calling `on_found` as soon
+/// as each version probe completes (in completion order, not path order).
+///
+/// Probes run concurrently; any still running when `timeout` expires are
+/// abandoned and their installations are not reported.
+pub fn discover_installations_streaming(
+    timeout: Duration,
+    on_found: impl Fn(&CliInstallation),
+) -> Vec<CliInstallation> {
+    let candidates = candidate_paths();
+    let pending = candidates.len();
+    let (tx, rx) = mpsc::channel();
+
+    for path in candidates {
+        let tx = tx.clone();
+        std::thread::spawn(move || {
+            let version = get_cli_version(&path);
+            let _ = tx.send(CliInstallation {
+                path,
+                version,
+                cli_type: "claude".to_string(),
+            });
+        });
+    }
+    drop(tx);
+
+    let deadline = Instant::now() + timeout;
+    let mut installations = Vec::new();
+    while installations.len() < pending {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match rx.recv_timeout(remaining) {
+            Ok(installation) => {
+                on_found(&installation);
+                installations.push(installation);
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                eprintln!(
+                    "Warning: CLI discovery timed out with {} probe(s) pending",
+                    pending - installations.len()
+                );
+                break;
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    installations
+}
+
+/// All existing Claude CLI paths: the `which`/`where` hit first, then known
+/// install locations, deduplicated.
+fn candidate_paths() -> Vec<String> {
+    let mut paths: Vec<String> = Vec::new();
+    if let Some(path) = which_binary(binary_name()) {
+        paths.push(path);
+    }
+    for candidate in known_paths() {
+        if !candidate.exists() {
+            continue;
+        }
+        let path = candidate.to_string_lossy().to_string();
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// File name of the Claude CLI binary on this platform
+fn binary_name() -> &'static str {
+    if cfg!(windows) {
+        "claude.exe"
+    } else {
+        "claude"
+    }
+}
+
 /// Use `where` (Windows) or `which` (Unix) to find a binary.
 fn which_binary(name: &str) -> Option<String> {
     #[cfg(windows)]
@@ -76,12 +143,7 @@ fn which_binary(name: &str) -> Option<String> {
 fn known_paths() -> Vec<PathBuf> {
     let mut paths = Vec::new();
     let home = dirs::home_dir();
-
-    let binary_name = if cfg!(windows) {
-        "claude.exe"
-    } else {
-        "claude"
-    };
+    let binary_name = binary_name();
 
     if let Some(ref home) = home {
         // npm global
@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::env;
-use std::path::PathBuf;
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+
+use crate::settings;
 
 /// CLI configuration info returned to the frontend (API key is masked).
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,6 +18,60 @@ pub struct CliConfig {
     pub config_path: String,
 }
 
+/// Where a resolved config value came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ConfigOrigin {
+    /// The `env` map in `~/.claude/settings.json`, under this key
+    SettingsEnv { key: String },
+    /// A top-level field of `~/.claude/settings.json`
+    SettingsField { field: String },
+    /// A process environment variable
+    EnvVar { name: String },
+    /// The viewer's own settings (`.session-viewer-settings.json` in the data directory)
+    ViewerSettings { field: String },
+    /// The built-in default
+    Default,
+    /// Nothing configured the value
+    Unset,
+}
+
+/// Which source won for each config value. Never carries the API key itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigExplanation {
+    pub source: String,
+    pub api_key: ConfigOrigin,
+    pub base_url: ConfigOrigin,
+    /// Resolved base URL (not secret)
+    pub base_url_value: String,
+    pub default_model: ConfigOrigin,
+    /// Resolved default model (not secret)
+    pub default_model_value: String,
+    pub config_path: String,
+}
+
+/// Whether the CLI config file can be written, and why not if it can't
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigWritability {
+    pub writable: bool,
+    pub config_path: String,
+    /// Why the file can't be written (None when writable)
+    pub reason: Option<String>,
+}
+
+/// Fully resolved Claude config with the origin of each value
+struct ResolvedConfig {
+    api_key: String,
+    api_key_origin: ConfigOrigin,
+    base_url: String,
+    base_url_origin: ConfigOrigin,
+    default_model: String,
+    default_model_origin: ConfigOrigin,
+    config_path: String,
+}
+
 // ── Internal deserialization structures ──
 
 /// Claude's `~/.claude/settings.json`
@@ -44,14 +101,78 @@ pub fn read_cli_config(source: &str) -> Result<CliConfig, String> {
     })
 }
 
+/// Explain which source won for the API key, base URL and default model.
+pub fn explain_config(source: &str) -> Result<ConfigExplanation, String> {
+    // Chat features always use the Claude config, as in `read_cli_config`
+    let _ = source;
+    let resolved = resolve_claude_config()?;
+
+    Ok(ConfigExplanation {
+        source: "claude".to_string(),
+        api_key: resolved.api_key_origin,
+        base_url: resolved.base_url_origin,
+        base_url_value: resolved.base_url,
+        default_model: resolved.default_model_origin,
+        default_model_value: resolved.default_model,
+        config_path: resolved.config_path,
+    })
+}
+
+/// Check whether the config file could be saved, without changing it.
+///
+/// An existing file is opened for appending (nothing is written). A missing
+/// file counts as writable if it could be created: the nearest existing
+/// ancestor directory must accept a new file, probed with a temporary file
+/// that is removed again. Read-only mounts and permission errors are reported
+/// in `reason`.
+pub fn config_writable(source: &str) -> Result<ConfigWritability, String> {
+    // Chat features always use the Claude config, as in `read_cli_config`
+    let _ = source;
+    let path = claude_settings_path()?;
+
+    let reason = writability_problem(&path).err();
+    Ok(ConfigWritability {
+        writable: reason.is_none(),
+        config_path: path.display().to_string(),
+        reason,
+    })
+}
+
+fn writability_problem(path: &Path) -> Result<(), String> {
+    if path.exists() {
+        if path.is_dir() {
+            return Err(format!("{} is a directory", path.display()));
+        }
+        return OpenOptions::new()
+            .append(true)
+            .open(path)
+            .map(|_| ())
+            .map_err(|e| format!("Cannot write {}: {}", path.display(), e));
+    }
+
+    let dir = path
+        .ancestors()
+        .skip(1)
+        .find(|p| p.exists())
+        .ok_or_else(|| format!("No existing parent directory for {}", path.display()))?;
+    if !dir.is_dir() {
+        return Err(format!("{} is not a directory", dir.display()));
+    }
+    let probe = dir.join(format!(".session-viewer-probe-{}", std::process::id()));
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .map_err(|e| format!("Cannot create files in {}: {}", dir.display(), e))?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
 /// Get real credentials for internal use (e.g. model_list, quick_chat).
 pub(crate) fn get_credentials(_source: &str) -> (String, String) {
     match read_claude_config() {
         Ok((api_key, base_url, _, _)) if !api_key.is_empty() => (api_key, base_url),
-        _ => (
-            String::new(),
-            "https://api.anthropic.com".to_string(),
-        ),
+        _ => (String::new(), settings::default_base_url()),
     }
 }
 
@@ -59,34 +180,86 @@ pub(crate) fn get_credentials(_source: &str) -> (String, String) {
 
 /// Returns (api_key, base_url, default_model, config_path).
 fn read_claude_config() -> Result<(String, String, String, String), String> {
+    let resolved = resolve_claude_config()?;
+    Ok((
+        resolved.api_key,
+        resolved.base_url,
+        resolved.default_model,
+        resolved.config_path,
+    ))
+}
+
+fn claude_settings_path() -> Result<PathBuf, String> {
     let home = dirs::home_dir().ok_or("Cannot determine home directory")?;
-    let settings_path = home.join(".claude").join("settings.json");
-    let config_path_str = settings_path.display().to_string();
+    Ok(home.join(".claude").join("settings.json"))
+}
+
+fn resolve_claude_config() -> Result<ResolvedConfig, String> {
+    let settings_path = claude_settings_path()?;
+    let config_path = settings_path.display().to_string();
 
     let settings = read_json_file::<ClaudeSettings>(&settings_path).unwrap_or_default();
+    let settings_env = |key: &str| {
+        settings.env.get(key).filter(|s| !s.is_empty()).map(|v| {
+            (
+                v.clone(),
+                ConfigOrigin::SettingsEnv {
+                    key: key.to_string(),
+                },
+            )
+        })
+    };
+    let process_env = |name: &str| {
+        env::var(name).ok().filter(|s| !s.is_empty()).map(|v| {
+            (
+                v,
+                ConfigOrigin::EnvVar {
+                    name: name.to_string(),
+                },
+            )
+        })
+    };
 
     // API key priority: settings.json env → environment variable
-    let api_key = settings
-        .env
-        .get("ANTHROPIC_AUTH_TOKEN")
-        .filter(|s| !s.is_empty())
-        .or_else(|| settings.env.get("ANTHROPIC_API_KEY").filter(|s| !s.is_empty()))
-        .cloned()
-        .or_else(|| env::var("ANTHROPIC_API_KEY").ok().filter(|s| !s.is_empty()))
-        .unwrap_or_default();
+    let (api_key, api_key_origin) = settings_env("ANTHROPIC_AUTH_TOKEN")
+        .or_else(|| settings_env("ANTHROPIC_API_KEY"))
+        .or_else(|| process_env("ANTHROPIC_API_KEY"))
+        .unwrap_or((String::new(), ConfigOrigin::Unset));
 
-    // Base URL: settings.json env → environment variable → default
-    let base_url = settings
-        .env
-        .get("ANTHROPIC_BASE_URL")
-        .filter(|s| !s.is_empty())
-        .cloned()
-        .or_else(|| env::var("ANTHROPIC_BASE_URL").ok().filter(|s| !s.is_empty()))
-        .unwrap_or_else(|| "https://api.anthropic.com".to_string());
+    // Base URL: settings.json env → environment variable → viewer settings → default
+    let (base_url, base_url_origin) = settings_env("ANTHROPIC_BASE_URL")
+        .or_else(|| process_env("ANTHROPIC_BASE_URL"))
+        .unwrap_or_else(|| {
+            let url = settings::default_base_url();
+            let origin = if url == settings::ANTHROPIC_BASE_URL {
+                ConfigOrigin::Default
+            } else {
+                ConfigOrigin::ViewerSettings {
+                    field: "defaultBaseUrl".to_string(),
+                }
+            };
+            (url, origin)
+        });
 
-    let default_model = settings.model.unwrap_or_default();
+    let (default_model, default_model_origin) = match settings.model {
+        Some(model) if !model.is_empty() => (
+            model,
+            ConfigOrigin::SettingsField {
+                field: "model".to_string(),
+            },
+        ),
+        _ => (String::new(), ConfigOrigin::Unset),
+    };
 
-    Ok((api_key, base_url, default_model, config_path_str))
+    Ok(ResolvedConfig {
+        api_key,
+        api_key_origin,
+        base_url,
+        base_url_origin,
+        default_model,
+        default_model_origin,
+        config_path,
+    })
 }
 
 fn mask_key(key: &str) -> String {
@@ -106,3 +279,211 @@ fn read_json_file<T: serde::de::DeserializeOwned>(path: &PathBuf) -> Option<T> {
     let content = std::fs::read_to_string(path).ok()?;
     serde_json::from_str(&content).ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestHome;
+
+    const PROXY: &str = "https://proxy.internal.example";
+
+    fn set_default_base_url(url: &str) {
+        settings::save_settings(&settings::SettingsFile {
+            default_base_url: Some(url.to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+    }
+
+    fn write_claude_settings(home: &TestHome, content: &str) {
+        std::fs::create_dir_all(home.path().join(".claude")).unwrap();
+        std::fs::write(home.path().join(".claude/settings.json"), content).unwrap();
+    }
+
+    #[test]
+    fn anthropic_is_the_last_resort_base_url() {
+        let _home = TestHome::new();
+        std::env::set_var("ANTHROPIC_API_KEY", "sk-test");
+        assert_eq!(
+            get_credentials("claude"),
+            (
+                "sk-test".to_string(),
+                settings::ANTHROPIC_BASE_URL.to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn settings_fallback_applies_without_config_or_env() {
+        let _home = TestHome::new();
+        set_default_base_url(PROXY);
+
+        // Without a key the fallback is still the URL reported
+        assert_eq!(
+            get_credentials("claude"),
+            (String::new(), PROXY.to_string())
+        );
+        std::env::set_var("ANTHROPIC_API_KEY", "sk-test");
+        assert_eq!(get_credentials("claude").1, PROXY);
+    }
+
+    #[test]
+    fn env_and_cli_config_win_over_the_settings_fallback() {
+        let home = TestHome::new();
+        set_default_base_url(PROXY);
+        std::env::set_var("ANTHROPIC_API_KEY", "sk-test");
+
+        std::env::set_var("ANTHROPIC_BASE_URL", "https://env.example");
+        assert_eq!(get_credentials("claude").1, "https://env.example");
+
+        write_claude_settings(
+            &home,
+            r#"{"env": {"ANTHROPIC_BASE_URL": "https://cli.example"}}"#,
+        );
+        assert_eq!(get_credentials("claude").1, "https://cli.example");
+    }
+
+    fn env_var(name: &str) -> ConfigOrigin {
+        ConfigOrigin::EnvVar {
+            name: name.to_string(),
+        }
+    }
+
+    fn settings_env(key: &str) -> ConfigOrigin {
+        ConfigOrigin::SettingsEnv {
+            key: key.to_string(),
+        }
+    }
+
+    #[test]
+    fn explain_with_nothing_configured() {
+        let _home = TestHome::new();
+        let explained = explain_config("claude").unwrap();
+        assert_eq!(explained.api_key, ConfigOrigin::Unset);
+        assert_eq!(explained.base_url, ConfigOrigin::Default);
+        assert_eq!(explained.base_url_value, settings::ANTHROPIC_BASE_URL);
+        assert_eq!(explained.default_model, ConfigOrigin::Unset);
+    }
+
+    #[test]
+    fn explain_reports_process_env_and_viewer_settings() {
+        let _home = TestHome::new();
+        std::env::set_var("ANTHROPIC_API_KEY", "sk-env-secret");
+        set_default_base_url(PROXY);
+
+        let explained = explain_config("claude").unwrap();
+        assert_eq!(explained.api_key, env_var("ANTHROPIC_API_KEY"));
+        assert_eq!(
+            explained.base_url,
+            ConfigOrigin::ViewerSettings {
+                field: "defaultBaseUrl".to_string()
+            }
+        );
+
+        std::env::set_var("ANTHROPIC_BASE_URL", "https://env.example");
+        let explained = explain_config("claude").unwrap();
+        assert_eq!(explained.base_url, env_var("ANTHROPIC_BASE_URL"));
+        assert_eq!(explained.base_url_value, "https://env.example");
+    }
+
+    #[test]
+    fn explain_prefers_the_cli_settings_file() {
+        let home = TestHome::new();
+        std::env::set_var("ANTHROPIC_API_KEY", "sk-env-secret");
+        std::env::set_var("ANTHROPIC_BASE_URL", "https://env.example");
+        write_claude_settings(
+            &home,
+            r#"{"model": "opus", "env": {
+                "ANTHROPIC_API_KEY": "sk-file-secret",
+                "ANTHROPIC_BASE_URL": "https://cli.example"
+            }}"#,
+        );
+
+        let explained = explain_config("claude").unwrap();
+        assert_eq!(explained.api_key, settings_env("ANTHROPIC_API_KEY"));
+        assert_eq!(explained.base_url, settings_env("ANTHROPIC_BASE_URL"));
+        assert_eq!(
+            explained.default_model,
+            ConfigOrigin::SettingsField {
+                field: "model".to_string()
+            }
+        );
+        assert_eq!(explained.default_model_value, "opus");
+
+        // The auth token outranks an API key in the same file
+        write_claude_settings(
+            &home,
+            r#"{"env": {"ANTHROPIC_API_KEY": "sk-file-secret", "ANTHROPIC_AUTH_TOKEN": "tok-secret"}}"#,
+        );
+        let explained = explain_config("claude").unwrap();
+        assert_eq!(explained.api_key, settings_env("ANTHROPIC_AUTH_TOKEN"));
+        let json = serde_json::to_string(&explained).unwrap();
+        assert!(!json.contains("secret"), "{}", json);
+    }
+
+    fn writability(home: &TestHome) -> ConfigWritability {
+        let result = config_writable("claude").unwrap();
+        assert_eq!(
+            result.config_path,
+            home.path()
+                .join(".claude/settings.json")
+                .display()
+                .to_string()
+        );
+        result
+    }
+
+    #[test]
+    fn missing_config_is_writable_when_an_ancestor_accepts_files() {
+        let home = TestHome::new();
+        let result = writability(&home);
+        assert!(result.writable, "{:?}", result.reason);
+        assert_eq!(result.reason, None);
+
+        // The probe file is cleaned up and nothing else is created
+        let leftovers: Vec<_> = fs::read_dir(home.path()).unwrap().flatten().collect();
+        assert!(leftovers.is_empty(), "{:?}", leftovers);
+    }
+
+    #[test]
+    fn a_directory_in_place_of_the_config_is_not_writable() {
+        let home = TestHome::new();
+        fs::create_dir_all(home.path().join(".claude/settings.json")).unwrap();
+        let result = writability(&home);
+        assert!(!result.writable);
+        assert!(result.reason.unwrap().ends_with("is a directory"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_only_config_and_directory_are_reported() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let home = TestHome::new();
+        let dir = home.path().join(".claude");
+        fs::create_dir_all(&dir).unwrap();
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o555)).unwrap();
+        if OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(dir.join("probe"))
+            .is_ok()
+        {
+            eprintln!("skipping: permission bits are not enforced for this user");
+            return;
+        }
+
+        let result = writability(&home);
+        assert!(!result.writable);
+        assert!(result.reason.unwrap().starts_with("Cannot create files in"));
+
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o755)).unwrap();
+        write_claude_settings(&home, "{}");
+        let config = dir.join("settings.json");
+        fs::set_permissions(&config, fs::Permissions::from_mode(0o444)).unwrap();
+
+        let result = writability(&home);
+        assert!(!result.writable);
+        assert!(result.reason.unwrap().starts_with("Cannot write"));
+    }
+}
@@ -0,0 +1,278 @@
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::models::stats::ModelUsageEntry;
+use crate::parser::jsonl as claude_parser;
+use crate::provider::codex;
+use crate::stats;
+
+/// List prices in USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+    pub cache_write: f64,
+    pub cache_read: f64,
+}
+
+const fn price(input: f64, output: f64, cache_write: f64, cache_read: f64) -> ModelPrice {
+    ModelPrice {
+        input,
+        output,
+        cache_write,
+        cache_read,
+    }
+}
+
+/// Built-in price table keyed by model id prefix; the longest matching prefix
+/// wins, so dated ids (`claude-sonnet-4-20250514`) resolve to their family.
+/// Published list prices at the time of writing, without batch or regional
+/// discounts.
+const MODEL_PRICES: &[(&str, ModelPrice)] = &[
+    ("claude-opus-4-5", price(5.0, 25.0, 6.25, 0.5)),
+    ("claude-opus-4", price(15.0, 75.0, 18.75, 1.5)),
+    ("claude-sonnet-4", price(3.0, 15.0, 3.75, 0.3)),
+    ("claude-haiku-4-5", price(1.0, 5.0, 1.25, 0.1)),
+    ("claude-3-opus", price(15.0, 75.0, 18.75, 1.5)),
+    ("claude-3-7-sonnet", price(3.0, 15.0, 3.75, 0.3)),
+    ("claude-3-5-sonnet", price(3.0, 15.0, 3.75, 0.3)),
+    ("claude-3-5-haiku", price(0.8, 4.0, 1.0, 0.08)),
+    ("claude-3-haiku", price(0.25, 1.25, 0.3, 0.03)),
+    ("gpt-5", price(1.25, 10.0, 0.0, 0.125)),
+    ("gpt-5-mini", price(0.25, 2.0, 0.0, 0.025)),
+    ("gpt-5-nano", price(0.05, 0.4, 0.0, 0.005)),
+    ("gpt-4.1", price(2.0, 8.0, 0.0, 0.5)),
+    ("gpt-4.1-mini", price(0.4, 1.6, 0.0, 0.1)),
+    ("gpt-4o", price(2.5, 10.0, 0.0, 1.25)),
+    ("o3", price(2.0, 8.0, 0.0, 0.5)),
+    ("o3-mini", price(1.1, 4.4, 0.0, 0.55)),
+    ("o4-mini", price(1.1, 4.4, 0.0, 0.275)),
+    ("codex-mini", price(1.5, 6.0, 0.0, 0.375)),
+];
+
+/// Price of a model id, or None if it isn't in the built-in table
+pub fn model_price(model: &str) -> Option<ModelPrice> {
+    let model = model.trim().to_lowercase();
+    MODEL_PRICES
+        .iter()
+        .filter(|(prefix, _)| model.starts_with(prefix))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, price)| *price)
+}
+
+/// Token usage and cost of one model
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCost {
+    pub model: String,
+    #[serde(flatten)]
+    pub usage: ModelUsageEntry,
+    pub cost_usd: f64,
+    /// False when the model has no known price (its cost is counted as zero)
+    pub priced: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostEstimate {
+    /// Most expensive model first
+    pub models: Vec<ModelCost>,
+    /// Models that were used but have no known price
+    pub unpriced: Vec<String>,
+    pub total_usd: f64,
+}
+
+/// Cost of a usage breakdown at the given prices
+pub fn usage_cost(usage: &ModelUsageEntry, price: &ModelPrice) -> f64 {
+    let per_token = |tokens: u64, per_million: f64| tokens as f64 * per_million / 1_000_000.0;
+    per_token(usage.input_tokens, price.input)
+        + per_token(usage.output_tokens, price.output)
+        + per_token(usage.cache_creation_input_tokens, price.cache_write)
+        + per_token(usage.cache_read_input_tokens, price.cache_read)
+}
+
+/// Price a per-model usage map
+pub fn estimate_cost(usage_by_model: HashMap<String, ModelUsageEntry>) -> CostEstimate {
+    let mut estimate = CostEstimate::default();
+    for (model, usage) in usage_by_model {
+        let price = model_price(&model);
+        let cost_usd = price.map(|p| usage_cost(&usage, &p)).unwrap_or(0.0);
+        if price.is_none() {
+            estimate.unpriced.push(model.clone());
+        }
+        estimate.total_usd += cost_usd;
+        estimate.models.push(ModelCost {
+            model,
+            usage,
+            cost_usd,
+            priced: price.is_some(),
+        });
+    }
+    estimate.models.sort_by(|a, b| {
+        b.cost_usd
+            .total_cmp(&a.cost_usd)
+            .then_with(|| a.model.cmp(&b.model))
+    });
+    estimate.unpriced.sort();
+    estimate
+}
+
+fn session_usage_by_model(
+    file_path: &Path,
+    source: &str,
+) -> Result<HashMap<String, ModelUsageEntry>, String> {
+    match source {
+        "claude" => Ok(claude_parser::extract_token_usage_by_model(file_path)),
+        "codex" => Ok(codex::extract_token_usage_by_model(file_path)),
+        _ => Err(format!("Unknown source: {}", source)),
+    }
+}
+
+/// Estimated cost of one session, per model and in total
+pub fn session_cost(file_path: &str, source: &str) -> Result<CostEstimate, String> {
+    let path = Path::new(file_path);
+    if !path.is_file() {
+        return Err(format!("Session file not found: {}", file_path));
+    }
+    session_usage_by_model(path, source).map(estimate_cost)
+}
+
+/// Estimated cost of every session in a project (a cwd for Codex)
+pub fn project_cost(source: &str, project_id: &str) -> Result<CostEstimate, String> {
+    let sessions = stats::list_sessions(source, Some(project_id))?;
+
+    let per_session: Vec<HashMap<String, ModelUsageEntry>> = sessions
+        .par_iter()
+        .filter_map(|s| session_usage_by_model(Path::new(&s.file_path), source).ok())
+        .collect();
+
+    let mut totals: HashMap<String, ModelUsageEntry> = HashMap::new();
+    for (model, usage) in per_session.into_iter().flatten() {
+        let total = totals.entry(model).or_default();
+        total.input_tokens += usage.input_tokens;
+        total.output_tokens += usage.output_tokens;
+        total.cache_read_input_tokens += usage.cache_read_input_tokens;
+        total.cache_creation_input_tokens += usage.cache_creation_input_tokens;
+    }
+    Ok(estimate_cost(totals))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{claude_user, TestHome};
+
+    const PROJECT: &str = "-home-user-project";
+
+    /// An assistant record for API message `id` with explicit usage:
+    /// input, output, cache write, cache read
+    fn reply(id: &str, model: &str, usage: [u64; 4]) -> String {
+        serde_json::json!({
+            "type": "assistant",
+            "uuid": format!("uuid-{}", id),
+            "message": {
+                "id": id,
+                "role": "assistant",
+                "model": model,
+                "content": [{ "type": "text", "text": "ok" }],
+                "usage": {
+                    "input_tokens": usage[0],
+                    "output_tokens": usage[1],
+                    "cache_creation_input_tokens": usage[2],
+                    "cache_read_input_tokens": usage[3],
+                },
+            },
+        })
+        .to_string()
+    }
+
+    fn assert_usd(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    fn write_sessions(home: &TestHome) -> String {
+        let sonnet = reply(
+            "m1",
+            "claude-sonnet-4-6",
+            [1_000_000, 100_000, 1_000_000, 2_000_000],
+        );
+        let path = home.claude_session(
+            PROJECT,
+            "s1",
+            &[
+                claude_user("u1", "hi"),
+                sonnet.clone(),
+                // The CLI repeats a message's usage on each of its content blocks
+                sonnet,
+                reply("m2", "claude-opus-4-5-20251101", [200_000, 40_000, 0, 0]),
+                reply("m3", "claude-next", [500, 500, 0, 0]),
+            ],
+        );
+        home.claude_session(
+            PROJECT,
+            "s2",
+            &[reply("m4", "claude-sonnet-4-6", [1_000_000, 0, 0, 0])],
+        );
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn session_cost_prices_each_model_once_per_message() {
+        let home = TestHome::new();
+        let path = write_sessions(&home);
+
+        let estimate = session_cost(&path, "claude").unwrap();
+        let models: Vec<(&str, bool)> = estimate
+            .models
+            .iter()
+            .map(|m| (m.model.as_str(), m.priced))
+            .collect();
+        assert_eq!(
+            models,
+            [
+                ("claude-sonnet-4-6", true),
+                ("claude-opus-4-5-20251101", true),
+                ("claude-next", false),
+            ]
+        );
+        // 3.00 input + 1.50 output + 3.75 cache write + 0.60 cache read
+        assert_usd(estimate.models[0].cost_usd, 8.85);
+        assert_eq!(estimate.models[0].usage.input_tokens, 1_000_000);
+        // 1.00 input + 1.00 output
+        assert_usd(estimate.models[1].cost_usd, 2.0);
+        assert_usd(estimate.models[2].cost_usd, 0.0);
+        assert_eq!(estimate.unpriced, ["claude-next"]);
+        assert_usd(estimate.total_usd, 10.85);
+    }
+
+    #[test]
+    fn project_cost_sums_every_session() {
+        let home = TestHome::new();
+        write_sessions(&home);
+
+        let estimate = project_cost("claude", PROJECT).unwrap();
+        assert_usd(estimate.models[0].cost_usd, 11.85);
+        assert_usd(estimate.total_usd, 13.85);
+        assert_eq!(estimate.unpriced, ["claude-next"]);
+    }
+
+    #[test]
+    fn missing_sessions_and_unknown_sources_are_errors() {
+        let home = TestHome::new();
+        let path = write_sessions(&home);
+
+        assert!(session_cost("/no/such/session.jsonl", "claude").is_err());
+        assert_eq!(
+            session_cost(&path, "gemini").unwrap_err(),
+            "Unknown source: gemini"
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Environment variable that relocates the viewer's own files (default: home directory)
+pub const DATA_DIR_ENV: &str = "SESSION_VIEWER_DATA_DIR";
+
+/// Files and directories the viewer keeps in its data directory.
+/// Per-project metadata lives next to the sessions and is not included.
+pub const DATA_ENTRIES: &[&str] = &[
+    ".session-viewer-bookmarks.json",
+    ".session-viewer-project-aliases.json",
+    ".session-viewer-settings.json",
+    ".session-viewer",
+    ".session-viewer-backups",
+];
+
+/// Directory holding bookmarks, settings, project aliases and backups:
+/// `$SESSION_VIEWER_DATA_DIR` when set, otherwise the home directory
+pub fn data_dir() -> Result<PathBuf, String> {
+    match std::env::var_os(DATA_DIR_ENV) {
+        Some(dir) if !dir.is_empty() => Ok(PathBuf::from(dir)),
+        _ => dirs::home_dir().ok_or_else(|| "Cannot determine home directory".to_string()),
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    pub from: String,
+    pub to: String,
+    /// Entries copied (or already identical) and verified at the destination
+    pub copied: Vec<String>,
+    /// Known entries that did not exist in the old location
+    pub missing: Vec<String>,
+    /// The originals were deleted after every copy was verified
+    pub removed_source: bool,
+}
+
+/// Copy the viewer's data files from the current data directory into
+/// `new_path`, verify every copy byte for byte, and with `remove_source`
+/// delete the originals once all copies check out.
+///
+/// Nothing is copied if an entry already exists at the destination with
+/// different contents. The running app keeps using the old location until
+/// `SESSION_VIEWER_DATA_DIR` is set to `new_path` and it is restarted.
+pub fn migrate_data_dir(new_path: &str, remove_source: bool) -> Result<MigrationReport, String> {
+    let from = data_dir()?;
+    let to = PathBuf::from(new_path);
+    if fs::canonicalize(&from).ok() == fs::canonicalize(&to).ok() && to.exists() {
+        return Err(format!("{} is already the data directory", new_path));
+    }
+
+    let mut report = MigrationReport {
+        from: from.to_string_lossy().to_string(),
+        to: to.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+    let mut pending = Vec::new();
+    for name in DATA_ENTRIES {
+        let src = from.join(name);
+        if !src.exists() {
+            report.missing.push(name.to_string());
+            continue;
+        }
+        let dst = to.join(name);
+        if dst.exists() && !same_contents(&src, &dst) {
+            return Err(format!(
+                "{} already exists in {} with different contents",
+                name, new_path
+            ));
+        }
+        pending.push((name, src, dst));
+    }
+
+    fs::create_dir_all(&to).map_err(|e| format!("Failed to create {}: {}", new_path, e))?;
+    for (name, src, dst) in &pending {
+        copy_entry(src, dst).map_err(|e| format!("Failed to copy {}: {}", name, e))?;
+        if !same_contents(src, dst) {
+            return Err(format!("Copy of {} does not match the original", name));
+        }
+        report.copied.push(name.to_string());
+    }
+
+    if remove_source {
+        for (name, src, _) in &pending {
+            let removed = if src.is_dir() {
+                fs::remove_dir_all(src)
+            } else {
+                fs::remove_file(src)
+            };
+            removed.map_err(|e| format!("Failed to remove old {}: {}", name, e))?;
+        }
+        report.removed_source = true;
+    }
+
+    Ok(report)
+}
+
+fn copy_entry(src: &Path, dst: &Path) -> std::io::Result<()> {
+    if src.is_dir() {
+        fs::create_dir_all(dst)?;
+        for entry in fs::read_dir(src)?.flatten() {
+            copy_entry(&entry.path(), &dst.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(src, dst).map(|_| ())
+    }
+}
+
+/// Whether two files (or directory trees) hold the same bytes
+fn same_contents(a: &Path, b: &Path) -> bool {
+    if a.is_dir() {
+        let entries = match fs::read_dir(a) {
+            Ok(rd) => rd,
+            Err(_) => return false,
+        };
+        b.is_dir()
+            && entries
+                .flatten()
+                .all(|e| same_contents(&e.path(), &b.join(e.file_name())))
+    } else {
+        match (fs::read(a), fs::read(b)) {
+            (Ok(x), Ok(y)) => x == y,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{temp_dir, TestHome};
+
+    const BOOKMARKS: &str = ".session-viewer-bookmarks.json";
+    const RULES: &str = ".session-viewer/auto-tags.json";
+
+    /// Bookmarks, settings and a nested file in the current data directory
+    fn seed(home: &TestHome) {
+        let dir = home.path();
+        fs::write(dir.join(BOOKMARKS), r#"{"version":1,"bookmarks":[]}"#).unwrap();
+        fs::write(dir.join(".session-viewer-settings.json"), "{}").unwrap();
+        fs::create_dir_all(dir.join(".session-viewer")).unwrap();
+        fs::write(dir.join(RULES), r#"{"sql": "database"}"#).unwrap();
+    }
+
+    #[test]
+    fn migration_copies_and_verifies_everything() {
+        let home = TestHome::new();
+        seed(&home);
+        let dest = temp_dir();
+        let new_dir = dest.path().join("viewer");
+
+        let report = migrate_data_dir(new_dir.to_str().unwrap(), false).unwrap();
+        assert_eq!(
+            report.copied,
+            [
+                BOOKMARKS,
+                ".session-viewer-settings.json",
+                ".session-viewer"
+            ]
+        );
+        assert_eq!(
+            report.missing,
+            [
+                ".session-viewer-project-aliases.json",
+                ".session-viewer-backups"
+            ]
+        );
+        assert!(!report.removed_source);
+        for name in [BOOKMARKS, RULES] {
+            assert_eq!(
+                fs::read(home.path().join(name)).unwrap(),
+                fs::read(new_dir.join(name)).unwrap()
+            );
+        }
+
+        std::env::set_var(DATA_DIR_ENV, &new_dir);
+        assert_eq!(data_dir().unwrap(), new_dir);
+    }
+
+    #[test]
+    fn sources_are_removed_only_when_asked() {
+        let home = TestHome::new();
+        seed(&home);
+        let dest = temp_dir();
+
+        let report = migrate_data_dir(dest.path().to_str().unwrap(), true).unwrap();
+        assert!(report.removed_source);
+        assert!(!home.path().join(BOOKMARKS).exists());
+        assert!(!home.path().join(".session-viewer").exists());
+        assert!(dest.path().join(RULES).is_file());
+    }
+
+    #[test]
+    fn conflicting_destination_stops_before_anything_is_copied() {
+        let home = TestHome::new();
+        seed(&home);
+        let dest = temp_dir();
+        fs::write(dest.path().join(BOOKMARKS), "different").unwrap();
+
+        let err = migrate_data_dir(dest.path().to_str().unwrap(), true).unwrap_err();
+        assert!(err.contains("different contents"), "{}", err);
+        assert!(home.path().join(BOOKMARKS).is_file());
+        assert!(!dest.path().join(".session-viewer").exists());
+
+        let err = migrate_data_dir(home.path().to_str().unwrap(), false).unwrap_err();
+        assert!(err.ends_with("is already the data directory"), "{}", err);
+    }
+}
@@ -0,0 +1,58 @@
+use crate::provider::{claude, codex};
+
+/// Compute the total byte size of session files per project, sorted descending.
+/// Codex sessions are not stored per project, so a single aggregate entry is returned.
+pub fn project_disk_usage(source: &str) -> Result<Vec<(String, u64)>, String> {
+    match source {
+        "claude" => claude::project_disk_usage(),
+        "codex" => Ok(vec![(String::new(), codex::disk_usage())]),
+        _ => Err(format!("Unknown source: {}", source)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestHome;
+    use std::fs;
+
+    #[test]
+    fn sums_session_files_per_project_largest_first() {
+        let home = TestHome::new();
+        let small = home.claude_project("-home-user-small");
+        fs::write(small.join("a.jsonl"), [b'x'; 100]).unwrap();
+        let big = home.claude_project("-home-user-big");
+        fs::write(big.join("b.jsonl"), [b'x'; 300]).unwrap();
+        fs::write(big.join("c.jsonl"), [b'x'; 200]).unwrap();
+        // Bookkeeping files are not sessions and don't count
+        fs::write(big.join(".session-viewer-meta.json"), [b'x'; 1000]).unwrap();
+        fs::write(big.join("notes.txt"), [b'x'; 1000]).unwrap();
+
+        let usage = project_disk_usage("claude").unwrap();
+        assert_eq!(
+            usage,
+            vec![
+                ("-home-user-big".to_string(), 500),
+                ("-home-user-small".to_string(), 100),
+            ]
+        );
+    }
+
+    #[test]
+    fn codex_is_one_aggregate_entry() {
+        let home = TestHome::new();
+        let a = home.codex_session("a", &["x".repeat(99)]);
+        let b = home.codex_session("b", &["y".repeat(199)]);
+        let expected = fs::metadata(a).unwrap().len() + fs::metadata(b).unwrap().len();
+
+        assert_eq!(
+            project_disk_usage("codex").unwrap(),
+            vec![(String::new(), expected)]
+        );
+    }
+
+    #[test]
+    fn unknown_source_is_an_error() {
+        assert!(project_disk_usage("gemini").is_err());
+    }
+}
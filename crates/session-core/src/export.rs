@@ -0,0 +1,288 @@
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::bookmarks;
+use crate::metadata;
+use crate::models::message::{DisplayContentBlock, Role};
+use crate::provider::{claude, codex};
+
+/// A fenced code block found in a session message
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeBlock {
+    /// First word of the fence's info string ("" when none was given)
+    pub language: String,
+    pub code: String,
+    /// Index of the message in the parsed session
+    pub message_index: usize,
+    pub role: String,
+}
+
+/// Write a project's sessions to `dest_path` as NDJSON: one `SessionIndexEntry`
+/// summary (with alias/tags merged in) per line. Returns the number of lines.
+///
+/// Entries are serialized one at a time into a `.tmp` sibling that is renamed
+/// into place, so a failed export never leaves a partial file at `dest_path`.
+pub fn export_project_ndjson(
+    source: &str,
+    project_id: &str,
+    dest_path: &str,
+) -> Result<usize, String> {
+    let (mut sessions, meta_project_id) = match source {
+        "claude" => (claude::get_sessions(project_id, None)?, project_id),
+        // Codex metadata is a single file, keyed under the empty project id
+        "codex" => (codex::get_sessions(project_id)?, ""),
+        _ => return Err(format!("Unknown source: {}", source)),
+    };
+    metadata::merge_session_meta(source, meta_project_id, &mut sessions);
+
+    let dest = Path::new(dest_path);
+    if let Some(parent) = dest.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create export directory: {}", e))?;
+    }
+
+    let tmp_path = dest.with_extension("ndjson.tmp");
+    let result = (|| {
+        let file =
+            File::create(&tmp_path).map_err(|e| format!("Failed to create export: {}", e))?;
+        let mut writer = BufWriter::new(file);
+        for session in &sessions {
+            serde_json::to_writer(&mut writer, session)
+                .map_err(|e| format!("Failed to write session: {}", e))?;
+            writer
+                .write_all(b"\n")
+                .map_err(|e| format!("Failed to write session: {}", e))?;
+        }
+        writer
+            .flush()
+            .map_err(|e| format!("Failed to write export: {}", e))
+    })();
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+
+    fs::rename(&tmp_path, dest).map_err(|e| format!("Failed to rename export: {}", e))?;
+    Ok(sessions.len())
+}
+
+/// Every fenced code block (``` or ~~~) in the text of user and assistant
+/// messages, in session order. Tool input/output is not searched.
+pub fn extract_code_blocks(file_path: &str, source: &str) -> Result<Vec<CodeBlock>, String> {
+    let path = Path::new(file_path);
+    if !path.exists() {
+        return Err(format!("File not found: {}", file_path));
+    }
+
+    let messages = bookmarks::load_messages(source, path)?;
+    let mut blocks = Vec::new();
+    for (index, msg) in messages.iter().enumerate() {
+        if msg.role != Role::User && msg.role != Role::Assistant {
+            continue;
+        }
+        for block in &msg.content {
+            if let DisplayContentBlock::Text { text } = block {
+                for (language, code) in fenced_blocks(text) {
+                    blocks.push(CodeBlock {
+                        language,
+                        code,
+                        message_index: index,
+                        role: msg.role.as_str().to_string(),
+                    });
+                }
+            }
+        }
+    }
+    Ok(blocks)
+}
+
+/// Write a session's code blocks into `dest_dir` as `block-001.<ext>`, ... with
+/// the extension taken from the language. Returns the written paths.
+pub fn write_code_blocks(
+    file_path: &str,
+    source: &str,
+    dest_dir: &str,
+) -> Result<Vec<String>, String> {
+    let blocks = extract_code_blocks(file_path, source)?;
+    let dir = Path::new(dest_dir);
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+
+    let mut written = Vec::with_capacity(blocks.len());
+    for (i, block) in blocks.iter().enumerate() {
+        let path = dir.join(format!(
+            "block-{:03}.{}",
+            i + 1,
+            extension_for(&block.language)
+        ));
+        fs::write(&path, &block.code)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        written.push(path.to_string_lossy().to_string());
+    }
+    Ok(written)
+}
+
+/// (language, code) of each fenced block in Markdown text. A fence closes on
+/// the same character repeated at least as many times; an unclosed fence runs
+/// to the end of the text.
+fn fenced_blocks(text: &str) -> Vec<(String, String)> {
+    let mut blocks = Vec::new();
+    let mut open: Option<(char, usize, String, Vec<&str>)> = None;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        let fence = trimmed
+            .chars()
+            .next()
+            .filter(|c| indent <= 3 && (*c == '`' || *c == '~'))
+            .map(|c| (c, trimmed.chars().take_while(|x| *x == c).count()))
+            .filter(|(_, n)| *n >= 3);
+
+        if let Some((ch, len, language, lines)) = &mut open {
+            let closes =
+                fence.is_some_and(|(c, n)| c == *ch && n >= *len && trimmed[n..].trim().is_empty());
+            if !closes {
+                lines.push(line);
+                continue;
+            }
+            blocks.push((std::mem::take(language), lines.join("\n")));
+            open = None;
+        } else if let Some((c, n)) = fence {
+            let language = trimmed[n..].split_whitespace().next().unwrap_or("");
+            open = Some((c, n, language.to_string(), Vec::new()));
+        }
+    }
+    if let Some((_, _, language, lines)) = open {
+        blocks.push((language, lines.join("\n")));
+    }
+    blocks
+}
+
+/// File extension for a fence language; unknown languages use their own name
+fn extension_for(language: &str) -> String {
+    let ext = match language.to_lowercase().as_str() {
+        "" | "text" | "plaintext" => "txt",
+        "rust" => "rs",
+        "python" | "py" => "py",
+        "javascript" | "js" => "js",
+        "typescript" | "ts" => "ts",
+        "tsx" => "tsx",
+        "jsx" => "jsx",
+        "bash" | "sh" | "shell" | "zsh" => "sh",
+        "powershell" | "ps1" => "ps1",
+        "json" => "json",
+        "yaml" | "yml" => "yml",
+        "toml" => "toml",
+        "html" => "html",
+        "css" => "css",
+        "markdown" | "md" => "md",
+        "go" | "golang" => "go",
+        "java" => "java",
+        "kotlin" | "kt" => "kt",
+        "c" => "c",
+        "cpp" | "c++" => "cpp",
+        "csharp" | "cs" | "c#" => "cs",
+        "ruby" | "rb" => "rb",
+        "sql" => "sql",
+        "diff" | "patch" => "diff",
+        other => {
+            let cleaned: String = other
+                .chars()
+                .filter(|c| c.is_ascii_alphanumeric())
+                .collect();
+            return if cleaned.is_empty() {
+                "txt".to_string()
+            } else {
+                cleaned
+            };
+        }
+    };
+    ext.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::session::SessionIndexEntry;
+    use crate::test_support::{claude_assistant, claude_user, TestHome};
+
+    const PROJECT: &str = "-home-user-project";
+
+    #[test]
+    fn ndjson_export_writes_one_line_per_session() {
+        let home = TestHome::new();
+        for id in ["s1", "s2", "s3"] {
+            home.claude_session(
+                PROJECT,
+                id,
+                &[
+                    claude_user(&format!("{}-u", id), "question"),
+                    claude_assistant(&format!("{}-a", id), "answer", "claude-sonnet-4-6"),
+                ],
+            );
+        }
+        metadata::update_session_meta("claude", PROJECT, "s2", Some("second".into()), vec![], None)
+            .unwrap();
+        let dest = home.path().join("out/project.ndjson");
+
+        let written = export_project_ndjson("claude", PROJECT, dest.to_str().unwrap()).unwrap();
+
+        let content = fs::read_to_string(&dest).unwrap();
+        let mut sessions: Vec<SessionIndexEntry> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(written, 3);
+        assert_eq!(sessions.len(), written);
+        sessions.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+        assert_eq!(sessions[1].alias.as_deref(), Some("second"));
+        assert!(!dest.with_extension("ndjson.tmp").exists());
+    }
+
+    #[test]
+    fn ndjson_export_of_an_unknown_source_writes_nothing() {
+        let home = TestHome::new();
+        let dest = home.path().join("project.ndjson");
+
+        assert!(export_project_ndjson("gemini", PROJECT, dest.to_str().unwrap()).is_err());
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn code_blocks_keep_their_fence_language() {
+        let home = TestHome::new();
+        let question = "Port this:\n```python\nprint('hi')\n```\nand keep the shell step:\n~~~bash\necho hi\n~~~";
+        let answer = "Here it is:\n```rust title=main.rs\nfn main() {\n    println!(\"hi\");\n}\n```\nOutput:\n```\nhi\n```\nConfig:\n````toml\n[package]\n```\nname = \"hi\"\n````";
+        let path = home.claude_session(
+            PROJECT,
+            "s1",
+            &[
+                claude_user("u1", question),
+                claude_assistant("a1", answer, "claude-sonnet-4-6"),
+            ],
+        );
+
+        let blocks = extract_code_blocks(path.to_str().unwrap(), "claude").unwrap();
+        let summary: Vec<(&str, &str, usize)> = blocks
+            .iter()
+            .map(|b| (b.language.as_str(), b.role.as_str(), b.message_index))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("python", "user", 0),
+                ("bash", "user", 0),
+                ("rust", "assistant", 1),
+                ("", "assistant", 1),
+                ("toml", "assistant", 1),
+            ]
+        );
+        assert_eq!(blocks[0].code, "print('hi')");
+        assert_eq!(blocks[2].code, "fn main() {\n    println!(\"hi\");\n}");
+        // A shorter fence inside a longer one is part of the code
+        assert_eq!(blocks[4].code, "[package]\n```\nname = \"hi\"");
+    }
+}
@@ -0,0 +1,103 @@
+/// Score of an exact (substring) match; fuzzy matches score below it
+pub const EXACT_SCORE: u32 = 100;
+
+/// Points lost per typo in a fuzzy match
+const TYPO_PENALTY: u32 = 10;
+
+/// Typos tolerated in a query word of `len` characters
+fn allowed_typos(len: usize) -> usize {
+    match len {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Fewest edits turning `pattern` into some substring of `text`
+/// (approximate substring matching: a match may start anywhere for free).
+fn substring_distance(pattern: &[char], text: &[char]) -> usize {
+    let m = pattern.len();
+    let mut prev: Vec<usize> = (0..=m).collect();
+    let mut cur: Vec<usize> = vec![0; m + 1];
+    let mut best = m;
+    for &tc in text {
+        for i in 1..=m {
+            let substitute = prev[i - 1] + usize::from(pattern[i - 1] != tc);
+            cur[i] = substitute.min(prev[i] + 1).min(cur[i - 1] + 1);
+        }
+        best = best.min(cur[m]);
+        if best == 0 {
+            break;
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    best
+}
+
+/// Fuzzy score of lowercase `text` against a lowercase query, or `None` if it
+/// doesn't match.
+///
+/// Every query word must appear in the text, in any order, within a few typos
+/// (none for words under 4 characters). An exact substring match of the whole
+/// query scores `EXACT_SCORE`; otherwise each typo costs `TYPO_PENALTY`.
+pub fn score(query_lower: &str, text_lower: &str) -> Option<u32> {
+    if query_lower.trim().is_empty() {
+        return None;
+    }
+    if text_lower.contains(query_lower) {
+        return Some(EXACT_SCORE);
+    }
+
+    let text_chars: Vec<char> = text_lower.chars().collect();
+    let mut typos = 0;
+    for word in query_lower.split_whitespace() {
+        if text_lower.contains(word) {
+            continue;
+        }
+        let word_chars: Vec<char> = word.chars().collect();
+        let distance = substring_distance(&word_chars, &text_chars);
+        if distance > allowed_typos(word_chars.len()) {
+            return None;
+        }
+        typos += distance as u32;
+    }
+
+    // Reordered words still rank below an exact phrase match
+    let score = (EXACT_SCORE - 1).saturating_sub(typos * TYPO_PENALTY);
+    Some(score.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn substring_is_an_exact_match() {
+        assert_eq!(score("parser", "refactor the parser"), Some(EXACT_SCORE));
+    }
+
+    #[test]
+    fn one_character_typo_still_matches() {
+        // A dropped, an extra and a wrong character
+        for typo in ["parsr", "parsere", "psrser"] {
+            let s = score(typo, "refactor the parser");
+            assert!(s.is_some_and(|s| s < EXACT_SCORE), "{}: {:?}", typo, s);
+        }
+    }
+
+    #[test]
+    fn reordered_words_match_below_an_exact_phrase() {
+        let reordered = score("parser refactor", "refactor the parser").unwrap();
+        assert!(reordered < EXACT_SCORE);
+        let with_typo = score("parser refactr", "refactor the parser").unwrap();
+        assert!(with_typo < reordered);
+    }
+
+    #[test]
+    fn short_words_and_distant_words_do_not_match() {
+        // Words under four characters must match exactly
+        assert_eq!(score("cat", "the car"), None);
+        assert_eq!(score("parser", "release notes"), None);
+        assert_eq!(score("  ", "anything"), None);
+    }
+}
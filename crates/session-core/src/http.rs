@@ -0,0 +1,221 @@
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::settings;
+
+/// Pooled clients keyed by (user agent, connect timeout)
+fn clients() -> &'static Mutex<HashMap<(String, Duration), reqwest::Client>> {
+    static CLIENTS: OnceLock<Mutex<HashMap<(String, Duration), reqwest::Client>>> = OnceLock::new();
+    CLIENTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Shared HTTP client with the configured user agent and diagnostic connect
+/// timeout. Reusing it keeps connections and TLS sessions pooled across calls;
+/// a changed setting gets a fresh client on the next call. There is no client
+/// level total timeout: set one per request with `RequestBuilder::timeout`.
+/// The system proxy is picked up by reqwest as usual.
+pub fn client() -> Result<reqwest::Client, String> {
+    client_with_connect_timeout(settings::diagnostic_timeouts().0)
+}
+
+/// Same as `client`, with a specific connect timeout (e.g. the chat's longer one)
+pub fn client_with_connect_timeout(connect_timeout: Duration) -> Result<reqwest::Client, String> {
+    let key = (settings::user_agent(), connect_timeout);
+    if let Some(client) = clients().lock().get(&key) {
+        return Ok(client.clone());
+    }
+
+    let client = reqwest::Client::builder()
+        .user_agent(key.0.clone())
+        .connect_timeout(connect_timeout)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    clients().lock().insert(key, client.clone());
+    Ok(client)
+}
+
+/// Headers `customHeaders` may not set, so a settings file can't swap credentials
+const PROTECTED_HEADERS: &[&str] = &["authorization", "proxy-authorization", "x-api-key"];
+
+/// Check one configured custom header: a valid HTTP name and value that isn't
+/// an auth header.
+pub fn check_custom_header(name: &str, value: &str) -> Result<(), String> {
+    let header = reqwest::header::HeaderName::from_bytes(name.trim().as_bytes())
+        .map_err(|_| format!("{}: not a valid header name", name))?;
+    if PROTECTED_HEADERS.contains(&header.as_str()) {
+        return Err(format!("{}: auth headers can't be overridden", name));
+    }
+    reqwest::header::HeaderValue::from_str(value)
+        .map_err(|_| format!("{}: not a valid header value", name))?;
+    Ok(())
+}
+
+/// Apply the `customHeaders` setting to an API request. Call after the
+/// built-in headers: a custom header replaces a built-in one of the same name.
+/// Invalid and auth headers are skipped (`validate_settings` reports them).
+pub fn with_custom_headers(request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    let mut headers = reqwest::header::HeaderMap::new();
+    for (name, value) in settings::custom_headers() {
+        if check_custom_header(&name, &value).is_err() {
+            continue;
+        }
+        if let (Ok(name), Ok(value)) = (
+            reqwest::header::HeaderName::from_bytes(name.trim().as_bytes()),
+            reqwest::header::HeaderValue::from_str(&value),
+        ) {
+            headers.insert(name, value);
+        }
+    }
+    if headers.is_empty() {
+        request
+    } else {
+        request.headers(headers)
+    }
+}
+
+/// The provider's id for a request (Anthropic's `request-id`, or the common
+/// `x-request-id`), for quoting in support tickets
+pub fn request_id(resp: &reqwest::Response) -> Option<String> {
+    ["request-id", "x-request-id"]
+        .iter()
+        .find_map(|name| resp.headers().get(*name))
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+/// ` (request-id: ...)` suffix for an API error message, or "" without an id
+pub fn request_id_suffix(request_id: Option<&str>) -> String {
+    request_id
+        .map(|id| format!(" (request-id: {})", id))
+        .unwrap_or_default()
+}
+
+/// Read a response body as text, keeping at most `settings::max_response_bytes()`.
+///
+/// Used for error bodies, which a misbehaving proxy may stream megabytes of
+/// HTML into. Anything past the cap is dropped and replaced with a marker.
+pub async fn read_text_capped(mut resp: reqwest::Response) -> String {
+    let cap = settings::max_response_bytes();
+    let mut buf: Vec<u8> = Vec::new();
+    let mut truncated = false;
+
+    while let Ok(Some(chunk)) = resp.chunk().await {
+        let room = cap - buf.len();
+        if chunk.len() > room {
+            buf.extend_from_slice(&chunk[..room]);
+            truncated = true;
+            break;
+        }
+        buf.extend_from_slice(&chunk);
+    }
+
+    let mut text = String::from_utf8_lossy(&buf).into_owned();
+    if truncated {
+        text.push_str(&format!("\n[truncated: response exceeded {} bytes]", cap));
+    }
+    text
+}
+
+/// Format epoch seconds as an HTTP date (`Sun, 06 Nov 1994 08:49:37 GMT`)
+pub fn format_http_date(secs: i64) -> String {
+    chrono::DateTime::from_timestamp(secs, 0)
+        .unwrap_or_default()
+        .format("%a, %d %b %Y %H:%M:%S GMT")
+        .to_string()
+}
+
+/// Parse an HTTP date (IMF-fixdate) into epoch seconds
+pub fn parse_http_date(s: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc2822(s.trim())
+        .ok()
+        .map(|dt| dt.timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockResponse, MockServer, TestHome};
+
+    async fn sent_user_agent() -> Option<String> {
+        let server = MockServer::start(vec![MockResponse::json(200, "{}", &[])]);
+        client().unwrap().get(&server.url).send().await.unwrap();
+        let requests = server.requests();
+        requests[0].header("user-agent").map(str::to_string)
+    }
+
+    #[tokio::test]
+    async fn sends_the_default_user_agent() {
+        let _home = TestHome::new();
+        assert_eq!(
+            sent_user_agent().await.as_deref(),
+            Some(settings::DEFAULT_USER_AGENT)
+        );
+    }
+
+    #[tokio::test]
+    async fn sends_the_configured_user_agent() {
+        let _home = TestHome::new();
+        settings::save_settings(&settings::SettingsFile {
+            user_agent: Some("my-proxy-client/2.0".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            sent_user_agent().await.as_deref(),
+            Some("my-proxy-client/2.0")
+        );
+    }
+
+    #[test]
+    fn clients_are_reused_per_setting() {
+        let _home = TestHome::new();
+        clients().lock().clear();
+
+        client().unwrap();
+        client().unwrap();
+        assert_eq!(clients().lock().len(), 1);
+
+        client_with_connect_timeout(Duration::from_secs(90)).unwrap();
+        client_with_connect_timeout(Duration::from_secs(90)).unwrap();
+        assert_eq!(clients().lock().len(), 2);
+
+        settings::save_settings(&settings::SettingsFile {
+            user_agent: Some("my-proxy-client/2.0".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        client().unwrap();
+        assert_eq!(clients().lock().len(), 3);
+    }
+
+    async fn error_body(body: &str) -> String {
+        let server = MockServer::start(vec![MockResponse::json(502, body, &[])]);
+        let resp = client().unwrap().get(&server.url).send().await.unwrap();
+        read_text_capped(resp).await
+    }
+
+    #[tokio::test]
+    async fn oversized_error_body_is_truncated_with_a_marker() {
+        let _home = TestHome::new();
+        settings::save_settings(&settings::SettingsFile {
+            max_response_bytes: Some(1024),
+            ..Default::default()
+        })
+        .unwrap();
+        let body = "<html>".repeat(10_000);
+
+        let text = error_body(&body).await;
+        let (kept, marker) = text.split_at(1024);
+        assert_eq!(kept, &body[..1024]);
+        assert_eq!(marker, "\n[truncated: response exceeded 1024 bytes]");
+    }
+
+    #[tokio::test]
+    async fn error_body_under_the_cap_is_kept_whole() {
+        let _home = TestHome::new();
+        let body = r#"{"error": {"type": "overloaded_error"}}"#;
+        assert_eq!(error_body(body).await, body);
+    }
+}
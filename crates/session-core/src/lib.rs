@@ -1,12 +1,32 @@
+pub mod auto_tag;
+pub mod backup;
 pub mod bookmarks;
 pub mod cli;
 pub mod cli_config;
+pub mod cost;
+pub mod data_dir;
+pub mod disk_usage;
+pub mod export;
+pub mod fuzzy;
+pub mod http;
+pub mod live_sessions;
 pub mod metadata;
 pub mod model_list;
 pub mod models;
+pub mod parallel;
 pub mod parser;
+pub mod project_alias;
+pub mod project_info;
 pub mod provider;
 pub mod quick_chat;
 pub mod search;
+pub mod session_cache;
+pub mod session_ops;
+pub mod session_query;
+pub mod settings;
 pub mod state;
 pub mod stats;
+pub mod summary;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
+pub mod trash;
@@ -0,0 +1,89 @@
+use std::ffi::OsStr;
+use std::path::Path;
+use sysinfo::{Process, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+use crate::parser::path_encoder::encode_project_id;
+use crate::provider::{claude, codex};
+
+/// Session ids of `source` ("claude" or "codex") that a running CLI process
+/// appears to be working on.
+///
+/// A session id passed on the command line (`claude --resume <id>` /
+/// `--session-id <id>`, `codex resume <id>`) is used as is. Otherwise the
+/// newest session of the process's working directory is assumed. Returns an
+/// empty list for an unknown source, or where process inspection is not
+/// permitted.
+pub fn detect_running_cli_sessions(source: &str) -> Vec<String> {
+    if !matches!(source, "claude" | "codex") {
+        return Vec::new();
+    }
+
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing()
+            .with_cmd(UpdateKind::Always)
+            .with_cwd(UpdateKind::Always),
+    );
+
+    let mut ids: Vec<String> = sys
+        .processes()
+        .values()
+        .filter(|p| p.thread_kind().is_none() && is_cli_process(p, source))
+        .filter_map(|p| {
+            let args: Vec<&str> = p.cmd().iter().filter_map(|a| a.to_str()).collect();
+            session_id_arg(&args, source).or_else(|| newest_session_in(p.cwd()?, source))
+        })
+        .collect();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// Whether the process is the CLI binary itself, or a script launched as
+/// `node …/claude` / `node …/codex`
+fn is_cli_process(process: &Process, source: &str) -> bool {
+    let is_binary = |name: &OsStr| Path::new(name).file_stem() == Some(OsStr::new(source));
+    is_binary(process.name())
+        || process
+            .cmd()
+            .iter()
+            .take(2)
+            .any(|a| is_binary(a.as_os_str()))
+}
+
+/// Session id given on the command line, if any
+fn session_id_arg(args: &[&str], source: &str) -> Option<String> {
+    let is_id = |v: &str| uuid::Uuid::parse_str(v).is_ok();
+    let value = match source {
+        "claude" => args.iter().enumerate().find_map(|(i, arg)| {
+            for flag in ["--resume", "-r", "--session-id"] {
+                if *arg == flag {
+                    return args.get(i + 1).copied();
+                }
+                if let Some(v) = arg.strip_prefix(flag).and_then(|r| r.strip_prefix('=')) {
+                    return Some(v);
+                }
+            }
+            None
+        }),
+        "codex" => args
+            .iter()
+            .position(|a| *a == "resume")
+            .and_then(|i| args.get(i + 1).copied()),
+        _ => None,
+    }?;
+    is_id(value).then(|| value.to_string())
+}
+
+/// Most recently modified session of the project rooted at `cwd`
+fn newest_session_in(cwd: &Path, source: &str) -> Option<String> {
+    let cwd = cwd.to_string_lossy();
+    let sessions = match source {
+        "claude" => claude::get_sessions(&encode_project_id(&cwd), None).ok()?,
+        "codex" => codex::get_sessions(&cwd).ok()?,
+        _ => return None,
+    };
+    sessions.into_iter().next().map(|s| s.session_id)
+}
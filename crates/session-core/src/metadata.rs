@@ -1,18 +1,77 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use crate::fuzzy;
+use crate::models::session::SessionIndexEntry;
+use crate::parallel;
+use crate::parser::format::is_session_file;
+use crate::parser::jsonl as claude_parser;
 use crate::parser::path_encoder::get_projects_dir;
-use crate::provider::codex;
+use crate::provider::{claude, codex};
+use crate::settings;
 
-/// Per-session metadata (alias + tags)
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// Per-session metadata (alias + tags + archived flag + pinned messages + note + rating)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct SessionMeta {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub alias: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
+    /// Soft-hidden from session lists (file stays on disk)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
+    /// Message uuids the user pinned within this session
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pinned_message_ids: Vec<String>,
+    /// Cached generated summary of the session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// User rating of the conversation, 1–5
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+}
+
+impl SessionMeta {
+    fn is_empty(&self) -> bool {
+        self.alias.is_none()
+            && self.tags.is_empty()
+            && !self.archived
+            && self.pinned_message_ids.is_empty()
+            && self.note.is_none()
+            && self.rating.is_none()
+    }
+}
+
+/// Outcome of `reconcile_metadata`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileReport {
+    /// Metadata entries whose session file exists
+    pub matched: usize,
+    /// Metadata keys with no session file (after any re-keying)
+    pub orphaned: Vec<String>,
+    /// Session files with no metadata entry, as (session_id, file_path)
+    pub unmatched_files: Vec<(String, String)>,
+    /// Entries moved from an old key to the current session id, as (old, new)
+    pub rekeyed: Vec<(String, String)>,
+}
+
+/// How `import_metadata` resolves a session present in both the current and
+/// the imported metadata. Sessions only present in the import are always added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictPolicy {
+    /// The current entry is left untouched: alias and tags are both kept
+    #[default]
+    KeepExisting,
+    /// Imported alias, tags, note and archived state replace the current ones;
+    /// an imported entry with no alias (or no tags) keeps the current value
+    PreferImported,
+    /// Tags and pins are unioned; the current alias and note win, falling back
+    /// to the imported ones when unset
+    UnionTags,
 }
 
 /// The metadata file structure
@@ -31,11 +90,19 @@ impl Default for MetadataFile {
     }
 }
 
-/// Resolve the metadata file path for a given source and project
-fn metadata_path(source: &str, project_id: &str) -> Option<PathBuf> {
+/// Resolve the metadata file path for a given source and project.
+/// `projects_dir` overrides the Claude projects root (None = `~/.claude/projects`).
+pub(crate) fn metadata_path(
+    source: &str,
+    project_id: &str,
+    projects_dir: Option<&Path>,
+) -> Option<PathBuf> {
     match source {
         "claude" => {
-            let projects_dir = get_projects_dir()?;
+            let projects_dir = match projects_dir {
+                Some(dir) => dir.to_path_buf(),
+                None => get_projects_dir()?,
+            };
             Some(projects_dir.join(project_id).join(".session-viewer-meta.json"))
         }
         "codex" => {
@@ -48,7 +115,16 @@ fn metadata_path(source: &str, project_id: &str) -> Option<PathBuf> {
 
 /// Load metadata file; returns default if not found
 pub fn load_metadata(source: &str, project_id: &str) -> MetadataFile {
-    let path = match metadata_path(source, project_id) {
+    load_metadata_in(source, project_id, None)
+}
+
+/// Load metadata from a custom Claude projects root; returns default if not found
+pub fn load_metadata_in(
+    source: &str,
+    project_id: &str,
+    projects_dir: Option<&Path>,
+) -> MetadataFile {
+    let path = match metadata_path(source, project_id, projects_dir) {
         Some(p) => p,
         None => return MetadataFile::default(),
     };
@@ -65,7 +141,7 @@ pub fn load_metadata(source: &str, project_id: &str) -> MetadataFile {
 
 /// Save metadata file (atomic: write tmp + rename)
 pub fn save_metadata(source: &str, project_id: &str, meta: &MetadataFile) -> Result<(), String> {
-    let path = metadata_path(source, project_id)
+    let path = metadata_path(source, project_id, None)
         .ok_or_else(|| "Cannot resolve metadata path".to_string())?;
 
     if let Some(parent) = path.parent() {
@@ -83,22 +159,266 @@ pub fn save_metadata(source: &str, project_id: &str, meta: &MetadataFile) -> Res
     Ok(())
 }
 
-/// Update metadata for a single session
+/// Merge stored alias/tags/archived/pinned/rating state into session entries.
+/// Returns the loaded metadata for callers that filter on it.
+pub fn merge_session_meta(
+    source: &str,
+    project_id: &str,
+    sessions: &mut [SessionIndexEntry],
+) -> MetadataFile {
+    merge_session_meta_in(source, project_id, None, sessions)
+}
+
+/// `merge_session_meta` with the metadata read from a custom Claude projects root
+pub fn merge_session_meta_in(
+    source: &str,
+    project_id: &str,
+    projects_dir: Option<&Path>,
+    sessions: &mut [SessionIndexEntry],
+) -> MetadataFile {
+    let meta = load_metadata_in(source, project_id, projects_dir);
+    for session in sessions.iter_mut() {
+        if let Some(sm) = meta.sessions.get(&session.session_id) {
+            session.alias = sm.alias.clone();
+            if !sm.tags.is_empty() {
+                session.tags = Some(sm.tags.clone());
+            }
+            session.archived = sm.archived;
+            session.pinned_message_ids = sm.pinned_message_ids.clone();
+            session.rating = sm.rating;
+        }
+    }
+    meta
+}
+
+/// Update metadata for a single session.
+/// `archived: None` keeps the session's current archived state.
 pub fn update_session_meta(
     source: &str,
     project_id: &str,
     session_id: &str,
     alias: Option<String>,
     tags: Vec<String>,
+    archived: Option<bool>,
 ) -> Result<(), String> {
+    let tags = normalize_tags(tags, settings::lowercase_tags());
+    let mut meta = load_metadata(source, project_id);
+
+    let existing = meta.sessions.remove(session_id).unwrap_or_default();
+    // Only new tags are checked, so tightening the rules never blocks other edits
+    let added: Vec<String> = tags
+        .iter()
+        .filter(|t| !existing.tags.contains(t))
+        .cloned()
+        .collect();
+    check_tags(&added)?;
+    let entry = SessionMeta {
+        alias,
+        tags,
+        archived: archived.unwrap_or(existing.archived),
+        pinned_message_ids: existing.pinned_message_ids,
+        note: existing.note,
+        rating: existing.rating,
+    };
+
+    // Drop the entry entirely once nothing is left in it
+    if !entry.is_empty() {
+        meta.sessions.insert(session_id.to_string(), entry);
+    }
+
+    save_metadata(source, project_id, &meta)
+}
+
+/// Add tags to many sessions of a project with a single write. Tags are
+/// normalized as in `update_session_meta`, and tags a session already has are
+/// skipped, so repeating a call changes nothing. Returns the number of
+/// sessions that gained a tag; with `dry_run` nothing is written.
+pub fn bulk_add_tags(
+    source: &str,
+    project_id: &str,
+    additions: &HashMap<String, Vec<String>>,
+    dry_run: bool,
+) -> Result<usize, String> {
+    let lowercase = settings::lowercase_tags();
+    let added = normalize_tags(additions.values().flatten().cloned().collect(), lowercase);
+    check_tags(&added)?;
     let mut meta = load_metadata(source, project_id);
+    let mut changed = 0;
+
+    for (session_id, tags) in additions {
+        let entry = meta.sessions.entry(session_id.clone()).or_default();
+        let merged = normalize_tags(entry.tags.iter().chain(tags).cloned().collect(), lowercase);
+        if merged.iter().any(|t| !entry.tags.contains(t)) {
+            entry.tags = merged;
+            changed += 1;
+        }
+    }
+    meta.sessions.retain(|_, entry| !entry.is_empty());
 
-    // If both alias and tags are empty, remove the entry
-    if alias.is_none() && tags.is_empty() {
-        meta.sessions.remove(session_id);
+    if !dry_run && changed > 0 {
+        save_metadata(source, project_id, &meta)?;
+    }
+    Ok(changed)
+}
+
+/// Trim tags and collapse internal whitespace, optionally lowercase them,
+/// then drop empties and duplicates and sort.
+pub fn normalize_tags(tags: Vec<String>, lowercase: bool) -> Vec<String> {
+    let mut normalized: Vec<String> = tags
+        .iter()
+        .map(|t| t.split_whitespace().collect::<Vec<_>>().join(" "))
+        .map(|t| if lowercase { t.to_lowercase() } else { t })
+        .filter(|t| !t.is_empty())
+        .collect();
+    normalized.sort();
+    normalized.dedup();
+    normalized
+}
+
+/// Check tags against the `tagRules` setting. Returns one message per
+/// violation (`"tag": reason`); with no rules configured every tag passes.
+/// An `allowedChars` that isn't a valid regex is ignored.
+pub fn validate_tags(tags: &[String]) -> Result<(), Vec<String>> {
+    let rules = settings::tag_rules();
+    let allowed = rules
+        .allowed_chars
+        .as_deref()
+        .and_then(|class| regex::Regex::new(&format!("^(?:{})$", class)).ok());
+    let disallowed: Vec<String> = rules.disallowed.iter().map(|t| t.to_lowercase()).collect();
+
+    let mut violations = Vec::new();
+    for tag in tags {
+        if let Some(max) = rules.max_length {
+            let len = tag.chars().count();
+            if len > max {
+                violations.push(format!(
+                    "{:?}: {} characters, at most {} allowed",
+                    tag, len, max
+                ));
+            }
+        }
+        if let Some(allowed) = &allowed {
+            let mut bad: Vec<char> = tag
+                .chars()
+                .filter(|c| !allowed.is_match(c.encode_utf8(&mut [0; 4])))
+                .collect();
+            bad.sort_unstable();
+            bad.dedup();
+            if !bad.is_empty() {
+                let bad: String = bad.into_iter().collect();
+                violations.push(format!("{:?}: characters {:?} are not allowed", tag, bad));
+            }
+        }
+        if disallowed.contains(&tag.to_lowercase()) {
+            violations.push(format!("{:?}: tag is not allowed", tag));
+        }
+    }
+
+    if violations.is_empty() {
+        Ok(())
     } else {
-        meta.sessions
-            .insert(session_id.to_string(), SessionMeta { alias, tags });
+        Err(violations)
+    }
+}
+
+/// `validate_tags` as a single error message, for rejecting a save
+fn check_tags(tags: &[String]) -> Result<(), String> {
+    validate_tags(tags).map_err(|violations| format!("Invalid tags: {}", violations.join("; ")))
+}
+
+/// Pin or unpin a message within a session.
+/// Returns `true` if the message is pinned after the call.
+pub fn toggle_pinned_message(
+    source: &str,
+    project_id: &str,
+    session_id: &str,
+    message_id: &str,
+) -> Result<bool, String> {
+    let mut meta = load_metadata(source, project_id);
+    let mut entry = meta.sessions.remove(session_id).unwrap_or_default();
+
+    let pinned = match entry
+        .pinned_message_ids
+        .iter()
+        .position(|id| id == message_id)
+    {
+        Some(idx) => {
+            entry.pinned_message_ids.remove(idx);
+            false
+        }
+        None => {
+            entry.pinned_message_ids.push(message_id.to_string());
+            true
+        }
+    };
+
+    if !entry.is_empty() {
+        meta.sessions.insert(session_id.to_string(), entry);
+    }
+
+    save_metadata(source, project_id, &meta)?;
+    Ok(pinned)
+}
+
+/// Store (or clear, with `None`) the cached note for a single session
+pub fn set_session_note(
+    source: &str,
+    project_id: &str,
+    session_id: &str,
+    note: Option<String>,
+) -> Result<(), String> {
+    let mut meta = load_metadata(source, project_id);
+    let mut entry = meta.sessions.remove(session_id).unwrap_or_default();
+    entry.note = note;
+
+    if !entry.is_empty() {
+        meta.sessions.insert(session_id.to_string(), entry);
+    }
+
+    save_metadata(source, project_id, &meta)
+}
+
+/// Set (or clear, with `None`) a session's 1–5 rating.
+/// A rated session keeps its metadata entry even without alias or tags.
+pub fn set_session_rating(
+    source: &str,
+    project_id: &str,
+    session_id: &str,
+    rating: Option<u8>,
+) -> Result<(), String> {
+    if let Some(r) = rating {
+        if !(1..=5).contains(&r) {
+            return Err(format!("Rating must be between 1 and 5, got {}", r));
+        }
+    }
+
+    let mut meta = load_metadata(source, project_id);
+    let mut entry = meta.sessions.remove(session_id).unwrap_or_default();
+    entry.rating = rating;
+
+    if !entry.is_empty() {
+        meta.sessions.insert(session_id.to_string(), entry);
+    }
+
+    save_metadata(source, project_id, &meta)
+}
+
+/// Clear a session's alias and tags.
+///
+/// Only the user-facing labels are reset: archived state, pins and the cached
+/// note are kept, and the entry is dropped only if nothing else remains. Use
+/// `remove_session_meta` to wipe the whole entry.
+pub fn clear_session_meta(source: &str, project_id: &str, session_id: &str) -> Result<(), String> {
+    let mut meta = load_metadata(source, project_id);
+    let mut entry = match meta.sessions.remove(session_id) {
+        Some(e) => e,
+        None => return Ok(()),
+    };
+    entry.alias = None;
+    entry.tags.clear();
+
+    if !entry.is_empty() {
+        meta.sessions.insert(session_id.to_string(), entry);
     }
 
     save_metadata(source, project_id, &meta)
@@ -117,6 +437,252 @@ pub fn remove_session_meta(
     Ok(())
 }
 
+/// Move a session's metadata entry to another project (and/or session id)
+pub fn move_session_meta(
+    source: &str,
+    from_project: &str,
+    from_session: &str,
+    to_project: &str,
+    to_session: &str,
+) -> Result<(), String> {
+    let mut from_meta = load_metadata(source, from_project);
+    let entry = match from_meta.sessions.remove(from_session) {
+        Some(e) => e,
+        None => return Ok(()),
+    };
+
+    if from_project == to_project {
+        from_meta.sessions.insert(to_session.to_string(), entry);
+        return save_metadata(source, from_project, &from_meta);
+    }
+
+    let mut to_meta = load_metadata(source, to_project);
+    to_meta.sessions.insert(to_session.to_string(), entry);
+    save_metadata(source, to_project, &to_meta)?;
+    save_metadata(source, from_project, &from_meta)
+}
+
+/// What `import_metadata` changed (or, in a dry run, would change). Ids are sorted.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    /// Sessions that had no metadata in the project
+    pub added: Vec<String>,
+    /// Existing sessions whose metadata the policy changed
+    pub updated: Vec<String>,
+    /// Empty imported entries, and existing sessions left as they were
+    pub skipped: Vec<String>,
+    /// Existing sessions whose imported entry differs from the current one
+    pub conflicts: Vec<String>,
+    /// Nothing was written
+    pub dry_run: bool,
+}
+
+impl ImportSummary {
+    /// Number of sessions added or changed
+    pub fn changed(&self) -> usize {
+        self.added.len() + self.updated.len()
+    }
+}
+
+/// Merge an imported metadata file into a project's metadata.
+///
+/// Conflicting sessions are resolved by `policy`. With `dry_run` the merge is
+/// computed the same way but nothing is written, so the summary previews the
+/// real import. Otherwise the file is only written when something changed.
+pub fn import_metadata(
+    source: &str,
+    project_id: &str,
+    imported: MetadataFile,
+    policy: ConflictPolicy,
+    dry_run: bool,
+) -> Result<ImportSummary, String> {
+    let mut meta = load_metadata(source, project_id);
+    let mut summary = ImportSummary {
+        dry_run,
+        ..Default::default()
+    };
+
+    for (session_id, incoming) in imported.sessions {
+        if incoming.is_empty() {
+            summary.skipped.push(session_id);
+            continue;
+        }
+        let merged = match meta.sessions.get(&session_id) {
+            None => {
+                summary.added.push(session_id.clone());
+                incoming
+            }
+            Some(current) => {
+                if incoming != *current {
+                    summary.conflicts.push(session_id.clone());
+                }
+                let merged = resolve_conflict(current, incoming, policy);
+                if merged == *current {
+                    summary.skipped.push(session_id);
+                    continue;
+                }
+                summary.updated.push(session_id.clone());
+                merged
+            }
+        };
+        meta.sessions.insert(session_id, merged);
+    }
+
+    summary.added.sort();
+    summary.updated.sort();
+    summary.skipped.sort();
+    summary.conflicts.sort();
+
+    if !dry_run && summary.changed() > 0 {
+        save_metadata(source, project_id, &meta)?;
+    }
+    Ok(summary)
+}
+
+fn resolve_conflict(
+    current: &SessionMeta,
+    incoming: SessionMeta,
+    policy: ConflictPolicy,
+) -> SessionMeta {
+    match policy {
+        ConflictPolicy::KeepExisting => current.clone(),
+        ConflictPolicy::PreferImported => SessionMeta {
+            alias: incoming.alias.or_else(|| current.alias.clone()),
+            tags: if incoming.tags.is_empty() {
+                current.tags.clone()
+            } else {
+                incoming.tags
+            },
+            archived: incoming.archived,
+            pinned_message_ids: if incoming.pinned_message_ids.is_empty() {
+                current.pinned_message_ids.clone()
+            } else {
+                incoming.pinned_message_ids
+            },
+            note: incoming.note.or_else(|| current.note.clone()),
+            rating: incoming.rating.or(current.rating),
+        },
+        ConflictPolicy::UnionTags => {
+            let mut tags = current.tags.clone();
+            for tag in incoming.tags {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+            let mut pinned_message_ids = current.pinned_message_ids.clone();
+            for id in incoming.pinned_message_ids {
+                if !pinned_message_ids.contains(&id) {
+                    pinned_message_ids.push(id);
+                }
+            }
+            SessionMeta {
+                alias: current.alias.clone().or(incoming.alias),
+                tags,
+                archived: current.archived,
+                pinned_message_ids,
+                note: current.note.clone().or(incoming.note),
+                rating: current.rating.or(incoming.rating),
+            }
+        }
+    }
+}
+
+/// Match metadata entries against the project's current session files.
+///
+/// An orphaned entry can be re-keyed (only when `rekey` is set) if its old key
+/// is still recorded inside a session file whose id changed: the embedded
+/// `sessionId` for Claude, or the file name / `session_meta` id for Codex. The
+/// metadata file is only written when something was re-keyed.
+pub fn reconcile_metadata(
+    source: &str,
+    project_id: &str,
+    rekey: bool,
+) -> Result<ReconcileReport, String> {
+    let sessions = match source {
+        "claude" => claude::get_sessions(project_id, None)?,
+        // Codex metadata is shared across projects, so check every session
+        "codex" => codex::list_all_sessions()?,
+        _ => return Err(format!("Unknown source: {}", source)),
+    };
+    let mut meta = load_metadata(source, project_id);
+
+    let mut report = ReconcileReport::default();
+    let mut unmatched: Vec<&SessionIndexEntry> = Vec::new();
+    for session in &sessions {
+        if meta.sessions.contains_key(&session.session_id) {
+            report.matched += 1;
+        } else {
+            unmatched.push(session);
+        }
+    }
+
+    let current_ids: std::collections::HashSet<&str> =
+        sessions.iter().map(|s| s.session_id.as_str()).collect();
+    let mut orphaned: Vec<String> = meta
+        .sessions
+        .keys()
+        .filter(|id| !current_ids.contains(id.as_str()))
+        .cloned()
+        .collect();
+    orphaned.sort();
+
+    if rekey && !orphaned.is_empty() {
+        unmatched.retain(|session| {
+            let old_id = previous_ids(source, session)
+                .into_iter()
+                .find(|id| orphaned.contains(id));
+            match old_id {
+                Some(old_id) => {
+                    if let Some(entry) = meta.sessions.remove(&old_id) {
+                        meta.sessions.insert(session.session_id.clone(), entry);
+                    }
+                    orphaned.retain(|id| id != &old_id);
+                    report.rekeyed.push((old_id, session.session_id.clone()));
+                    false
+                }
+                None => true,
+            }
+        });
+        report.matched += report.rekeyed.len();
+    }
+
+    report.orphaned = orphaned;
+    report.unmatched_files = unmatched
+        .iter()
+        .map(|s| (s.session_id.clone(), s.file_path.clone()))
+        .collect();
+
+    if !report.rekeyed.is_empty() {
+        save_metadata(source, project_id, &meta)?;
+    }
+    Ok(report)
+}
+
+/// Ids a session may have been known by before, read from its file
+fn previous_ids(source: &str, session: &SessionIndexEntry) -> Vec<String> {
+    let path = Path::new(&session.file_path);
+    let mut ids = Vec::new();
+    match source {
+        "claude" => {
+            if let Some((embedded, _, _)) = claude_parser::extract_session_metadata(path) {
+                ids.push(embedded);
+            }
+        }
+        "codex" => {
+            if let Some(meta) = codex::extract_session_meta(path) {
+                ids.push(meta.id);
+            }
+            if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                ids.push(stem.to_string());
+            }
+        }
+        _ => {}
+    }
+    ids.retain(|id| !id.is_empty() && id != &session.session_id);
+    ids
+}
+
 /// Get all unique tags used in a project (for autocomplete)
 pub fn get_all_tags(source: &str, project_id: &str) -> Vec<String> {
     let meta = load_metadata(source, project_id);
@@ -131,33 +697,219 @@ pub fn get_all_tags(source: &str, project_id: &str) -> Vec<String> {
     tags
 }
 
+/// What `rename_tag` would change, computed without writing anything
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenamePreview {
+    /// The normalized new tag name
+    pub new_tag: String,
+    /// Sessions carrying the old tag, sorted
+    pub affected: Vec<String>,
+    /// Subset of `affected` that already has the new tag (the two collapse into one)
+    pub deduped: Vec<String>,
+}
+
+/// Preview renaming tag `old` to `new` across a project
+pub fn preview_rename_tag(
+    source: &str,
+    project_id: &str,
+    old: &str,
+    new: &str,
+) -> Result<RenamePreview, String> {
+    let meta = load_metadata(source, project_id);
+    plan_rename(&meta, old, new)
+}
+
+/// Rename tag `old` to `new` on every session of a project.
+/// Returns the preview of what was changed.
+pub fn rename_tag(
+    source: &str,
+    project_id: &str,
+    old: &str,
+    new: &str,
+) -> Result<RenamePreview, String> {
+    let mut meta = load_metadata(source, project_id);
+    let plan = plan_rename(&meta, old, new)?;
+    if plan.affected.is_empty() || plan.new_tag == old {
+        return Ok(plan);
+    }
+
+    for id in &plan.affected {
+        if let Some(entry) = meta.sessions.get_mut(id) {
+            let tags = std::mem::take(&mut entry.tags)
+                .into_iter()
+                .map(|t| if t == old { plan.new_tag.clone() } else { t })
+                .collect();
+            entry.tags = normalize_tags(tags, false);
+        }
+    }
+
+    save_metadata(source, project_id, &meta)?;
+    Ok(plan)
+}
+
+/// Remove tag `tag` from every session of a project. Entries left with no
+/// metadata are dropped. Returns the number of sessions changed.
+pub fn delete_tag(source: &str, project_id: &str, tag: &str) -> Result<usize, String> {
+    let mut meta = load_metadata(source, project_id);
+    let mut changed = 0;
+    for entry in meta.sessions.values_mut() {
+        let before = entry.tags.len();
+        entry.tags.retain(|t| t != tag);
+        if entry.tags.len() != before {
+            changed += 1;
+        }
+    }
+    if changed == 0 {
+        return Ok(0);
+    }
+    meta.sessions.retain(|_, entry| !entry.is_empty());
+
+    save_metadata(source, project_id, &meta)?;
+    Ok(changed)
+}
+
+/// Unify tags of a project that differ only by case. Each group takes its
+/// most used spelling (ties go to the one seen first, in session id order).
+/// Returns the number of tags rewritten.
+pub fn merge_tag_casing(source: &str, project_id: &str) -> Result<usize, String> {
+    let mut meta = load_metadata(source, project_id);
+    let mut ids: Vec<String> = meta.sessions.keys().cloned().collect();
+    ids.sort();
+
+    // lowercase → spellings as (tag, uses), in first-seen order
+    let mut groups: HashMap<String, Vec<(String, usize)>> = HashMap::new();
+    for id in &ids {
+        for tag in &meta.sessions[id].tags {
+            let spellings = groups.entry(tag.to_lowercase()).or_default();
+            match spellings.iter_mut().find(|(t, _)| t == tag) {
+                Some((_, uses)) => *uses += 1,
+                None => spellings.push((tag.clone(), 1)),
+            }
+        }
+    }
+
+    let canonical: HashMap<String, String> = groups
+        .into_iter()
+        .filter(|(_, spellings)| spellings.len() > 1)
+        .filter_map(|(lower, spellings)| {
+            // max_by_key keeps the last maximum, so scan in reverse for first-seen
+            let (tag, _) = spellings.into_iter().rev().max_by_key(|(_, uses)| *uses)?;
+            Some((lower, tag))
+        })
+        .collect();
+    if canonical.is_empty() {
+        return Ok(0);
+    }
+
+    let mut replaced = 0;
+    for entry in meta.sessions.values_mut() {
+        let mut changed = false;
+        let tags: Vec<String> = std::mem::take(&mut entry.tags)
+            .into_iter()
+            .map(|tag| match canonical.get(&tag.to_lowercase()) {
+                Some(canon) if *canon != tag => {
+                    changed = true;
+                    replaced += 1;
+                    canon.clone()
+                }
+                _ => tag,
+            })
+            .collect();
+        entry.tags = if changed {
+            normalize_tags(tags, false)
+        } else {
+            tags
+        };
+    }
+
+    save_metadata(source, project_id, &meta)?;
+    Ok(replaced)
+}
+
+fn plan_rename(meta: &MetadataFile, old: &str, new: &str) -> Result<RenamePreview, String> {
+    let new_tag = normalize_tags(vec![new.to_string()], settings::lowercase_tags())
+        .pop()
+        .ok_or_else(|| "New tag name is empty".to_string())?;
+    check_tags(std::slice::from_ref(&new_tag))?;
+
+    let mut affected = Vec::new();
+    let mut deduped = Vec::new();
+    for (id, entry) in &meta.sessions {
+        if !entry.tags.iter().any(|t| t == old) {
+            continue;
+        }
+        if new_tag != old && entry.tags.contains(&new_tag) {
+            deduped.push(id.clone());
+        }
+        affected.push(id.clone());
+    }
+    affected.sort();
+    deduped.sort();
+
+    Ok(RenamePreview {
+        new_tag,
+        affected,
+        deduped,
+    })
+}
+
+/// List (session_id, alias) pairs for every aliased session in a project, sorted by session id
+pub fn list_aliases(source: &str, project_id: &str) -> Vec<(String, String)> {
+    let meta = load_metadata(source, project_id);
+    let mut aliases: Vec<(String, String)> = meta
+        .sessions
+        .into_iter()
+        .filter_map(|(id, sm)| sm.alias.map(|alias| (id, alias)))
+        .collect();
+    aliases.sort();
+    aliases
+}
+
+/// Aliased sessions matching `query` despite typos or reordered words, as
+/// (session_id, alias, score), best score first, at most `limit`
+pub fn find_session_by_alias_fuzzy(
+    source: &str,
+    project_id: &str,
+    query: &str,
+    limit: usize,
+) -> Vec<(String, String, u32)> {
+    let query_lower = query.to_lowercase();
+    let mut matches: Vec<(String, String, u32)> = list_aliases(source, project_id)
+        .into_iter()
+        .filter_map(|(id, alias)| {
+            let score = fuzzy::score(&query_lower, &alias.to_lowercase())?;
+            Some((id, alias, score))
+        })
+        .collect();
+    matches.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+    matches.truncate(limit);
+    matches
+}
+
+/// Group aliases shared by more than one session: alias → sorted session ids
+pub fn find_duplicate_aliases(source: &str, project_id: &str) -> HashMap<String, Vec<String>> {
+    let mut by_alias: HashMap<String, Vec<String>> = HashMap::new();
+    for (session_id, alias) in list_aliases(source, project_id) {
+        by_alias.entry(alias).or_default().push(session_id);
+    }
+    by_alias.retain(|_, ids| ids.len() > 1);
+    by_alias
+}
+
 /// Get tags for all projects across the given source.
 /// Returns a map: project_id (encoded_name for Claude, "" for Codex) → deduplicated sorted tags.
 pub fn get_all_cross_project_tags(source: &str) -> HashMap<String, Vec<String>> {
     match source {
         "claude" => {
-            let projects_dir = match get_projects_dir() {
-                Some(d) if d.exists() => d,
-                _ => return HashMap::new(),
-            };
-            let mut result = HashMap::new();
-            if let Ok(entries) = fs::read_dir(&projects_dir) {
-                for entry in entries.flatten() {
-                    let path = entry.path();
-                    if !path.is_dir() {
-                        continue;
-                    }
-                    let encoded_name = match path.file_name().and_then(|n| n.to_str()) {
-                        Some(name) => name.to_string(),
-                        None => continue,
-                    };
-                    let tags = get_all_tags("claude", &encoded_name);
-                    if !tags.is_empty() {
-                        result.insert(encoded_name, tags);
-                    }
-                }
-            }
-            result
+            let project_ids = visible_claude_project_ids(&settings::ignored_projects());
+            parallel::map_bounded(project_ids, |encoded_name| {
+                let tags = get_all_tags("claude", &encoded_name);
+                (encoded_name, tags)
+            })
+            .into_iter()
+            .filter(|(_, tags)| !tags.is_empty())
+            .collect()
         }
         "codex" => {
             let tags = get_all_tags("codex", "");
@@ -170,3 +922,816 @@ pub fn get_all_cross_project_tags(source: &str) -> HashMap<String, Vec<String>>
         _ => HashMap::new(),
     }
 }
+
+/// `get_all_cross_project_tags` for every source: source → project → tags.
+/// Sources without any tags are left out.
+pub fn get_all_tags_all_sources() -> HashMap<String, HashMap<String, Vec<String>>> {
+    ["claude", "codex"]
+        .into_iter()
+        .map(|source| (source.to_string(), get_all_cross_project_tags(source)))
+        .filter(|(_, projects)| !projects.is_empty())
+        .collect()
+}
+
+/// Number of sessions carrying each tag, summed over every source. Counts
+/// sessions, like `top_tags`, not the projects a tag appears in.
+pub fn tag_counts_all_sources() -> HashMap<String, usize> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for source in ["claude", "codex"] {
+        for (tag, n) in top_tags(source, usize::MAX) {
+            *counts.entry(tag).or_insert(0) += n;
+        }
+    }
+    counts
+}
+
+/// Encoded names of all Claude project directories
+fn claude_project_ids() -> Vec<String> {
+    let projects_dir = match get_projects_dir() {
+        Some(d) if d.exists() => d,
+        _ => return Vec::new(),
+    };
+    match fs::read_dir(&projects_dir) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_dir())
+            .filter_map(|p| p.file_name().and_then(|n| n.to_str()).map(String::from))
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Claude projects not hidden by the `ignoredProjects` setting
+fn visible_claude_project_ids(ignored: &[String]) -> Vec<String> {
+    claude_project_ids()
+        .into_iter()
+        .filter(|id| {
+            ignored.is_empty() || !settings::is_project_ignored(ignored, &claude::display_path(id))
+        })
+        .collect()
+}
+
+/// Codex sessions whose cwd is hidden by the `ignoredProjects` setting
+fn ignored_codex_session_ids(ignored: &[String]) -> HashSet<String> {
+    if ignored.is_empty() {
+        return HashSet::new();
+    }
+    codex::list_all_sessions()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|s| {
+            s.cwd
+                .as_deref()
+                .is_some_and(|cwd| settings::is_project_ignored(ignored, cwd))
+        })
+        .map(|s| s.session_id)
+        .collect()
+}
+
+/// The `limit` most-used tags across all projects of a source, as
+/// (tag, number of sessions carrying it). Ties are broken alphabetically.
+/// Projects hidden by the `ignoredProjects` setting are left out.
+pub fn top_tags(source: &str, limit: usize) -> Vec<(String, usize)> {
+    let ignored = settings::ignored_projects();
+    let (project_ids, hidden_sessions) = match source {
+        "claude" => (visible_claude_project_ids(&ignored), HashSet::new()),
+        "codex" => (vec![String::new()], ignored_codex_session_ids(&ignored)),
+        _ => return Vec::new(),
+    };
+
+    let per_project = parallel::map_bounded(project_ids, |project_id| {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        let sessions = load_metadata(source, &project_id).sessions;
+        for (session_id, session) in &sessions {
+            if hidden_sessions.contains(session_id) {
+                continue;
+            }
+            for tag in &session.tags {
+                *counts.entry(tag.clone()).or_insert(0) += 1;
+            }
+        }
+        counts
+    });
+
+    let mut totals: HashMap<String, usize> = HashMap::new();
+    for counts in per_project {
+        for (tag, n) in counts {
+            *totals.entry(tag).or_insert(0) += n;
+        }
+    }
+
+    let mut tags: Vec<(String, usize)> = totals.into_iter().collect();
+    tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    tags.truncate(limit);
+    tags
+}
+
+/// Each tag used in a project with the newest mtime (epoch seconds) among the
+/// session files carrying it, most recent first. Tags whose sessions no
+/// longer exist on disk are left out.
+pub fn get_tags_with_recency(source: &str, project_id: &str) -> Vec<(String, i64)> {
+    sort_by_recency(tag_recency(source, project_id, &HashSet::new()))
+}
+
+/// `get_tags_with_recency` across all projects of a source, leaving out
+/// projects matched by the `ignoredProjects` setting
+pub fn get_recent_tags(source: &str) -> Vec<(String, i64)> {
+    let ignored = settings::ignored_projects();
+    let (project_ids, hidden_sessions) = match source {
+        "claude" => (visible_claude_project_ids(&ignored), HashSet::new()),
+        "codex" => (vec![String::new()], ignored_codex_session_ids(&ignored)),
+        _ => return Vec::new(),
+    };
+
+    let mut newest: HashMap<String, i64> = HashMap::new();
+    let per_project = parallel::map_bounded(project_ids, |pid| {
+        tag_recency(source, &pid, &hidden_sessions)
+    });
+    for recency in per_project {
+        for (tag, mtime) in recency {
+            let entry = newest.entry(tag).or_insert(mtime);
+            *entry = (*entry).max(mtime);
+        }
+    }
+    sort_by_recency(newest)
+}
+
+fn sort_by_recency(recency: HashMap<String, i64>) -> Vec<(String, i64)> {
+    let mut tags: Vec<(String, i64)> = recency.into_iter().collect();
+    tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    tags
+}
+
+/// tag → newest mtime of a session carrying it, for one project, skipping
+/// the `hidden` session ids
+fn tag_recency(source: &str, project_id: &str, hidden: &HashSet<String>) -> HashMap<String, i64> {
+    let meta = load_metadata(source, project_id);
+    let mut recency: HashMap<String, i64> = HashMap::new();
+    if meta.sessions.values().all(|s| s.tags.is_empty()) {
+        return recency;
+    }
+
+    let mtimes = session_mtimes(source, project_id);
+    for (session_id, session) in &meta.sessions {
+        if hidden.contains(session_id) {
+            continue;
+        }
+        let mtime = match mtimes.get(session_id) {
+            Some(t) => *t,
+            None => continue,
+        };
+        for tag in &session.tags {
+            let entry = recency.entry(tag.clone()).or_insert(mtime);
+            *entry = (*entry).max(mtime);
+        }
+    }
+    recency
+}
+
+/// Session id → file mtime (epoch seconds) for a project's session files
+fn session_mtimes(source: &str, project_id: &str) -> HashMap<String, i64> {
+    let files: Vec<PathBuf> = match source {
+        "claude" => get_projects_dir()
+            .and_then(|dir| fs::read_dir(dir.join(project_id)).ok())
+            .map(|rd| {
+                rd.flatten()
+                    .map(|e| e.path())
+                    .filter(|p| is_session_file(p, "claude"))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        "codex" => codex::scan_all_session_files(),
+        _ => return HashMap::new(),
+    };
+
+    files
+        .into_iter()
+        .filter_map(|path| {
+            let mtime = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .ok()?
+                .duration_since(std::time::UNIX_EPOCH)
+                .ok()?
+                .as_secs() as i64;
+            let stem = path.file_stem()?.to_str()?.to_string();
+            // Codex metadata is keyed by the session_meta id, as in `session_key`
+            let session_id = match source {
+                "codex" => codex::extract_session_meta(&path)
+                    .map(|m| m.id)
+                    .filter(|id| !id.is_empty())
+                    .unwrap_or(stem),
+                _ => stem,
+            };
+            Some((session_id, mtime))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{claude_user, set_mtime, TestHome};
+
+    const PROJECT: &str = "-home-user-project";
+
+    fn set_alias(session_id: &str, alias: &str) {
+        update_session_meta(
+            "claude",
+            PROJECT,
+            session_id,
+            Some(alias.to_string()),
+            Vec::new(),
+            None,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn duplicate_aliases_are_grouped() {
+        let _home = TestHome::new();
+        set_alias("s1", "refactor parser");
+        set_alias("s2", "refactor parser");
+        set_alias("s3", "release notes");
+        update_session_meta("claude", PROJECT, "s4", None, vec!["wip".into()], None).unwrap();
+
+        assert_eq!(
+            list_aliases("claude", PROJECT),
+            [
+                ("s1".to_string(), "refactor parser".to_string()),
+                ("s2".to_string(), "refactor parser".to_string()),
+                ("s3".to_string(), "release notes".to_string()),
+            ]
+        );
+        let mut duplicates = find_duplicate_aliases("claude", PROJECT);
+        assert_eq!(duplicates.len(), 1);
+        let mut ids = duplicates.remove("refactor parser").unwrap();
+        ids.sort();
+        assert_eq!(ids, ["s1", "s2"]);
+    }
+
+    #[test]
+    fn distinct_aliases_have_no_duplicates() {
+        let _home = TestHome::new();
+        set_alias("s1", "one");
+        set_alias("s2", "two");
+
+        assert!(find_duplicate_aliases("claude", PROJECT).is_empty());
+    }
+
+    #[test]
+    fn merge_from_a_custom_root_reads_that_roots_metadata() {
+        let home = TestHome::new();
+        home.claude_session(PROJECT, "s1", &[claude_user("u1", "hello")]);
+        set_alias("s1", "live");
+        let root = home.path().join("backup");
+        let backup = root.join(PROJECT);
+        fs::create_dir_all(&backup).unwrap();
+        let live = home.claude_project(PROJECT);
+        fs::copy(live.join("s1.jsonl"), backup.join("s1.jsonl")).unwrap();
+        let meta = r#"{"version": 1, "sessions": {"s1": {"alias": "restored", "rating": 4}}}"#;
+        fs::write(backup.join(".session-viewer-meta.json"), meta).unwrap();
+
+        let mut sessions = crate::provider::claude::get_sessions(PROJECT, Some(&root)).unwrap();
+        let loaded = merge_session_meta_in("claude", PROJECT, Some(&root), &mut sessions);
+        assert_eq!(sessions[0].alias.as_deref(), Some("restored"));
+        assert_eq!(sessions[0].rating, Some(4));
+        assert!(loaded.sessions.contains_key("s1"));
+
+        merge_session_meta("claude", PROJECT, &mut sessions);
+        assert_eq!(sessions[0].alias.as_deref(), Some("live"));
+    }
+
+    /// A Claude user record that names `session_id` as the session it belongs to
+    fn record_of(session_id: &str, uuid: &str) -> String {
+        let mut record: serde_json::Value =
+            serde_json::from_str(&claude_user(uuid, "hello")).unwrap();
+        record["sessionId"] = session_id.into();
+        record.to_string()
+    }
+
+    /// Sessions `kept` (with metadata), `copy` (its records still say `old-id`,
+    /// whose metadata is orphaned) and `fresh` (no metadata), plus an orphaned
+    /// entry `gone` with no file at all
+    fn reconcile_fixture(home: &TestHome) {
+        home.claude_session(PROJECT, "kept", &[record_of("kept", "u1")]);
+        home.claude_session(PROJECT, "copy", &[record_of("old-id", "u2")]);
+        home.claude_session(PROJECT, "fresh", &[record_of("fresh", "u3")]);
+        set_alias("kept", "kept alias");
+        set_alias("old-id", "renamed alias");
+        set_alias("gone", "gone alias");
+    }
+
+    fn unmatched_ids(report: &ReconcileReport) -> Vec<&str> {
+        let mut ids: Vec<&str> = report
+            .unmatched_files
+            .iter()
+            .map(|(id, _)| id.as_str())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn reconcile_reports_orphans_and_unmatched_files() {
+        let home = TestHome::new();
+        reconcile_fixture(&home);
+
+        let report = reconcile_metadata("claude", PROJECT, false).unwrap();
+        assert_eq!(report.matched, 1);
+        assert_eq!(report.orphaned, ["gone", "old-id"]);
+        assert_eq!(unmatched_ids(&report), ["copy", "fresh"]);
+        assert!(report.rekeyed.is_empty());
+        // Without rekey nothing is written
+        assert!(load_metadata("claude", PROJECT)
+            .sessions
+            .contains_key("old-id"));
+    }
+
+    #[test]
+    fn reconcile_rekeys_entries_when_asked() {
+        let home = TestHome::new();
+        reconcile_fixture(&home);
+
+        let report = reconcile_metadata("claude", PROJECT, true).unwrap();
+        assert_eq!(report.rekeyed, [("old-id".to_string(), "copy".to_string())]);
+        assert_eq!(report.matched, 2);
+        assert_eq!(report.orphaned, ["gone"]);
+        assert_eq!(unmatched_ids(&report), ["fresh"]);
+
+        let meta = load_metadata("claude", PROJECT);
+        assert!(!meta.sessions.contains_key("old-id"));
+        assert_eq!(
+            meta.sessions["copy"].alias.as_deref(),
+            Some("renamed alias")
+        );
+    }
+
+    fn tag(project_id: &str, session_id: &str, tags: &[&str]) {
+        let tags = tags.iter().map(|t| t.to_string()).collect();
+        update_session_meta("claude", project_id, session_id, None, tags, None).unwrap();
+    }
+
+    /// rust ×3, ci ×2, docs ×2 and zeta ×1, spread unevenly over three projects
+    fn tag_three_projects() {
+        tag("-home-user-alpha", "s1", &["rust", "ci"]);
+        tag("-home-user-alpha", "s2", &["rust"]);
+        tag("-home-user-beta", "s3", &["rust", "docs"]);
+        tag("-home-user-beta", "s4", &["ci"]);
+        tag("-home-user-gamma", "s5", &["docs", "zeta"]);
+    }
+
+    fn counts(tags: &[(&str, usize)]) -> Vec<(String, usize)> {
+        tags.iter().map(|(t, n)| (t.to_string(), *n)).collect()
+    }
+
+    #[test]
+    fn top_tags_aggregates_across_projects_and_breaks_ties_alphabetically() {
+        let _home = TestHome::new();
+        tag_three_projects();
+
+        assert_eq!(
+            top_tags("claude", 3),
+            counts(&[("rust", 3), ("ci", 2), ("docs", 2)])
+        );
+        assert_eq!(top_tags("claude", 10).len(), 4);
+        assert!(top_tags("claude", 0).is_empty());
+        assert!(top_tags("unknown", 10).is_empty());
+    }
+
+    #[test]
+    fn top_tags_leaves_out_ignored_projects() {
+        let _home = TestHome::new();
+        tag_three_projects();
+        settings::save_settings(&settings::SettingsFile {
+            ignored_projects: Some(vec![claude::display_path("-home-user-beta")]),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(
+            top_tags("claude", 10),
+            counts(&[("rust", 2), ("ci", 1), ("docs", 1), ("zeta", 1)])
+        );
+    }
+
+    #[test]
+    fn tags_from_both_sources_are_merged() {
+        let _home = TestHome::new();
+        tag("-home-user-alpha", "s1", &["rust", "shared"]);
+        tag("-home-user-beta", "s2", &["shared"]);
+        let codex_tags = vec!["cli".to_string(), "shared".to_string()];
+        update_session_meta("codex", "", "c1", None, codex_tags, None).unwrap();
+
+        let all = get_all_tags_all_sources();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all["claude"]["-home-user-alpha"], ["rust", "shared"]);
+        assert_eq!(all["claude"]["-home-user-beta"], ["shared"]);
+        assert_eq!(all["codex"][""], ["cli", "shared"]);
+
+        let totals = tag_counts_all_sources();
+        assert_eq!(totals.len(), 3);
+        assert_eq!(totals["shared"], 3);
+        assert_eq!(totals["rust"], 1);
+        assert_eq!(totals["cli"], 1);
+    }
+
+    fn save_tag_rules(rules: settings::TagRules) {
+        settings::save_settings(&settings::SettingsFile {
+            tag_rules: Some(rules),
+            ..Default::default()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn any_tag_passes_without_rules() {
+        let _home = TestHome::new();
+        let long = "x".repeat(500);
+        assert!(validate_tags(&strings(&[&long, "C++ & C#", "テスト"])).is_ok());
+    }
+
+    #[test]
+    fn length_and_character_rules_report_each_bad_tag() {
+        let _home = TestHome::new();
+        save_tag_rules(settings::TagRules {
+            max_length: Some(8),
+            allowed_chars: Some("[a-z0-9-]".to_string()),
+            disallowed: strings(&["TODO"]),
+        });
+
+        assert!(validate_tags(&strings(&["rust", "ci-cd", "v2"])).is_ok());
+        let violations =
+            validate_tags(&strings(&["ok", "much-too-long", "C++", "todo"])).unwrap_err();
+        assert_eq!(
+            violations,
+            [
+                r#""much-too-long": 13 characters, at most 8 allowed"#,
+                r#""C++": characters "+C" are not allowed"#,
+                r#""todo": tag is not allowed"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_tags_are_rejected_before_saving() {
+        let _home = TestHome::new();
+        save_tag_rules(settings::TagRules {
+            max_length: Some(4),
+            ..Default::default()
+        });
+
+        let err = update_session_meta("claude", PROJECT, "s1", None, strings(&["toolong"]), None)
+            .unwrap_err();
+        assert!(err.starts_with("Invalid tags: "), "{}", err);
+        let additions = HashMap::from([("s1".to_string(), strings(&["toolong"]))]);
+        assert!(bulk_add_tags("claude", PROJECT, &additions, false).is_err());
+        assert!(load_metadata("claude", PROJECT).sessions.is_empty());
+
+        update_session_meta("claude", PROJECT, "s1", None, strings(&["ok"]), None).unwrap();
+        assert_eq!(load_metadata("claude", PROJECT).sessions["s1"].tags, ["ok"]);
+    }
+
+    fn entry(alias: Option<&str>, tags: &[&str]) -> SessionMeta {
+        SessionMeta {
+            alias: alias.map(str::to_string),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Import over a project whose `s1` is aliased "mine" and tagged a, b.
+    /// The import renames `s1` to "theirs" with tags b, c, adds `s2` and
+    /// carries an empty `s3`.
+    fn import_with(policy: ConflictPolicy, dry_run: bool) -> (ImportSummary, SessionMeta) {
+        update_session_meta(
+            "claude",
+            PROJECT,
+            "s1",
+            Some("mine".to_string()),
+            vec!["a".to_string(), "b".to_string()],
+            None,
+        )
+        .unwrap();
+        let imported = MetadataFile {
+            version: 1,
+            sessions: HashMap::from([
+                ("s1".to_string(), entry(Some("theirs"), &["b", "c"])),
+                ("s2".to_string(), entry(Some("new"), &[])),
+                ("s3".to_string(), entry(None, &[])),
+            ]),
+        };
+
+        let summary = import_metadata("claude", PROJECT, imported, policy, dry_run).unwrap();
+        let s1 = load_metadata("claude", PROJECT).sessions["s1"].clone();
+        (summary, s1)
+    }
+
+    #[test]
+    fn import_keep_existing_leaves_the_conflicting_entry_alone() {
+        let _home = TestHome::new();
+        let (summary, s1) = import_with(ConflictPolicy::KeepExisting, false);
+
+        assert_eq!(s1, entry(Some("mine"), &["a", "b"]));
+        assert_eq!(summary.added, ["s2"]);
+        assert!(summary.updated.is_empty());
+        assert_eq!(summary.conflicts, ["s1"]);
+        assert!(load_metadata("claude", PROJECT).sessions.contains_key("s2"));
+    }
+
+    #[test]
+    fn import_prefer_imported_takes_alias_and_tags_from_the_import() {
+        let _home = TestHome::new();
+        let (summary, s1) = import_with(ConflictPolicy::PreferImported, false);
+
+        assert_eq!(s1, entry(Some("theirs"), &["b", "c"]));
+        assert_eq!(summary.updated, ["s1"]);
+        assert_eq!(summary.conflicts, ["s1"]);
+    }
+
+    #[test]
+    fn import_union_tags_keeps_the_alias_and_merges_tags() {
+        let _home = TestHome::new();
+        let (summary, s1) = import_with(ConflictPolicy::UnionTags, false);
+
+        assert_eq!(s1, entry(Some("mine"), &["a", "b", "c"]));
+        assert_eq!(summary.updated, ["s1"]);
+    }
+
+    #[test]
+    fn prefer_imported_keeps_values_the_import_leaves_unset() {
+        let current = entry(Some("mine"), &["a"]);
+        let merged = resolve_conflict(&current, entry(None, &[]), ConflictPolicy::PreferImported);
+        assert_eq!(merged, current);
+    }
+
+    #[test]
+    fn import_dry_run_writes_nothing() {
+        let _home = TestHome::new();
+        let (summary, s1) = import_with(ConflictPolicy::PreferImported, true);
+
+        assert!(summary.dry_run);
+        assert_eq!(summary.updated, ["s1"]);
+        assert_eq!(s1, entry(Some("mine"), &["a", "b"]));
+        assert!(!load_metadata("claude", PROJECT).sessions.contains_key("s2"));
+    }
+
+    #[test]
+    fn dry_run_summary_matches_the_real_import() {
+        for policy in [
+            ConflictPolicy::KeepExisting,
+            ConflictPolicy::PreferImported,
+            ConflictPolicy::UnionTags,
+        ] {
+            let _home = TestHome::new();
+            let (preview, _) = import_with(policy, true);
+            let (applied, _) = import_with(policy, false);
+
+            assert!(preview.dry_run && !applied.dry_run);
+            let preview = ImportSummary {
+                dry_run: false,
+                ..preview
+            };
+            assert_eq!(preview, applied, "{:?}", policy);
+        }
+    }
+
+    fn strings(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn normalize_trims_collapses_dedups_and_sorts() {
+        let raw = strings(&["  bug ", "code   review", "Bug", "bug", "", "   ", "api"]);
+        assert_eq!(
+            normalize_tags(raw.clone(), false),
+            ["Bug", "api", "bug", "code review"]
+        );
+        assert_eq!(normalize_tags(raw, true), ["api", "bug", "code review"]);
+    }
+
+    #[test]
+    fn saved_tags_are_normalized_and_lowercased_only_when_enabled() {
+        let _home = TestHome::new();
+        tag(PROJECT, "s1", &[" Bug", "bug ", "needs  docs"]);
+        assert_eq!(
+            get_all_tags("claude", PROJECT),
+            ["Bug", "bug", "needs docs"]
+        );
+
+        settings::save_settings(&settings::SettingsFile {
+            lowercase_tags: Some(true),
+            ..Default::default()
+        })
+        .unwrap();
+        tag(PROJECT, "s1", &["BUG", " bug", "Needs Docs"]);
+        let additions = HashMap::from([("s2".to_string(), strings(&["  API ", "api"]))]);
+        bulk_add_tags("claude", PROJECT, &additions, false).unwrap();
+        assert_eq!(
+            get_all_tags("claude", PROJECT),
+            ["api", "bug", "needs docs"]
+        );
+    }
+
+    #[test]
+    fn fuzzy_alias_lookup_tolerates_a_typo() {
+        let _home = TestHome::new();
+        set_alias("s1", "database migration");
+        set_alias("s2", "release notes");
+        set_alias("s3", "migration rollback");
+
+        let found = find_session_by_alias_fuzzy("claude", PROJECT, "migratoin", 10);
+        let ids: Vec<&str> = found.iter().map(|(id, _, _)| id.as_str()).collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&"s1") && ids.contains(&"s3"));
+
+        let found = find_session_by_alias_fuzzy("claude", PROJECT, "release notes", 1);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, "s2");
+        assert_eq!(found[0].2, crate::fuzzy::EXACT_SCORE);
+    }
+
+    #[test]
+    fn clearing_resets_alias_and_tags_but_keeps_other_fields() {
+        let _home = TestHome::new();
+        for session_id in ["noted", "plain"] {
+            update_session_meta(
+                "claude",
+                PROJECT,
+                session_id,
+                Some("my alias".to_string()),
+                strings(&["bug"]),
+                None,
+            )
+            .unwrap();
+        }
+        set_session_note("claude", PROJECT, "noted", Some("summary".to_string())).unwrap();
+
+        clear_session_meta("claude", PROJECT, "noted").unwrap();
+        clear_session_meta("claude", PROJECT, "plain").unwrap();
+        clear_session_meta("claude", PROJECT, "unknown").unwrap();
+
+        let meta = load_metadata("claude", PROJECT);
+        let noted = &meta.sessions["noted"];
+        assert_eq!(noted.alias, None);
+        assert!(noted.tags.is_empty());
+        assert_eq!(noted.note.as_deref(), Some("summary"));
+        assert!(!meta.sessions.contains_key("plain"));
+        assert!(!meta.sessions.contains_key("unknown"));
+    }
+
+    /// Write a session file in `project_id` last modified at `mtime`
+    fn session_at(home: &TestHome, project_id: &str, session_id: &str, mtime: u64) {
+        let path = home.claude_session(project_id, session_id, &[claude_user("u1", "hi")]);
+        set_mtime(&path, mtime);
+    }
+
+    #[test]
+    fn a_tag_takes_the_time_of_its_most_recent_session() {
+        let home = TestHome::new();
+        let (old, new) = (1_700_000_000, 1_700_003_600);
+        session_at(&home, PROJECT, "old", old);
+        session_at(&home, PROJECT, "new", new);
+        session_at(&home, "-home-user-beta", "beta", new + 60);
+        tag(PROJECT, "old", &["shared", "legacy"]);
+        tag(PROJECT, "new", &["shared"]);
+        tag(PROJECT, "deleted", &["ghost"]);
+        tag("-home-user-beta", "beta", &["beta"]);
+
+        assert_eq!(
+            get_tags_with_recency("claude", PROJECT),
+            [
+                ("shared".to_string(), new as i64),
+                ("legacy".to_string(), old as i64),
+            ]
+        );
+        let recent: Vec<String> = get_recent_tags("claude")
+            .into_iter()
+            .map(|(tag, _)| tag)
+            .collect();
+        assert_eq!(recent, ["beta", "shared", "legacy"]);
+
+        settings::save_settings(&settings::SettingsFile {
+            ignored_projects: Some(vec![claude::display_path("-home-user-beta")]),
+            ..Default::default()
+        })
+        .unwrap();
+        let recent: Vec<String> = get_recent_tags("claude")
+            .into_iter()
+            .map(|(tag, _)| tag)
+            .collect();
+        assert_eq!(recent, ["shared", "legacy"]);
+    }
+
+    /// session id → tags, for every session with metadata in `PROJECT`
+    fn tags_by_session() -> HashMap<String, Vec<String>> {
+        load_metadata("claude", PROJECT)
+            .sessions
+            .into_iter()
+            .map(|(id, entry)| (id, entry.tags))
+            .collect()
+    }
+
+    #[test]
+    fn rename_preview_matches_what_rename_changes() {
+        let _home = TestHome::new();
+        tag(PROJECT, "s1", &["bug", "ui"]);
+        tag(PROJECT, "s2", &["bug", "defect"]);
+        tag(PROJECT, "s3", &["ui"]);
+        tag(PROJECT, "s4", &["defect"]);
+        let before = tags_by_session();
+
+        let preview = preview_rename_tag("claude", PROJECT, "bug", " defect ").unwrap();
+        assert_eq!(preview.new_tag, "defect");
+        assert_eq!(preview.affected, ["s1", "s2"]);
+        assert_eq!(preview.deduped, ["s2"]);
+        assert_eq!(tags_by_session(), before, "preview must not write");
+
+        let applied = rename_tag("claude", PROJECT, "bug", " defect ").unwrap();
+        assert_eq!(applied, preview);
+
+        let after = tags_by_session();
+        let mut changed: Vec<&String> = after
+            .keys()
+            .filter(|id| after[*id] != before[*id])
+            .collect();
+        changed.sort();
+        assert_eq!(changed, preview.affected.iter().collect::<Vec<_>>());
+        assert_eq!(after["s1"], ["defect", "ui"]);
+        assert_eq!(after["s2"], ["defect"]);
+    }
+
+    #[test]
+    fn casing_variants_merge_into_the_most_used_spelling() {
+        let _home = TestHome::new();
+        tag(PROJECT, "s1", &["Bug", "UI"]);
+        tag(PROJECT, "s2", &["bug", "ui"]);
+        tag(PROJECT, "s3", &["bug"]);
+        tag(PROJECT, "s4", &["Docs"]);
+
+        // "bug" wins 2–1; "UI" and "ui" tie, so the first seen (s1) wins
+        assert_eq!(merge_tag_casing("claude", PROJECT).unwrap(), 2);
+        let tags = tags_by_session();
+        assert_eq!(tags["s1"], ["UI", "bug"]);
+        assert_eq!(tags["s2"], ["UI", "bug"]);
+        assert_eq!(tags["s3"], ["bug"]);
+        assert_eq!(tags["s4"], ["Docs"]);
+
+        assert_eq!(merge_tag_casing("claude", PROJECT).unwrap(), 0);
+    }
+
+    #[test]
+    fn ratings_outside_one_to_five_are_rejected() {
+        let _home = TestHome::new();
+        for rating in [0, 6] {
+            let err = set_session_rating("claude", PROJECT, "s1", Some(rating)).unwrap_err();
+            assert_eq!(
+                err,
+                format!("Rating must be between 1 and 5, got {}", rating)
+            );
+        }
+        assert!(load_metadata("claude", PROJECT).sessions.is_empty());
+
+        for rating in 1..=5 {
+            set_session_rating("claude", PROJECT, "s1", Some(rating)).unwrap();
+        }
+        assert_eq!(
+            load_metadata("claude", PROJECT).sessions["s1"].rating,
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn a_rating_alone_keeps_the_entry() {
+        let _home = TestHome::new();
+        set_alias("s1", "labelled");
+        set_session_rating("claude", PROJECT, "s1", Some(4)).unwrap();
+
+        // Clearing alias and tags leaves the rated entry in place
+        update_session_meta("claude", PROJECT, "s1", None, Vec::new(), None).unwrap();
+        let entry = &load_metadata("claude", PROJECT).sessions["s1"];
+        assert_eq!((entry.alias.as_deref(), entry.rating), (None, Some(4)));
+
+        set_session_rating("claude", PROJECT, "s1", None).unwrap();
+        assert!(load_metadata("claude", PROJECT).sessions.is_empty());
+    }
+
+    #[test]
+    fn deleting_a_tag_strips_it_everywhere_and_drops_empty_entries() {
+        let _home = TestHome::new();
+        tag(PROJECT, "s1", &["bug", "ui"]);
+        tag(PROJECT, "s2", &["bug"]);
+        tag(PROJECT, "s3", &["ui"]);
+        set_alias("s4", "kept");
+
+        assert_eq!(delete_tag("claude", PROJECT, "bug").unwrap(), 2);
+        let tags = tags_by_session();
+        assert_eq!(tags["s1"], ["ui"]);
+        assert!(!tags.contains_key("s2"));
+        assert_eq!(tags["s3"], ["ui"]);
+        assert!(tags.contains_key("s4"));
+        assert_eq!(get_all_tags("claude", PROJECT), ["ui"]);
+
+        assert_eq!(delete_tag("claude", PROJECT, "bug").unwrap(), 0);
+    }
+}
@@ -1,6 +1,31 @@
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
 
 use crate::cli_config;
+use crate::data_dir;
+use crate::http;
+use crate::parser::format::read_records;
+use crate::provider::{claude, codex};
+use crate::settings;
+
+/// How long a fetched model list stays fresh in memory
+pub const MODEL_CACHE_TTL: Duration = Duration::from_secs(600);
+
+/// Context window of the built-in Claude models, in tokens
+const CLAUDE_CONTEXT_WINDOW: u32 = 200_000;
+
+/// Last successful API model list, keyed by base URL + API key
+static MODEL_CACHE: Mutex<Option<CachedModels>> = Mutex::new(None);
+
+struct CachedModels {
+    key: String,
+    fetched_at: Instant,
+    models: Vec<ModelInfo>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -10,11 +35,27 @@ pub struct ModelInfo {
     pub provider: String,
     pub group: String,
     pub created: Option<i64>,
+    /// Maximum input tokens the model accepts (None = unknown)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
 struct AnthropicModelsResponse {
     data: Vec<AnthropicModel>,
+    #[serde(default)]
+    has_more: bool,
+    #[serde(default)]
+    last_id: Option<String>,
+}
+
+/// One page of the model list plus the cursor to fetch the next one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPage {
+    pub models: Vec<ModelInfo>,
+    /// Pass back to `list_models_page` for the next page; None on the last page
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -39,8 +80,32 @@ fn infer_group(id: &str) -> String {
     "Other".to_string()
 }
 
-/// Built-in Claude models — mirrors Claude CLI `/model` output.
+/// Built-in Claude models: the user override file when valid, else the compiled-in list.
 fn builtin_claude_models() -> Vec<ModelInfo> {
+    load_builtin_override().unwrap_or_else(default_builtin_models)
+}
+
+/// Read `.session-viewer/builtin-models.json` in the data directory (a JSON
+/// array of `ModelInfo`).
+/// Returns None when the file is missing, malformed or empty.
+fn load_builtin_override() -> Option<Vec<ModelInfo>> {
+    let path = data_dir::data_dir()
+        .ok()?
+        .join(".session-viewer")
+        .join("builtin-models.json");
+    let content = std::fs::read_to_string(&path).ok()?;
+    match serde_json::from_str::<Vec<ModelInfo>>(&content) {
+        Ok(models) if !models.is_empty() => Some(models),
+        Ok(_) => None,
+        Err(e) => {
+            eprintln!("Warning: ignoring invalid {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Compiled-in Claude models — mirrors Claude CLI `/model` output.
+fn default_builtin_models() -> Vec<ModelInfo> {
     vec![
         ModelInfo {
             id: "claude-sonnet-4-6".to_string(),
@@ -48,6 +113,7 @@ fn builtin_claude_models() -> Vec<ModelInfo> {
             provider: "anthropic".to_string(),
             group: "Claude Sonnet".to_string(),
             created: None,
+            context_window: Some(CLAUDE_CONTEXT_WINDOW),
         },
         ModelInfo {
             id: "claude-opus-4-6".to_string(),
@@ -55,6 +121,7 @@ fn builtin_claude_models() -> Vec<ModelInfo> {
             provider: "anthropic".to_string(),
             group: "Claude Opus".to_string(),
             created: None,
+            context_window: Some(CLAUDE_CONTEXT_WINDOW),
         },
         ModelInfo {
             id: "claude-haiku-4-5".to_string(),
@@ -62,25 +129,46 @@ fn builtin_claude_models() -> Vec<ModelInfo> {
             provider: "anthropic".to_string(),
             group: "Claude Haiku".to_string(),
             created: None,
+            context_window: Some(CLAUDE_CONTEXT_WINDOW),
         },
     ]
 }
 
 async fn fetch_anthropic_models(api_key: &str, base_url: &str) -> Result<Vec<ModelInfo>, String> {
+    fetch_anthropic_page(api_key, base_url, None)
+        .await
+        .map(|page| page.models)
+}
+
+/// Fetch one page of `/v1/models`, starting after `after_id` when given.
+async fn fetch_anthropic_page(
+    api_key: &str,
+    base_url: &str,
+    after_id: Option<&str>,
+) -> Result<ModelPage, String> {
     let url = format!("{}/v1/models", base_url.trim_end_matches('/'));
-    let client = reqwest::Client::new();
-    let resp = client
-        .get(&url)
+    let (_, timeout) = settings::diagnostic_timeouts();
+    let mut request = http::client()?.get(&url);
+    if let Some(after_id) = after_id {
+        request = request.query(&[("after_id", after_id)]);
+    }
+    let request = request
+        .timeout(timeout)
         .header("x-api-key", api_key)
-        .header("anthropic-version", "2023-06-01")
+        .header("anthropic-version", "2023-06-01");
+    let resp = http::with_custom_headers(request)
         .send()
         .await
         .map_err(|e| format!("Anthropic API request failed: {}", e))?;
 
     if !resp.status().is_success() {
         let status = resp.status();
-        let text = resp.text().await.unwrap_or_default();
-        return Err(format!("Anthropic API error {}: {}", status, text));
+        let suffix = http::request_id_suffix(http::request_id(&resp).as_deref());
+        let text = http::read_text_capped(resp).await;
+        return Err(format!(
+            "Anthropic API error {}: {}{}",
+            status, text, suffix
+        ));
     }
 
     let body: AnthropicModelsResponse = resp
@@ -88,6 +176,7 @@ async fn fetch_anthropic_models(api_key: &str, base_url: &str) -> Result<Vec<Mod
         .await
         .map_err(|e| format!("Failed to parse Anthropic models response: {}", e))?;
 
+    let next_cursor = if body.has_more { body.last_id } else { None };
     let mut models: Vec<ModelInfo> = body
         .data
         .into_iter()
@@ -105,6 +194,8 @@ async fn fetch_anthropic_models(api_key: &str, base_url: &str) -> Result<Vec<Mod
                 provider: "anthropic".to_string(),
                 group,
                 created,
+                // Not reported by /v1/models
+                context_window: None,
             }
         })
         .collect();
@@ -114,13 +205,59 @@ async fn fetch_anthropic_models(api_key: &str, base_url: &str) -> Result<Vec<Mod
     models.retain(|m| m.id.to_lowercase().contains("claude"));
 
     // Sort by created desc (newest first)
-    models.sort_by(|a, b| b.created.cmp(&a.created));
+    models.sort_by_key(|m| std::cmp::Reverse(m.created));
+    Ok(ModelPage {
+        models,
+        next_cursor,
+    })
+}
+
+/// Serializes API fetches so a prefetch in flight and a picker open share one request.
+fn fetch_lock() -> &'static tokio::sync::Mutex<()> {
+    static LOCK: OnceLock<tokio::sync::Mutex<()>> = OnceLock::new();
+    LOCK.get_or_init(|| tokio::sync::Mutex::new(()))
+}
+
+fn cached_models(key: &str) -> Option<Vec<ModelInfo>> {
+    let cache = MODEL_CACHE.lock();
+    cache
+        .as_ref()
+        .filter(|c| c.key == key && c.fetched_at.elapsed() < MODEL_CACHE_TTL)
+        .map(|c| c.models.clone())
+}
+
+/// Fetch API models, reusing a fresh cached result when available.
+async fn fetch_anthropic_models_cached(
+    api_key: &str,
+    base_url: &str,
+) -> Result<Vec<ModelInfo>, String> {
+    let key = format!("{}\n{}", base_url, api_key);
+    if let Some(models) = cached_models(&key) {
+        return Ok(models);
+    }
+
+    let _guard = fetch_lock().lock().await;
+    // Another caller may have filled the cache while we waited
+    if let Some(models) = cached_models(&key) {
+        return Ok(models);
+    }
+
+    let models = fetch_anthropic_models(api_key, base_url).await?;
+    *MODEL_CACHE.lock() = Some(CachedModels {
+        key,
+        fetched_at: Instant::now(),
+        models: models.clone(),
+    });
     Ok(models)
 }
 
+/// Forget the cached API model list so the next request refetches it.
+pub fn clear_cache() {
+    *MODEL_CACHE.lock() = None;
+}
+
 /// Merge: built-in models first, then append any API-only extras (deduped).
 fn merge_models(builtin: Vec<ModelInfo>, api_models: Vec<ModelInfo>) -> Vec<ModelInfo> {
-    use std::collections::HashSet;
     let builtin_ids: HashSet<String> = builtin.iter().map(|m| m.id.clone()).collect();
     let mut result = builtin;
     for m in api_models {
@@ -136,12 +273,75 @@ fn merge_models(builtin: Vec<ModelInfo>, api_models: Vec<ModelInfo>) -> Vec<Mode
 /// - `_source`: ignored (always uses Claude)
 /// - `api_key`: user-provided key (empty string = use CLI config / env var)
 /// - `base_url`: base URL for the API (empty string = use CLI config / env var / default)
+/// - `provider_filter`: when set, only models whose `provider` matches are returned
+/// - `min_context`: when set, only models whose known context window is at least
+///   this many tokens are returned (models with an unknown window are dropped)
 pub async fn list_models(
     _source: &str,
     api_key: &str,
     base_url: &str,
+    provider_filter: Option<&str>,
+    min_context: Option<u32>,
 ) -> Result<Vec<ModelInfo>, String> {
-    let (resolved_key, resolved_url) = if api_key.is_empty() && base_url.is_empty() {
+    let models = list_all_models(api_key, base_url).await?;
+    let models = filter_by_provider(models, provider_filter);
+    Ok(filter_by_context(models, min_context))
+}
+
+/// List one page of Claude models, for catalogs too large to fetch at once.
+///
+/// The first page (`cursor` = None) has the built-in models merged in, like
+/// `list_models`; later pages hold only what the API returned. Pass the
+/// returned `next_cursor` back to get the following page. Unlike `list_models`,
+/// API errors are returned rather than falling back to the built-ins, and
+/// pages are not cached.
+pub async fn list_models_page(
+    _source: &str,
+    api_key: &str,
+    base_url: &str,
+    cursor: Option<&str>,
+) -> Result<ModelPage, String> {
+    let cursor = cursor.filter(|c| !c.is_empty());
+    let (resolved_key, resolved_url) = resolve_credentials(api_key, base_url);
+    if resolved_key.is_empty() {
+        if cursor.is_some() {
+            return Err("No API key configured".to_string());
+        }
+        return Ok(ModelPage {
+            models: ensure_non_empty(builtin_claude_models()),
+            next_cursor: None,
+        });
+    }
+
+    let page = fetch_anthropic_page(&resolved_key, &resolved_url, cursor).await?;
+    if cursor.is_some() {
+        return Ok(page);
+    }
+    Ok(ModelPage {
+        models: ensure_non_empty(merge_models(builtin_claude_models(), page.models)),
+        next_cursor: page.next_cursor,
+    })
+}
+
+/// Keep only models from the given provider (case-insensitive); `None` keeps all.
+fn filter_by_provider(mut models: Vec<ModelInfo>, provider_filter: Option<&str>) -> Vec<ModelInfo> {
+    if let Some(provider) = provider_filter.filter(|p| !p.is_empty()) {
+        models.retain(|m| m.provider.eq_ignore_ascii_case(provider));
+    }
+    models
+}
+
+/// Keep only models with a known context window of at least `min_context`; `None` keeps all.
+fn filter_by_context(mut models: Vec<ModelInfo>, min_context: Option<u32>) -> Vec<ModelInfo> {
+    if let Some(min) = min_context {
+        models.retain(|m| m.context_window.is_some_and(|w| w >= min));
+    }
+    models
+}
+
+/// Resolve the key and base URL from the arguments, CLI config and env vars.
+fn resolve_credentials(api_key: &str, base_url: &str) -> (String, String) {
+    if api_key.is_empty() && base_url.is_empty() {
         let (cli_key, cli_url) = cli_config::get_credentials("claude");
         let final_key = if cli_key.is_empty() {
             std::env::var("ANTHROPIC_API_KEY").unwrap_or_default()
@@ -156,20 +356,23 @@ pub async fn list_models(
             api_key.to_string()
         };
         let url = if base_url.is_empty() {
-            std::env::var("ANTHROPIC_BASE_URL")
-                .unwrap_or_else(|_| "https://api.anthropic.com".to_string())
+            std::env::var("ANTHROPIC_BASE_URL").unwrap_or_else(|_| settings::default_base_url())
         } else {
             base_url.to_string()
         };
         (key, url)
-    };
+    }
+}
+
+async fn list_all_models(api_key: &str, base_url: &str) -> Result<Vec<ModelInfo>, String> {
+    let (resolved_key, resolved_url) = resolve_credentials(api_key, base_url);
 
     let builtin = builtin_claude_models();
     if resolved_key.is_empty() {
-        return Ok(builtin);
+        return Ok(ensure_non_empty(builtin));
     }
 
-    let api_models = match fetch_anthropic_models(&resolved_key, &resolved_url).await {
+    let api_models = match fetch_anthropic_models_cached(&resolved_key, &resolved_url).await {
         Ok(models) => models,
         Err(e) => {
             eprintln!("Warning: failed to fetch Anthropic models: {}", e);
@@ -177,5 +380,626 @@ pub async fn list_models(
         }
     };
 
-    Ok(merge_models(builtin, api_models))
+    Ok(ensure_non_empty(merge_models(builtin, api_models)))
+}
+
+/// Last-resort model so the picker is never blank.
+fn minimal_default_model() -> ModelInfo {
+    ModelInfo {
+        id: "claude-sonnet-4-6".to_string(),
+        name: "Sonnet 4.6".to_string(),
+        provider: "anthropic".to_string(),
+        group: "Claude Sonnet".to_string(),
+        created: None,
+        context_window: Some(CLAUDE_CONTEXT_WINDOW),
+    }
+}
+
+/// Fall back to `minimal_default_model` when neither the API nor the built-ins yielded anything.
+fn ensure_non_empty(models: Vec<ModelInfo>) -> Vec<ModelInfo> {
+    if !models.is_empty() {
+        return models;
+    }
+    eprintln!("Warning: no Claude models from API or built-in list, using minimal default");
+    vec![minimal_default_model()]
+}
+
+/// CLI family shortcuts accepted by `resolve_model_alias` (each matches ids containing it)
+const MODEL_FAMILY_ALIASES: &[&str] = &["sonnet", "opus", "haiku"];
+
+/// Resolve a CLI model shortcut ("sonnet", "opus", "haiku", "latest") to a full API id.
+///
+/// Inputs that already look like a full id (`claude-...`) are returned unchanged.
+/// Family aliases pick the first matching model from the (cached) model list, so
+/// the recommended built-in wins over older API entries; "latest" picks the most
+/// recently created model. Returns None for an unknown alias.
+pub async fn resolve_model_alias(source: &str, alias: &str) -> Option<String> {
+    let alias = alias.trim();
+    if alias.is_empty() {
+        return None;
+    }
+    if alias.to_lowercase().starts_with("claude-") {
+        return Some(alias.to_string());
+    }
+
+    let lower = alias.to_lowercase();
+    let models = list_models(source, "", "", None, None).await.ok()?;
+    if lower == "latest" {
+        return models
+            .iter()
+            .filter(|m| m.created.is_some())
+            .max_by_key(|m| m.created)
+            .or_else(|| models.first())
+            .map(|m| m.id.clone());
+    }
+
+    let family = MODEL_FAMILY_ALIASES.iter().find(|a| **a == lower)?;
+    models
+        .iter()
+        .find(|m| m.id.to_lowercase().contains(family))
+        .map(|m| m.id.clone())
+}
+
+/// Warm the model cache in the background so the first picker open is instant.
+///
+/// Fire-and-forget: must be called from within a tokio runtime; errors are logged.
+pub fn prefetch_models(source: &str) {
+    let source = source.to_string();
+    tokio::spawn(async move {
+        if let Err(e) = list_models(&source, "", "", None, None).await {
+            tracing::warn!("Model prefetch failed: {}", e);
+        }
+    });
+}
+
+/// How a session's model id compares with the current model list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ModelStatus {
+    /// The id is in the current model list
+    Known,
+    /// The id is not offered any more (retired or renamed upstream)
+    Unknown,
+    /// No model recorded, or no model list for this provider to compare against
+    Unchecked,
+}
+
+/// Model audit result for one session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelAudit {
+    pub session_id: String,
+    pub file_path: String,
+    /// Last model the session used (the one a resume would ask for)
+    pub model: Option<String>,
+    pub status: ModelStatus,
+}
+
+/// Check each session's model id against the current (cached) model list.
+///
+/// Only Claude models are listed, so Codex sessions come back `Unchecked`.
+pub async fn audit_session_models(
+    source: &str,
+    project_id: &str,
+) -> Result<Vec<ModelAudit>, String> {
+    let sessions = match source {
+        "claude" => claude::get_sessions(project_id, None)?,
+        "codex" => codex::get_sessions(project_id)?,
+        _ => return Err(format!("Unknown source: {}", source)),
+    };
+
+    let known: HashSet<String> = if source == "claude" {
+        list_models(source, "", "", None, None)
+            .await?
+            .into_iter()
+            .map(|m| m.id)
+            .collect()
+    } else {
+        HashSet::new()
+    };
+
+    Ok(sessions
+        .into_iter()
+        .map(|s| {
+            let model = last_model_id(Path::new(&s.file_path), source);
+            let status = match &model {
+                Some(id) if source == "claude" => {
+                    if known.contains(id) {
+                        ModelStatus::Known
+                    } else {
+                        ModelStatus::Unknown
+                    }
+                }
+                _ => ModelStatus::Unchecked,
+            };
+            ModelAudit {
+                session_id: s.session_id,
+                file_path: s.file_path,
+                model,
+                status,
+            }
+        })
+        .collect())
+}
+
+/// Whether a session's recorded model can still be used to resume it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum ModelAvailability {
+    /// The model is in the current model list
+    Available { model: String },
+    /// The model was retired; `replacement` is the current model of its family
+    Deprecated { model: String, replacement: String },
+    /// No model recorded, not a Claude model, or no family to map it to;
+    /// `suggested` is the newest model when one is known
+    Unknown {
+        model: Option<String>,
+        suggested: Option<String>,
+    },
+}
+
+/// Check the last model a session used against the current (cached) model
+/// list, so a resume can be warned about, or switched away from, a retired model.
+pub async fn check_session_model_available(
+    file_path: &str,
+    source: &str,
+) -> Result<ModelAvailability, String> {
+    if !matches!(source, "claude" | "codex") {
+        return Err(format!("Unknown source: {}", source));
+    }
+    let path = Path::new(file_path);
+    if !path.exists() {
+        return Err(format!("File not found: {}", file_path));
+    }
+
+    let model = last_model_id(path, source);
+    // Only Claude models are listed, so there is nothing to compare Codex ones to
+    let model = match model {
+        Some(id) if source == "claude" => id,
+        model => {
+            return Ok(ModelAvailability::Unknown {
+                model,
+                suggested: None,
+            })
+        }
+    };
+
+    let known = list_models(source, "", "", None, None).await?;
+    if known.iter().any(|m| m.id == model) {
+        return Ok(ModelAvailability::Available { model });
+    }
+
+    let lower = model.to_lowercase();
+    let replacement = match MODEL_FAMILY_ALIASES.iter().find(|f| lower.contains(*f)) {
+        Some(family) => resolve_model_alias(source, family).await,
+        None => None,
+    };
+    Ok(match replacement {
+        Some(replacement) => ModelAvailability::Deprecated { model, replacement },
+        None => ModelAvailability::Unknown {
+            model: Some(model),
+            suggested: resolve_model_alias(source, "latest").await,
+        },
+    })
+}
+
+/// Last model id recorded in a session file.
+///
+/// Claude: assistant `message.model` (skipping the CLI's "<synthetic>" error
+/// placeholder). Codex: `turn_context` `payload.model`.
+fn last_model_id(path: &Path, source: &str) -> Option<String> {
+    let records = read_records(path).ok()?;
+    let mut last = None;
+    for line in records.map_while(Result::ok) {
+        if !line.contains("\"model\"") {
+            continue;
+        }
+        let record: serde_json::Value = match serde_json::from_str(&line) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let model = match source {
+            "codex" => record
+                .get("type")
+                .filter(|t| t.as_str() == Some("turn_context"))
+                .and_then(|_| record.get("payload"))
+                .and_then(|p| p.get("model")),
+            _ => record.get("message").and_then(|m| m.get("model")),
+        };
+        if let Some(id) = model.and_then(|m| m.as_str()) {
+            if !id.is_empty() && id != "<synthetic>" {
+                last = Some(id.to_string());
+            }
+        }
+    }
+    last
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        claude_assistant, claude_user, codex_meta, MockResponse, MockServer, TestHome,
+    };
+
+    fn model(id: &str, provider: &str) -> ModelInfo {
+        ModelInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            provider: provider.to_string(),
+            group: infer_group(id),
+            created: None,
+            context_window: None,
+        }
+    }
+
+    fn ids(models: &[ModelInfo]) -> Vec<&str> {
+        models.iter().map(|m| m.id.as_str()).collect()
+    }
+
+    #[test]
+    fn provider_filter_keeps_only_matching_models() {
+        let mixed = vec![
+            model("claude-sonnet-4-6", "anthropic"),
+            model("gpt-4o", "openai"),
+            model("claude-haiku-4-5", "Anthropic"),
+            model("o3-mini", "openai"),
+        ];
+
+        let openai = filter_by_provider(mixed.clone(), Some("openai"));
+        assert_eq!(ids(&openai), ["gpt-4o", "o3-mini"]);
+        let anthropic = filter_by_provider(mixed.clone(), Some("ANTHROPIC"));
+        assert_eq!(ids(&anthropic), ["claude-sonnet-4-6", "claude-haiku-4-5"]);
+        assert_eq!(filter_by_provider(mixed.clone(), None).len(), 4);
+        assert_eq!(filter_by_provider(mixed, Some("")).len(), 4);
+    }
+
+    #[tokio::test]
+    async fn unmatched_provider_lists_nothing() {
+        let _home = TestHome::new();
+        let models = list_models("claude", "", "", Some("google"), None)
+            .await
+            .unwrap();
+        assert!(models.is_empty());
+    }
+
+    #[test]
+    fn context_filter_drops_small_and_unknown_windows() {
+        let with_window = |id: &str, window: Option<u32>| ModelInfo {
+            context_window: window,
+            ..model(id, "anthropic")
+        };
+        let mixed = vec![
+            with_window("claude-small", Some(100_000)),
+            with_window("claude-standard", Some(200_000)),
+            with_window("claude-unknown", None),
+            with_window("claude-large", Some(1_000_000)),
+        ];
+
+        let large = filter_by_context(mixed.clone(), Some(200_000));
+        assert_eq!(ids(&large), ["claude-standard", "claude-large"]);
+        assert!(filter_by_context(mixed.clone(), Some(2_000_000)).is_empty());
+        assert_eq!(filter_by_context(mixed, None).len(), 4);
+    }
+
+    fn write_builtin_override(home: &TestHome, content: &str) {
+        let dir = home.path().join(".session-viewer");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("builtin-models.json"), content).unwrap();
+    }
+
+    #[test]
+    fn valid_builtin_override_replaces_the_defaults() {
+        let home = TestHome::new();
+        write_builtin_override(
+            &home,
+            r#"[{"id": "claude-opus-5", "name": "Opus 5", "provider": "anthropic",
+                 "group": "Claude Opus", "created": null, "contextWindow": 500000}]"#,
+        );
+
+        let models = builtin_claude_models();
+        assert_eq!(ids(&models), ["claude-opus-5"]);
+        assert_eq!(models[0].context_window, Some(500000));
+    }
+
+    #[tokio::test]
+    async fn listing_applies_the_minimum_context() {
+        let home = TestHome::new();
+        write_builtin_override(
+            &home,
+            r#"[{"id": "claude-standard", "name": "Standard", "provider": "anthropic",
+                 "group": "Claude", "created": null, "contextWindow": 200000},
+                {"id": "claude-large", "name": "Large", "provider": "anthropic",
+                 "group": "Claude", "created": null, "contextWindow": 1000000}]"#,
+        );
+
+        let models = list_models("claude", "", "", None, Some(500_000))
+            .await
+            .unwrap();
+        assert_eq!(ids(&models), ["claude-large"]);
+    }
+
+    #[test]
+    fn invalid_or_empty_builtin_override_is_ignored() {
+        let home = TestHome::new();
+        let defaults = ids(&default_builtin_models())
+            .into_iter()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+
+        for content in [r#"[{"id": "claude-opus-5"}]"#, "[]", "not json"] {
+            write_builtin_override(&home, content);
+            assert_eq!(ids(&builtin_claude_models()), defaults, "{}", content);
+        }
+    }
+
+    #[test]
+    fn empty_model_list_falls_back_to_the_minimal_default() {
+        assert_eq!(ids(&ensure_non_empty(Vec::new())), ["claude-sonnet-4-6"]);
+        let one = vec![model("claude-opus-5", "anthropic")];
+        assert_eq!(ids(&ensure_non_empty(one)), ["claude-opus-5"]);
+    }
+
+    #[tokio::test]
+    async fn proxy_with_no_claude_models_still_lists_the_builtins() {
+        let _home = TestHome::new();
+        let server = MockServer::start(vec![MockResponse::json(
+            200,
+            r#"{"data": [{"id": "gpt-4o"}, {"id": "o3-mini"}], "has_more": false}"#,
+            &[],
+        )]);
+
+        let models = list_models("claude", "sk-test", &server.url, None, None)
+            .await
+            .unwrap();
+        assert_eq!(ids(&models), ids(&default_builtin_models()));
+    }
+
+    #[tokio::test]
+    async fn full_ids_pass_through_and_unknown_aliases_resolve_to_none() {
+        let _home = TestHome::new();
+        for id in ["claude-sonnet-4-6", "Claude-Custom-Proxy-Model"] {
+            assert_eq!(resolve_model_alias("claude", id).await.as_deref(), Some(id));
+        }
+        assert_eq!(resolve_model_alias("claude", "gpt-4o").await, None);
+        assert_eq!(resolve_model_alias("claude", "  ").await, None);
+    }
+
+    #[tokio::test]
+    async fn family_aliases_resolve_to_the_builtins_offline() {
+        let _home = TestHome::new();
+        for (alias, id) in [
+            ("sonnet", "claude-sonnet-4-6"),
+            ("Opus", "claude-opus-4-6"),
+            (" haiku ", "claude-haiku-4-5"),
+            // No creation dates offline, so the first (recommended) model
+            ("latest", "claude-sonnet-4-6"),
+        ] {
+            assert_eq!(
+                resolve_model_alias("claude", alias).await.as_deref(),
+                Some(id),
+                "{}",
+                alias
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn latest_picks_the_newest_api_model() {
+        let _home = TestHome::new();
+        let server = MockServer::start(vec![MockResponse::json(
+            200,
+            r#"{"data": [
+                {"id": "claude-3-5-sonnet-20241022", "created_at": "2024-10-22T00:00:00Z"},
+                {"id": "claude-opus-4-7", "created_at": "2026-02-01T00:00:00Z"}
+            ]}"#,
+            &[],
+        )]);
+        std::env::set_var("ANTHROPIC_API_KEY", "sk-test");
+        std::env::set_var("ANTHROPIC_BASE_URL", &server.url);
+
+        assert_eq!(
+            resolve_model_alias("claude", "latest").await.as_deref(),
+            Some("claude-opus-4-7")
+        );
+        // The built-in stays the pick for its family over older API entries
+        assert_eq!(
+            resolve_model_alias("claude", "sonnet").await.as_deref(),
+            Some("claude-sonnet-4-6")
+        );
+    }
+
+    #[tokio::test]
+    async fn models_are_fetched_from_the_settings_base_url() {
+        let _home = TestHome::new();
+        let server = MockServer::start(vec![MockResponse::json(
+            200,
+            r#"{"data": [{"id": "claude-proxy-only"}]}"#,
+            &[],
+        )]);
+        settings::save_settings(&settings::SettingsFile {
+            default_base_url: Some(server.url.clone()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let models = list_models("claude", "sk-test", "", None, None)
+            .await
+            .unwrap();
+        assert!(models.iter().any(|m| m.id == "claude-proxy-only"));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn model_requests_carry_the_custom_headers() {
+        let _home = TestHome::new();
+        let server = MockServer::start(vec![MockResponse::json(200, r#"{"data": []}"#, &[])]);
+        settings::save_settings(&settings::SettingsFile {
+            default_base_url: Some(server.url.clone()),
+            custom_headers: Some(std::collections::HashMap::from([(
+                "X-Route".to_string(),
+                "eu-west".to_string(),
+            )])),
+            ..Default::default()
+        })
+        .unwrap();
+
+        list_models("claude", "sk-test", "", None, None)
+            .await
+            .unwrap();
+        let requests = server.requests();
+        assert_eq!(requests[0].header("x-route"), Some("eu-west"));
+        assert_eq!(requests[0].header("x-api-key"), Some("sk-test"));
+    }
+
+    #[tokio::test]
+    async fn model_list_errors_quote_the_request_id() {
+        let _home = TestHome::new();
+        let server = MockServer::start(vec![MockResponse::json(
+            401,
+            r#"{"error": {"type": "authentication_error"}}"#,
+            &[("x-request-id", "gw-7f3a")],
+        )]);
+
+        let err = fetch_anthropic_models("sk-secret-key", &server.url)
+            .await
+            .unwrap_err();
+        assert!(err.starts_with("Anthropic API error 401"), "{}", err);
+        assert!(err.ends_with(" (request-id: gw-7f3a)"), "{}", err);
+        assert!(!err.contains("sk-secret-key"));
+    }
+
+    #[tokio::test]
+    async fn model_pages_follow_the_cursor() {
+        let _home = TestHome::new();
+        let server = MockServer::start(vec![
+            MockResponse::json(
+                200,
+                r#"{"data": [{"id": "claude-page-one"}, {"id": "claude-sonnet-4-6"}],
+                    "has_more": true, "last_id": "claude-sonnet-4-6"}"#,
+                &[],
+            ),
+            MockResponse::json(
+                200,
+                r#"{"data": [{"id": "claude-page-two"}], "has_more": false,
+                    "last_id": "claude-page-two"}"#,
+                &[],
+            ),
+        ]);
+
+        let first = list_models_page("claude", "sk-test", &server.url, None)
+            .await
+            .unwrap();
+        let first_ids = ids(&first.models);
+        // The built-ins lead the first page and the duplicate is dropped
+        assert_eq!(first_ids[0], "claude-sonnet-4-6");
+        assert_eq!(first_ids.last(), Some(&"claude-page-one"));
+        assert_eq!(
+            first_ids
+                .iter()
+                .filter(|id| **id == "claude-sonnet-4-6")
+                .count(),
+            1
+        );
+        assert_eq!(first.next_cursor.as_deref(), Some("claude-sonnet-4-6"));
+
+        let cursor = first.next_cursor.as_deref();
+        let second = list_models_page("claude", "sk-test", &server.url, cursor)
+            .await
+            .unwrap();
+        assert_eq!(ids(&second.models), ["claude-page-two"]);
+        assert_eq!(second.next_cursor, None);
+
+        let requests = server.requests();
+        assert_eq!(requests[0].path, "/v1/models");
+        assert_eq!(requests[1].path, "/v1/models?after_id=claude-sonnet-4-6");
+    }
+
+    #[tokio::test]
+    async fn stalled_model_fetch_times_out() {
+        let _home = TestHome::new();
+        settings::save_settings(&settings::SettingsFile {
+            diagnostic_timeout_secs: Some(1),
+            ..Default::default()
+        })
+        .unwrap();
+        let server = MockServer::start(vec![MockResponse::json(200, r#"{"data": []}"#, &[])
+            .delayed(std::time::Duration::from_secs(5))]);
+
+        let started = Instant::now();
+        let err = fetch_anthropic_models("sk-test", &server.url)
+            .await
+            .unwrap_err();
+        assert!(err.starts_with("Anthropic API request failed"), "{}", err);
+        assert!(started.elapsed() < std::time::Duration::from_secs(4));
+    }
+
+    /// Availability of a Claude session whose last reply came from `model`
+    async fn availability_of(home: &TestHome, model: Option<&str>) -> ModelAvailability {
+        let mut lines = vec![claude_user("u1", "hi")];
+        if let Some(model) = model {
+            lines.push(claude_assistant("a1", "hello", model));
+        }
+        let path = home.claude_session("-home-user-project", "s1", &lines);
+        check_session_model_available(path.to_str().unwrap(), "claude")
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn listed_model_is_available() {
+        let home = TestHome::new();
+        assert_eq!(
+            availability_of(&home, Some("claude-opus-4-6")).await,
+            ModelAvailability::Available {
+                model: "claude-opus-4-6".to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn retired_model_is_replaced_by_its_family() {
+        let home = TestHome::new();
+        assert_eq!(
+            availability_of(&home, Some("claude-3-opus-20240229")).await,
+            ModelAvailability::Deprecated {
+                model: "claude-3-opus-20240229".to_string(),
+                replacement: "claude-opus-4-6".to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn unmapped_or_missing_models_are_unknown() {
+        let home = TestHome::new();
+        assert_eq!(
+            availability_of(&home, Some("claude-instant-1.2")).await,
+            ModelAvailability::Unknown {
+                model: Some("claude-instant-1.2".to_string()),
+                suggested: Some("claude-sonnet-4-6".to_string()),
+            }
+        );
+        assert_eq!(
+            availability_of(&home, None).await,
+            ModelAvailability::Unknown {
+                model: None,
+                suggested: None,
+            }
+        );
+
+        let codex = home.codex_session("c1", &[codex_meta("c1", "/home/user/project")]);
+        let result = check_session_model_available(codex.to_str().unwrap(), "codex").await;
+        assert_eq!(
+            result.unwrap(),
+            ModelAvailability::Unknown {
+                model: None,
+                suggested: None,
+            }
+        );
+        assert!(
+            check_session_model_available("/no/such/file.jsonl", "claude")
+                .await
+                .is_err()
+        );
+    }
 }
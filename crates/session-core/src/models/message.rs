@@ -46,6 +46,11 @@ pub enum ContentBlock {
     Text { text: String },
     #[serde(rename = "thinking")]
     Thinking { thinking: String },
+    #[serde(rename = "image")]
+    Image {
+        #[serde(default)]
+        source: Option<Value>,
+    },
     #[serde(rename = "tool_use")]
     ToolUse {
         id: String,
@@ -65,15 +70,65 @@ pub enum ContentBlock {
 
 // ── Unified display types (sent to frontend) ──
 
+/// Canonical message role shared by all providers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Assistant,
+    System,
+    Tool,
+}
+
+impl Role {
+    /// Map a provider-specific role label to its canonical role
+    pub fn from_raw(raw: &str) -> Option<Self> {
+        match raw.to_lowercase().as_str() {
+            "user" | "human" => Some(Role::User),
+            "assistant" | "model" | "ai" => Some(Role::Assistant),
+            "system" | "developer" => Some(Role::System),
+            "tool" | "function" | "function_call_output" | "tool_result" => Some(Role::Tool),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Assistant => "assistant",
+            Role::System => "system",
+            Role::Tool => "tool",
+        }
+    }
+}
+
 /// A display-ready message for the frontend
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DisplayMessage {
     pub uuid: Option<String>,
-    pub role: String,
+    pub role: Role,
+    /// Role label as written by the provider (for debugging)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_role: Option<String>,
     pub timestamp: Option<String>,
     pub model: Option<String>,
     pub content: Vec<DisplayContentBlock>,
+    /// Tool invocations made by this message, paired with their results
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+}
+
+/// A tool invocation matched to its result by call id
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    pub input_json: String,
+    /// None if no result was recorded (e.g. interrupted session)
+    pub result: Option<String>,
+    pub is_error: bool,
 }
 
 /// Unified content block enum covering both Claude and Codex types
@@ -120,3 +175,46 @@ pub struct PaginatedMessages {
     pub page_size: usize,
     pub has_more: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_provider_role_labels() {
+        let cases = [
+            ("user", Role::User),
+            ("human", Role::User),
+            ("assistant", Role::Assistant),
+            ("model", Role::Assistant),
+            ("ai", Role::Assistant),
+            ("system", Role::System),
+            ("developer", Role::System),
+            ("tool", Role::Tool),
+            ("function", Role::Tool),
+            ("function_call_output", Role::Tool),
+            ("tool_result", Role::Tool),
+        ];
+        for (raw, role) in cases {
+            assert_eq!(Role::from_raw(raw), Some(role), "{}", raw);
+        }
+    }
+
+    #[test]
+    fn role_labels_are_case_insensitive() {
+        assert_eq!(Role::from_raw("Human"), Some(Role::User));
+        assert_eq!(Role::from_raw("ASSISTANT"), Some(Role::Assistant));
+    }
+
+    #[test]
+    fn unknown_label_has_no_role() {
+        assert_eq!(Role::from_raw("narrator"), None);
+        assert_eq!(Role::from_raw(""), None);
+    }
+
+    #[test]
+    fn role_serializes_lowercase() {
+        assert_eq!(serde_json::to_string(&Role::Tool).unwrap(), "\"tool\"");
+        assert_eq!(Role::Assistant.as_str(), "assistant");
+    }
+}
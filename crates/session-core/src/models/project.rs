@@ -9,7 +9,7 @@ pub struct ProjectEntry {
     pub id: String,
     /// Full display path
     pub display_path: String,
-    /// Last path segment
+    /// Last path segment, or the project alias when one is set
     pub short_name: String,
     /// Number of session files
     pub session_count: usize,
@@ -17,4 +17,55 @@ pub struct ProjectEntry {
     pub last_modified: Option<String>,
     /// Codex: model provider (e.g. "openai")
     pub model_provider: Option<String>,
+    /// User-chosen project name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+}
+
+/// Whether a provider's data directory exists, so "not installed" can be told
+/// apart from "no sessions yet".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", content = "projectCount", rename_all = "camelCase")]
+pub enum DataDirStatus {
+    /// No home directory, or the CLI never created its data directory
+    Missing,
+    /// The directory exists but holds no projects
+    Empty,
+    /// The directory holds this many project directories
+    Present(usize),
+}
+
+/// Lightweight summary of a single project, served by the project-info cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectInfo {
+    /// "claude" or "codex"
+    pub source: String,
+    /// Claude: encoded_name, Codex: cwd
+    pub id: String,
+    /// Decoded project path
+    pub display_path: String,
+    /// Last path segment, or the project alias when one is set
+    pub short_name: String,
+    /// Number of session files
+    pub session_count: usize,
+    /// Last activity time (ISO 8601)
+    pub last_activity: Option<String>,
+    /// User-chosen project name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+}
+
+impl From<ProjectEntry> for ProjectInfo {
+    fn from(entry: ProjectEntry) -> Self {
+        Self {
+            source: entry.source,
+            id: entry.id,
+            display_path: entry.display_path,
+            short_name: entry.short_name,
+            session_count: entry.session_count,
+            last_activity: entry.last_modified,
+            alias: entry.alias,
+        }
+    }
 }
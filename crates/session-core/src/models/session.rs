@@ -49,4 +49,188 @@ pub struct SessionIndexEntry {
     // User metadata
     pub alias: Option<String>,
     pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub archived: bool,
+    /// Pinned message uuids (the UI shows the count and jumps to them)
+    #[serde(default)]
+    pub pinned_message_ids: Vec<String>,
+    /// Populated only by token-usage queries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_usage: Option<SessionTokenUsage>,
+    /// User rating, 1–5
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+    /// Hex SHA-256 of the file, only filled in when a caller asks for it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+}
+
+/// Token totals for a single session
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub total_tokens: u64,
+}
+
+/// Field order of a row in `CompactSessions`, using the verbose (camelCase) key names.
+/// New fields are only ever appended so older clients can keep indexing by position.
+pub const COMPACT_SESSION_FIELDS: &[&str] = &[
+    "source",
+    "sessionId",
+    "filePath",
+    "firstPrompt",
+    "messageCount",
+    "created",
+    "modified",
+    "gitBranch",
+    "projectPath",
+    "isSidechain",
+    "cwd",
+    "modelProvider",
+    "cliVersion",
+    "alias",
+    "tags",
+    "archived",
+    "pinnedMessageIds",
+    "tokenUsage",
+    "rating",
+    "contentHash",
+];
+
+/// Array-of-arrays form of a session list: key names are sent once in `fields`
+/// and each entry becomes a row of values in that order (absent values are `null`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactSessions {
+    pub fields: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+}
+
+impl CompactSessions {
+    pub fn from_entries(entries: &[SessionIndexEntry]) -> Result<Self, String> {
+        let rows = entries
+            .iter()
+            .map(|entry| {
+                let value = serde_json::to_value(entry)
+                    .map_err(|e| format!("Failed to serialize session: {}", e))?;
+                Ok(COMPACT_SESSION_FIELDS
+                    .iter()
+                    .map(|field| {
+                        value
+                            .get(*field)
+                            .cloned()
+                            .unwrap_or(serde_json::Value::Null)
+                    })
+                    .collect())
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Self {
+            fields: COMPACT_SESSION_FIELDS
+                .iter()
+                .map(|f| f.to_string())
+                .collect(),
+            rows,
+        })
+    }
+
+    /// Rebuild the verbose entries (inverse of `from_entries`)
+    pub fn into_entries(self) -> Result<Vec<SessionIndexEntry>, String> {
+        self.rows
+            .into_iter()
+            .map(|row| {
+                let object: serde_json::Map<String, serde_json::Value> = self
+                    .fields
+                    .iter()
+                    .cloned()
+                    .zip(row)
+                    .filter(|(_, v)| !v.is_null())
+                    .collect();
+                serde_json::from_value(serde_json::Value::Object(object))
+                    .map_err(|e| format!("Invalid compact session row: {}", e))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entries() -> Vec<SessionIndexEntry> {
+        let full = json!({
+            "source": "claude",
+            "sessionId": "s1",
+            "filePath": "/home/user/.claude/projects/p/s1.jsonl",
+            "firstPrompt": "fix the build",
+            "messageCount": 12,
+            "created": "2025-01-15T10:00:00Z",
+            "modified": "2025-01-15T11:00:00Z",
+            "gitBranch": "main",
+            "projectPath": "/home/user/p",
+            "isSidechain": false,
+            "cwd": null,
+            "modelProvider": null,
+            "cliVersion": null,
+            "alias": "build fix",
+            "tags": ["ci", "rust"],
+            "archived": true,
+            "pinnedMessageIds": ["u3"],
+            "tokenUsage": { "inputTokens": 10, "outputTokens": 5, "totalTokens": 15 },
+            "rating": 4,
+            "contentHash": "abc123",
+        });
+        let sparse = json!({
+            "source": "codex",
+            "sessionId": "s2",
+            "filePath": "/home/user/.codex/sessions/s2.jsonl",
+            "messageCount": 0,
+            "cwd": "/tmp",
+        });
+        vec![
+            serde_json::from_value(full).unwrap(),
+            serde_json::from_value(sparse).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn compact_form_round_trips() {
+        let original = entries();
+        let compact = CompactSessions::from_entries(&original).unwrap();
+        let wire = serde_json::to_string(&compact).unwrap();
+        let restored = serde_json::from_str::<CompactSessions>(&wire)
+            .unwrap()
+            .into_entries()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&restored).unwrap(),
+            serde_json::to_value(&original).unwrap()
+        );
+    }
+
+    #[test]
+    fn compact_fields_cover_every_serialized_key() {
+        let full = serde_json::to_value(&entries()[0]).unwrap();
+        for key in full.as_object().unwrap().keys() {
+            assert!(
+                COMPACT_SESSION_FIELDS.contains(&key.as_str()),
+                "{} is missing from COMPACT_SESSION_FIELDS",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn compact_rows_follow_the_field_order() {
+        let compact = CompactSessions::from_entries(&entries()).unwrap();
+        assert_eq!(compact.fields, COMPACT_SESSION_FIELDS);
+        let sparse = &compact.rows[1];
+        assert_eq!(sparse.len(), COMPACT_SESSION_FIELDS.len());
+        assert_eq!(sparse[1], json!("s2"));
+        assert_eq!(sparse[3], serde_json::Value::Null);
+        assert_eq!(sparse[10], json!("/tmp"));
+    }
 }
@@ -15,7 +15,7 @@ pub struct StatsCache {
     pub model_usage: HashMap<String, ModelUsageEntry>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ModelUsageEntry {
     #[serde(default)]
@@ -0,0 +1,121 @@
+use parking_lot::Mutex;
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+use std::sync::Arc;
+
+use crate::settings;
+
+/// Pool shared by every `map_bounded` call, with the width it was built for
+static POOL: Mutex<Option<(usize, Arc<ThreadPool>)>> = Mutex::new(None);
+
+/// The shared pool for `limit` threads, rebuilt only when the limit changes
+fn pool(limit: usize) -> Result<Arc<ThreadPool>, ThreadPoolBuildError> {
+    let mut cached = POOL.lock();
+    if let Some((width, pool)) = cached.as_ref() {
+        if *width == limit {
+            return Ok(pool.clone());
+        }
+    }
+    let pool = Arc::new(ThreadPoolBuilder::new().num_threads(limit).build()?);
+    *cached = Some((limit, pool.clone()));
+    Ok(pool)
+}
+
+/// Map `f` over `items` on a thread pool bounded by `settings::max_concurrency()`.
+///
+/// Results keep the input order. Falls back to a serial map if the pool
+/// can't be created.
+pub fn map_bounded<T, R, F>(items: Vec<T>, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync + Send,
+{
+    let limit = settings::max_concurrency();
+    if limit <= 1 || items.len() <= 1 {
+        return items.into_iter().map(f).collect();
+    }
+
+    match pool(limit) {
+        Ok(pool) => pool.install(|| items.into_par_iter().map(&f).collect()),
+        Err(e) => {
+            eprintln!(
+                "Warning: failed to build thread pool, running serially: {}",
+                e
+            );
+            items.into_iter().map(f).collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::claude;
+    use crate::test_support::{claude_user, TestHome};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    fn set_max_concurrency(limit: usize) {
+        settings::save_settings(&settings::SettingsFile {
+            max_concurrency: Some(limit),
+            ..Default::default()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn keeps_input_order_and_stays_within_the_limit() {
+        let _home = TestHome::new();
+        set_max_concurrency(3);
+        let active = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        let out = map_bounded((0..24).collect(), |i: u32| {
+            let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(5));
+            active.fetch_sub(1, Ordering::SeqCst);
+            i * 2
+        });
+
+        assert_eq!(out, (0..24).map(|i| i * 2).collect::<Vec<_>>());
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+    }
+
+    #[test]
+    fn pool_is_reused_until_the_limit_changes() {
+        // Other scans resize the shared pool; hold the lock they run under
+        let _home = TestHome::new();
+        let first = pool(3).unwrap();
+        assert!(Arc::ptr_eq(&first, &pool(3).unwrap()));
+        assert!(!Arc::ptr_eq(&first, &pool(5).unwrap()));
+    }
+
+    #[test]
+    fn project_scan_over_many_dirs_matches_the_serial_result() {
+        let home = TestHome::new();
+        for i in 0..200 {
+            let project = format!("-home-user-project-{:03}", i);
+            let lines: Vec<String> = (0..=i % 4)
+                .map(|n| claude_user(&format!("u{}", n), "hello"))
+                .collect();
+            home.claude_session(&project, "session", &lines);
+        }
+        let scan = || -> Vec<(String, usize)> {
+            claude::get_projects()
+                .unwrap()
+                .into_iter()
+                .map(|p| (p.id, p.session_count))
+                .collect()
+        };
+
+        set_max_concurrency(1);
+        let serial = scan();
+        set_max_concurrency(8);
+        let parallel = scan();
+
+        assert_eq!(serial.len(), 200);
+        assert_eq!(parallel, serial);
+    }
+}
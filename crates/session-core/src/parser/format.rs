@@ -0,0 +1,345 @@
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::parser::preview::truncate_chars;
+
+/// Longest excerpt of the offending content kept in a `ParseError`
+const SNIPPET_CHARS: usize = 120;
+
+/// Session file extensions Claude has written (`.json` in older CLI versions)
+pub const CLAUDE_SESSION_EXTENSIONS: &[&str] = &["jsonl", "json"];
+
+/// Session file extensions Codex has written (`.json` rollouts in early versions)
+pub const CODEX_SESSION_EXTENSIONS: &[&str] = &["jsonl", "json"];
+
+/// Files that live next to sessions but are never sessions themselves
+const NON_SESSION_FILES: &[&str] = &["sessions-index.json", ".session-viewer-meta.json"];
+
+/// Suffixes of in-flight atomic writes (`*.json.tmp`, `*.jsonl.tmp`) and lock files
+const NON_SESSION_SUFFIXES: &[&str] = &[".tmp", ".lock"];
+
+/// On-disk layout of a session file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionFormat {
+    /// One JSON record per line
+    Jsonl,
+    /// A single JSON document holding all records
+    Json,
+}
+
+/// Known session extensions for a source ("claude" or "codex")
+pub fn session_extensions(source: &str) -> &'static [&'static str] {
+    match source {
+        "codex" => CODEX_SESSION_EXTENSIONS,
+        _ => CLAUDE_SESSION_EXTENSIONS,
+    }
+}
+
+/// Whether a file name is viewer/CLI bookkeeping (index, metadata, temp or
+/// lock file) rather than a session, regardless of its extension.
+pub fn is_excluded_file(name: &str) -> bool {
+    name.starts_with('.')
+        || NON_SESSION_FILES.contains(&name)
+        || NON_SESSION_SUFFIXES.iter().any(|s| name.ends_with(s))
+}
+
+/// Whether `path` looks like a session file for `source`: a known extension
+/// and not one of the excluded bookkeeping files.
+pub fn is_session_file(path: &Path, source: &str) -> bool {
+    let name = match path.file_name().and_then(|n| n.to_str()) {
+        Some(n) => n,
+        None => return false,
+    };
+    if is_excluded_file(name) {
+        return false;
+    }
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|ext| session_extensions(source).contains(&ext))
+        .unwrap_or(false)
+}
+
+/// Whether the file's mtime is later than `since_epoch` (Unix seconds)
+pub fn modified_after(path: &Path, since_epoch: i64) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64 > since_epoch)
+        .unwrap_or(false)
+}
+
+/// Detect the format from the extension (anything but `.json` is treated as JSONL)
+pub fn detect_format(path: &Path) -> SessionFormat {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => SessionFormat::Json,
+        _ => SessionFormat::Jsonl,
+    }
+}
+
+/// Iterate the records of a session file as JSON lines, whatever its format.
+///
+/// JSONL files are streamed line by line. Single-JSON files are loaded and each
+/// record re-serialized compactly, so line-oriented parsers (and their
+/// `"type":"..."` pre-filters) work unchanged.
+pub fn read_records(path: &Path) -> io::Result<Box<dyn Iterator<Item = io::Result<String>>>> {
+    match detect_format(path) {
+        SessionFormat::Jsonl => {
+            let reader = BufReader::new(File::open(path)?);
+            Ok(Box::new(reader.lines()))
+        }
+        SessionFormat::Json => {
+            let content = fs::read_to_string(path)?;
+            let doc: Value = serde_json::from_str(&content)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            let lines: Vec<io::Result<String>> = json_records(doc)
+                .into_iter()
+                .map(|record| Ok(record.to_string()))
+                .collect();
+            Ok(Box::new(lines.into_iter()))
+        }
+    }
+}
+
+/// Count the lines of a file by scanning for newlines, without decoding or
+/// parsing anything. A final line without a trailing newline is counted.
+pub fn count_lines(path: &Path) -> io::Result<usize> {
+    let mut reader = BufReader::with_capacity(64 * 1024, File::open(path)?);
+    let mut lines = 0;
+    let mut last = b'\n';
+    loop {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            break;
+        }
+        lines += buf.iter().filter(|b| **b == b'\n').count();
+        last = buf[buf.len() - 1];
+        let len = buf.len();
+        reader.consume(len);
+    }
+    if last != b'\n' {
+        lines += 1;
+    }
+    Ok(lines)
+}
+
+/// Flatten a single-JSON session document into its records.
+///
+/// Accepts a bare array of records, `{ "messages": [...] }`, or the legacy Codex
+/// rollout `{ "session": {...}, "items": [...] }`, which is rewritten into the
+/// `session_meta` / `response_item` rows the Codex parser expects.
+fn json_records(doc: Value) -> Vec<Value> {
+    match doc {
+        Value::Array(records) => records,
+        Value::Object(mut obj) => {
+            if let Some(Value::Array(items)) = obj.remove("items") {
+                let mut records = Vec::with_capacity(items.len() + 1);
+                if let Some(session) = obj.remove("session") {
+                    records.push(serde_json::json!({
+                        "type": "session_meta",
+                        "timestamp": session.get("timestamp").cloned(),
+                        "payload": session,
+                    }));
+                }
+                for item in items {
+                    records.push(serde_json::json!({ "type": "response_item", "payload": item }));
+                }
+                records
+            } else if let Some(Value::Array(messages)) = obj.remove("messages") {
+                messages
+            } else {
+                vec![Value::Object(obj)]
+            }
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Location and excerpt of the first malformed record in a session file
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParseError {
+    /// 1-based line of the bad record (JSONL) or of the syntax error (JSON)
+    pub line: usize,
+    /// 1-based column reported by the JSON parser
+    pub column: usize,
+    /// Byte offset of the error from the start of the file
+    pub byte_offset: u64,
+    /// The start of the offending content
+    pub snippet: String,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {} (byte {}): {} near `{}`",
+            self.line, self.column, self.byte_offset, self.message, self.snippet
+        )
+    }
+}
+
+/// Find the first malformed record in a session file, if any.
+///
+/// Blank lines are ignored, as the parsers do. For JSONL the error points at
+/// the bad line, so every record before `line` parsed cleanly.
+pub fn find_parse_error(path: &Path) -> io::Result<Option<ParseError>> {
+    Ok(scan_parse_errors(path, true)?.into_iter().next())
+}
+
+/// Every malformed record in a session file, in file order.
+///
+/// JSONL lines are checked independently, so one bad line doesn't hide later
+/// ones. A single-JSON document has at most one (fatal) error.
+pub fn find_parse_errors(path: &Path) -> io::Result<Vec<ParseError>> {
+    scan_parse_errors(path, false)
+}
+
+fn scan_parse_errors(path: &Path, first_only: bool) -> io::Result<Vec<ParseError>> {
+    match detect_format(path) {
+        SessionFormat::Jsonl => {
+            let mut reader = BufReader::new(File::open(path)?);
+            let mut buf = String::new();
+            let mut line_no = 0;
+            let mut offset: u64 = 0;
+            let mut errors = Vec::new();
+            loop {
+                buf.clear();
+                let read = reader.read_line(&mut buf)?;
+                if read == 0 {
+                    return Ok(errors);
+                }
+                line_no += 1;
+                let trimmed = buf.trim();
+                if !trimmed.is_empty() {
+                    if let Err(e) = serde_json::from_str::<Value>(trimmed) {
+                        let leading = (buf.len() - buf.trim_start().len()) as u64;
+                        errors.push(ParseError {
+                            line: line_no,
+                            column: e.column(),
+                            byte_offset: offset + leading + e.column().saturating_sub(1) as u64,
+                            snippet: snippet(trimmed, 0),
+                            message: e.to_string(),
+                        });
+                        if first_only {
+                            return Ok(errors);
+                        }
+                    }
+                }
+                offset += read as u64;
+            }
+        }
+        SessionFormat::Json => {
+            let content = fs::read_to_string(path)?;
+            let e = match serde_json::from_str::<Value>(&content) {
+                Ok(_) => return Ok(Vec::new()),
+                Err(e) => e,
+            };
+            let line_start: usize = content
+                .split_inclusive('\n')
+                .take(e.line().saturating_sub(1))
+                .map(str::len)
+                .sum();
+            let byte_offset = (line_start + e.column().saturating_sub(1)).min(content.len());
+            Ok(vec![ParseError {
+                line: e.line(),
+                column: e.column(),
+                byte_offset: byte_offset as u64,
+                snippet: snippet(&content, line_start),
+                message: e.to_string(),
+            }])
+        }
+    }
+}
+
+/// Up to `SNIPPET_CHARS` characters of the line starting at byte `start`
+fn snippet(content: &str, start: usize) -> String {
+    let rest = content.get(start..).unwrap_or("");
+    let line = rest.lines().next().unwrap_or("").trim();
+    truncate_chars(line, SNIPPET_CHARS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    const MALFORMED_JSONL: &str = concat!(
+        "{\"type\":\"user\",\"uuid\":\"u1\"}\n",
+        "\n",
+        "{\"type\": oops, \"uuid\":\"u2\"}\n",
+        "{\"type\":\"assistant\",\"uuid\":\"a1\"}\n",
+        "{\"type\":\"user\" \"uuid\":\"u3\"}\n",
+    );
+
+    #[test]
+    fn jsonl_error_points_at_the_bad_line() {
+        let dir = temp_dir();
+        let path = dir.path().join("session.jsonl");
+        fs::write(&path, MALFORMED_JSONL).unwrap();
+
+        let err = find_parse_error(&path).unwrap().unwrap();
+        assert_eq!(err.line, 3);
+        assert_eq!(err.column, 10);
+        assert_eq!(&MALFORMED_JSONL[err.byte_offset as usize..][..4], "oops");
+        assert_eq!(err.snippet, "{\"type\": oops, \"uuid\":\"u2\"}");
+        assert!(err.to_string().starts_with("line 3, column 10"), "{}", err);
+    }
+
+    #[test]
+    fn every_bad_jsonl_line_is_reported() {
+        let dir = temp_dir();
+        let path = dir.path().join("session.jsonl");
+        fs::write(&path, MALFORMED_JSONL).unwrap();
+
+        let lines: Vec<usize> = find_parse_errors(&path)
+            .unwrap()
+            .iter()
+            .map(|e| e.line)
+            .collect();
+        assert_eq!(lines, [3, 5]);
+    }
+
+    #[test]
+    fn json_document_error_has_line_and_offset() {
+        let dir = temp_dir();
+        let path = dir.path().join("session.json");
+        let content = "{\n  \"messages\": [\n    {\"type\": \"user\",}\n  ]\n}\n";
+        fs::write(&path, content).unwrap();
+
+        let err = find_parse_error(&path).unwrap().unwrap();
+        assert_eq!(err.line, 3);
+        assert_eq!(&content[err.byte_offset as usize..][..1], "}");
+        assert_eq!(err.snippet, "{\"type\": \"user\",}");
+    }
+
+    #[test]
+    fn long_lines_are_cut_to_the_snippet_length() {
+        let dir = temp_dir();
+        let path = dir.path().join("session.jsonl");
+        let bad = format!("{{\"text\": \"{}\" oops}}", "日".repeat(200));
+        fs::write(&path, format!("{}\n", bad)).unwrap();
+
+        let err = find_parse_error(&path).unwrap().unwrap();
+        assert_eq!(err.snippet.chars().count(), SNIPPET_CHARS + 1);
+        assert!(err.snippet.ends_with('…'));
+    }
+
+    #[test]
+    fn clean_files_have_no_errors() {
+        let dir = temp_dir();
+        let jsonl = dir.path().join("clean.jsonl");
+        fs::write(&jsonl, "{\"type\":\"user\"}\n\n{\"type\":\"assistant\"}\n").unwrap();
+        let json = dir.path().join("clean.json");
+        fs::write(&json, "[{\"type\":\"user\"}]").unwrap();
+
+        assert!(find_parse_error(&jsonl).unwrap().is_none());
+        assert!(find_parse_errors(&json).unwrap().is_empty());
+    }
+}
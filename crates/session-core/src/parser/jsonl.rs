@@ -1,10 +1,16 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::collections::HashMap;
+use std::io;
 use std::path::Path;
 
 use crate::models::message::{
     ContentBlock, ContentValue, DisplayContentBlock, DisplayMessage, PaginatedMessages, RawRecord,
+    Role,
 };
+use crate::models::session::SessionTokenUsage;
+use crate::models::stats::ModelUsageEntry;
+use crate::parser::format::read_records;
+use crate::parser::preview::{build_preview, PreviewBlock};
+use crate::parser::tool_calls::link_tool_calls;
 
 /// Types of records to skip during parsing (large/irrelevant)
 const SKIP_TYPES: &[&str] = &["file-history-snapshot", "progress"];
@@ -17,12 +23,11 @@ pub fn parse_session_messages(
     page_size: usize,
     from_end: bool,
 ) -> Result<PaginatedMessages, String> {
-    let file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
-    let reader = BufReader::new(file);
+    let records = read_records(path).map_err(|e| format!("Failed to open file: {}", e))?;
 
     let mut all_messages: Vec<DisplayMessage> = Vec::new();
 
-    for line in reader.lines() {
+    for line in records {
         let line = match line {
             Ok(l) => l,
             Err(_) => continue,
@@ -59,28 +64,22 @@ pub fn parse_session_messages(
                 continue;
             }
 
-            // Fix: tool_result messages stored as role="user" should be treated as "tool"
-            let role = if msg.role == "user"
-                && !display_blocks.is_empty()
-                && display_blocks
-                    .iter()
-                    .all(|b| matches!(b, DisplayContentBlock::ToolResult { .. }))
-            {
-                "tool".to_string()
-            } else {
-                msg.role
-            };
+            let role = normalize_role(&msg.role, &record.record_type, &display_blocks);
 
             all_messages.push(DisplayMessage {
                 uuid: record.uuid,
                 role,
+                raw_role: Some(msg.role),
                 timestamp: record.timestamp,
                 model: msg.model,
                 content: display_blocks,
+                tool_calls: Vec::new(),
             });
         }
     }
 
+    link_tool_calls(&mut all_messages);
+
     let total = all_messages.len();
 
     if from_end {
@@ -125,11 +124,17 @@ pub fn parse_session_messages(
 
 /// Parse all messages from a JSONL file (no pagination, for search)
 pub fn parse_all_messages(path: &Path) -> Result<Vec<DisplayMessage>, String> {
-    let file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
-    let reader = BufReader::new(file);
+    let records = read_records(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    Ok(messages_from_records(records))
+}
+
+/// Convert Claude records (one JSON object each) into linked display messages
+pub fn messages_from_records(
+    records: impl Iterator<Item = io::Result<String>>,
+) -> Vec<DisplayMessage> {
     let mut messages: Vec<DisplayMessage> = Vec::new();
 
-    for line in reader.lines() {
+    for line in records {
         let line = match line {
             Ok(l) => l,
             Err(_) => continue,
@@ -159,36 +164,30 @@ pub fn parse_all_messages(path: &Path) -> Result<Vec<DisplayMessage>, String> {
             if display_blocks.is_empty() {
                 continue;
             }
-            let role = if msg.role == "user"
-                && !display_blocks.is_empty()
-                && display_blocks
-                    .iter()
-                    .all(|b| matches!(b, DisplayContentBlock::ToolResult { .. }))
-            {
-                "tool".to_string()
-            } else {
-                msg.role
-            };
+            let role = normalize_role(&msg.role, &record.record_type, &display_blocks);
 
             messages.push(DisplayMessage {
                 uuid: record.uuid,
                 role,
+                raw_role: Some(msg.role),
                 timestamp: record.timestamp,
                 model: msg.model,
                 content: display_blocks,
+                tool_calls: Vec::new(),
             });
         }
     }
 
-    Ok(messages)
+    link_tool_calls(&mut messages);
+
+    messages
 }
 
-/// Extract the first user prompt from a JSONL file
-pub fn extract_first_prompt(path: &Path) -> Option<String> {
-    let file = File::open(path).ok()?;
-    let reader = BufReader::new(file);
+/// Extract the first user prompt from a JSONL file, cut to `max_chars`
+pub fn extract_first_prompt(path: &Path, max_chars: usize) -> Option<String> {
+    let records = read_records(path).ok()?;
 
-    for line in reader.lines() {
+    for line in records {
         let line = match line {
             Ok(l) => l,
             Err(_) => continue,
@@ -206,21 +205,19 @@ pub fn extract_first_prompt(path: &Path) -> Option<String> {
         if record.record_type == "user" {
             if let Some(msg) = &record.message {
                 if msg.role == "user" {
-                    match &msg.content {
-                        ContentValue::Text(s) => {
-                            if !s.is_empty() {
-                                return Some(truncate_string(s, 200));
-                            }
-                        }
-                        ContentValue::Blocks(blocks) => {
-                            for block in blocks {
-                                if let ContentBlock::Text { text } = block {
-                                    if !text.is_empty() {
-                                        return Some(truncate_string(text, 200));
-                                    }
-                                }
-                            }
-                        }
+                    let blocks: Vec<PreviewBlock> = match &msg.content {
+                        ContentValue::Text(s) => vec![PreviewBlock::Text(s)],
+                        ContentValue::Blocks(blocks) => blocks
+                            .iter()
+                            .filter_map(|block| match block {
+                                ContentBlock::Text { text } => Some(PreviewBlock::Text(text)),
+                                ContentBlock::Image { .. } => Some(PreviewBlock::Image),
+                                _ => None,
+                            })
+                            .collect(),
+                    };
+                    if let Some(preview) = build_preview(&blocks, max_chars) {
+                        return Some(preview);
                     }
                 }
             }
@@ -231,10 +228,9 @@ pub fn extract_first_prompt(path: &Path) -> Option<String> {
 
 /// Extract session metadata (session_id, git_branch, etc.) from the first few lines
 pub fn extract_session_metadata(path: &Path) -> Option<(String, Option<String>, Option<String>)> {
-    let file = File::open(path).ok()?;
-    let reader = BufReader::new(file);
+    let records = read_records(path).ok()?;
 
-    for line in reader.lines().take(10) {
+    for line in records.take(10) {
         let line = match line {
             Ok(l) => l,
             Err(_) => continue,
@@ -256,6 +252,185 @@ pub fn extract_session_metadata(path: &Path) -> Option<(String, Option<String>,
     None
 }
 
+/// Distinct model ids used by assistant messages in a session, sorted.
+/// Claude's `<synthetic>` placeholder (locally generated messages) is skipped.
+pub fn extract_models(path: &Path) -> Vec<String> {
+    let records = match read_records(path) {
+        Ok(r) => r,
+        Err(_) => return Vec::new(),
+    };
+    let mut models = std::collections::BTreeSet::new();
+
+    for line in records {
+        let line = match line {
+            Ok(l) => l,
+            Err(_) => continue,
+        };
+        let trimmed = line.trim();
+        if trimmed.is_empty() || !trimmed.contains("\"model\"") {
+            continue;
+        }
+
+        let row: serde_json::Value = match serde_json::from_str(trimmed) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        if let Some(model) = row
+            .get("message")
+            .and_then(|m| m.get("model"))
+            .and_then(|v| v.as_str())
+        {
+            if !model.is_empty() && model != "<synthetic>" {
+                models.insert(model.to_string());
+            }
+        }
+    }
+    models.into_iter().collect()
+}
+
+/// Sum token usage across assistant messages in a session.
+/// Claude writes one record per content block with the same message id and
+/// usage, so usage is counted once per message id.
+pub fn extract_token_usage(path: &Path) -> Option<SessionTokenUsage> {
+    let records = read_records(path).ok()?;
+    let mut by_message: HashMap<String, (u64, u64)> = HashMap::new();
+
+    for (line_no, line) in records.enumerate() {
+        let line = match line {
+            Ok(l) => l,
+            Err(_) => continue,
+        };
+        let trimmed = line.trim();
+        if trimmed.is_empty() || !trimmed.contains("\"usage\"") {
+            continue;
+        }
+
+        let row: serde_json::Value = match serde_json::from_str(trimmed) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let msg = match row.get("message") {
+            Some(m) => m,
+            None => continue,
+        };
+        let usage = match msg.get("usage") {
+            Some(u) => u,
+            None => continue,
+        };
+
+        let field = |name: &str| usage.get(name).and_then(|v| v.as_u64()).unwrap_or(0);
+        let input = field("input_tokens")
+            + field("cache_read_input_tokens")
+            + field("cache_creation_input_tokens");
+        let output = field("output_tokens");
+
+        let key = msg
+            .get("id")
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .unwrap_or_else(|| format!("line:{}", line_no));
+        by_message.insert(key, (input, output));
+    }
+
+    if by_message.is_empty() {
+        return None;
+    }
+
+    let (input_tokens, output_tokens) = by_message
+        .values()
+        .fold((0, 0), |acc, (i, o)| (acc.0 + i, acc.1 + o));
+    Some(SessionTokenUsage {
+        input_tokens,
+        output_tokens,
+        total_tokens: input_tokens + output_tokens,
+    })
+}
+
+/// Token usage per model across assistant messages, with cache reads and
+/// writes kept apart from plain input. Like `extract_token_usage`, usage is
+/// counted once per message id.
+pub fn extract_token_usage_by_model(path: &Path) -> HashMap<String, ModelUsageEntry> {
+    let records = match read_records(path) {
+        Ok(r) => r,
+        Err(_) => return HashMap::new(),
+    };
+    let mut by_message: HashMap<String, (String, ModelUsageEntry)> = HashMap::new();
+
+    for (line_no, line) in records.enumerate() {
+        let line = match line {
+            Ok(l) => l,
+            Err(_) => continue,
+        };
+        let trimmed = line.trim();
+        if trimmed.is_empty() || !trimmed.contains("\"usage\"") {
+            continue;
+        }
+
+        let row: serde_json::Value = match serde_json::from_str(trimmed) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let msg = match row.get("message") {
+            Some(m) => m,
+            None => continue,
+        };
+        let usage = match msg.get("usage") {
+            Some(u) => u,
+            None => continue,
+        };
+        let model = msg.get("model").and_then(|v| v.as_str()).unwrap_or("");
+        if model.is_empty() || model == "<synthetic>" {
+            continue;
+        }
+
+        let field = |name: &str| usage.get(name).and_then(|v| v.as_u64()).unwrap_or(0);
+        let entry = ModelUsageEntry {
+            input_tokens: field("input_tokens"),
+            output_tokens: field("output_tokens"),
+            cache_read_input_tokens: field("cache_read_input_tokens"),
+            cache_creation_input_tokens: field("cache_creation_input_tokens"),
+        };
+
+        let key = msg
+            .get("id")
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .unwrap_or_else(|| format!("line:{}", line_no));
+        by_message.insert(key, (model.to_string(), entry));
+    }
+
+    let mut by_model: HashMap<String, ModelUsageEntry> = HashMap::new();
+    for (model, usage) in by_message.into_values() {
+        let total = by_model.entry(model).or_default();
+        total.input_tokens += usage.input_tokens;
+        total.output_tokens += usage.output_tokens;
+        total.cache_read_input_tokens += usage.cache_read_input_tokens;
+        total.cache_creation_input_tokens += usage.cache_creation_input_tokens;
+    }
+    by_model
+}
+
+/// Map a Claude role to its canonical form.
+/// tool_result messages are stored as role="user" but are treated as tool output.
+fn normalize_role(raw: &str, record_type: &str, blocks: &[DisplayContentBlock]) -> Role {
+    let role = Role::from_raw(raw).unwrap_or(if record_type == "assistant" {
+        Role::Assistant
+    } else {
+        Role::User
+    });
+
+    if role == Role::User
+        && !blocks.is_empty()
+        && blocks
+            .iter()
+            .all(|b| matches!(b, DisplayContentBlock::ToolResult { .. }))
+    {
+        Role::Tool
+    } else {
+        role
+    }
+}
+
 fn convert_content(content: &ContentValue) -> Vec<DisplayContentBlock> {
     match content {
         ContentValue::Text(s) => {
@@ -321,7 +496,7 @@ fn convert_content(content: &ContentValue) -> Vec<DisplayContentBlock> {
                             is_error: is_error.unwrap_or(false),
                         });
                     }
-                    ContentBlock::Unknown => {}
+                    ContentBlock::Image { .. } | ContentBlock::Unknown => {}
                 }
             }
             result
@@ -329,11 +504,70 @@ fn convert_content(content: &ContentValue) -> Vec<DisplayContentBlock> {
     }
 }
 
-fn truncate_string(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
-    } else {
-        let truncated: String = s.chars().take(max_len).collect();
-        format!("{}...", truncated)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::message::Role;
+    use serde_json::json;
+
+    fn roles(records: &[serde_json::Value]) -> Vec<(Role, Option<String>)> {
+        messages_from_records(records.iter().map(|r| Ok(r.to_string())))
+            .into_iter()
+            .map(|m| (m.role, m.raw_role))
+            .collect()
+    }
+
+    fn record(record_type: &str, role: &str, content: serde_json::Value) -> serde_json::Value {
+        json!({
+            "type": record_type,
+            "uuid": format!("{}-{}", record_type, role),
+            "message": { "role": role, "content": content },
+        })
+    }
+
+    #[test]
+    fn maps_claude_roles_and_keeps_the_raw_label() {
+        let records = [
+            record("user", "user", json!("hi")),
+            record("user", "human", json!("hello again")),
+            record(
+                "assistant",
+                "assistant",
+                json!([{ "type": "text", "text": "hey" }]),
+            ),
+        ];
+        assert_eq!(
+            roles(&records),
+            vec![
+                (Role::User, Some("user".to_string())),
+                (Role::User, Some("human".to_string())),
+                (Role::Assistant, Some("assistant".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn user_record_of_only_tool_results_is_a_tool_message() {
+        let result = json!([{ "type": "tool_result", "tool_use_id": "t1", "content": "ok" }]);
+        let records = [record("user", "user", result)];
+        assert_eq!(
+            roles(&records),
+            vec![(Role::Tool, Some("user".to_string()))]
+        );
+    }
+
+    #[test]
+    fn unknown_label_falls_back_to_the_record_type() {
+        let records = [
+            record("assistant", "bot", json!("from the bot")),
+            record("user", "person", json!("from a person")),
+        ];
+        assert_eq!(
+            roles(&records),
+            vec![
+                (Role::Assistant, Some("bot".to_string())),
+                (Role::User, Some("person".to_string())),
+            ]
+        );
     }
 }
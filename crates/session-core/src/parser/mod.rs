@@ -1,2 +1,5 @@
+pub mod format;
 pub mod jsonl;
 pub mod path_encoder;
+pub mod preview;
+pub mod tool_calls;
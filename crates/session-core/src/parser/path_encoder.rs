@@ -32,6 +32,26 @@ pub fn decode_project_path(encoded: &str) -> String {
     }
 }
 
+/// Encode a filesystem path into a Claude project directory name, the way the
+/// CLI does: every character other than an ASCII letter or digit becomes `-`
+/// (`/home/me/app` → `-home-me-app`, `C:\Users\me` → `C--Users-me`).
+/// Trailing separators are dropped, except on a root (`/`, `C:\`).
+///
+/// The inverse of `decode_project_path` only for paths made of letters,
+/// digits and separators; `-`, `.` and `_` in names don't survive a decode.
+pub fn encode_project_id(path: &str) -> String {
+    let path = path.trim();
+    let trimmed = path.trim_end_matches(['/', '\\']);
+    let path = if trimmed.is_empty() || trimmed.ends_with(':') {
+        &path[..(trimmed.len() + 1).min(path.len())]
+    } else {
+        trimmed
+    };
+    path.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
 /// Extract the last path segment as a short name
 pub fn short_name_from_path(path: &str) -> String {
     let path = path.trim_end_matches(['/', '\\']);
@@ -41,3 +61,49 @@ pub fn short_name_from_path(path: &str) -> String {
         path.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(not(windows))]
+    fn unix_paths_round_trip() {
+        for path in ["/home/me/app", "/Users/Me/Code/Project2", "/srv", "/"] {
+            assert_eq!(decode_project_path(&encode_project_id(path)), path);
+        }
+        assert_eq!(
+            decode_project_path(&encode_project_id("/home/me/app/")),
+            "/home/me/app"
+        );
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn windows_paths_round_trip() {
+        for path in [r"C:\Users\me\app", r"D:\work", r"C:\"] {
+            assert_eq!(decode_project_path(&encode_project_id(path)), path);
+        }
+        assert_eq!(
+            decode_project_path(&encode_project_id(r"C:\Users\me\app\")),
+            r"C:\Users\me\app"
+        );
+    }
+
+    #[test]
+    fn encoding_matches_the_cli() {
+        for (path, encoded) in [
+            ("/home/me/app", "-home-me-app"),
+            ("/home/me/app//", "-home-me-app"),
+            ("  /home/me/app  ", "-home-me-app"),
+            ("/home/me/my-app.v2_old", "-home-me-my-app-v2-old"),
+            ("/home/me/プロジェクト", "-home-me-------"),
+            ("/", "-"),
+            (r"C:\Users\me", "C--Users-me"),
+            (r"C:\Users\me\", "C--Users-me"),
+            (r"C:\", "C--"),
+        ] {
+            assert_eq!(encode_project_id(path), encoded, "{}", path);
+        }
+    }
+}
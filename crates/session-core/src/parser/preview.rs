@@ -0,0 +1,180 @@
+/// A content block reduced to what matters for a session-list preview
+pub enum PreviewBlock<'a> {
+    Text(&'a str),
+    Image,
+}
+
+/// Build a short, readable preview from a message's content blocks.
+///
+/// The leading block decides the shape:
+/// - image → `[image]` (followed by any caption text)
+/// - fenced code or JSON → `` ```lang…``` `` (followed by any trailing prose)
+/// - prose → markdown noise stripped and whitespace collapsed
+///
+/// Returns None when there is no non-empty text or image block.
+pub fn build_preview(blocks: &[PreviewBlock], max_chars: usize) -> Option<String> {
+    let mut blocks = blocks.iter().filter(|b| match b {
+        PreviewBlock::Text(t) => !t.trim().is_empty(),
+        PreviewBlock::Image => true,
+    });
+
+    match blocks.next()? {
+        PreviewBlock::Image => {
+            let caption = blocks.find_map(|b| match b {
+                PreviewBlock::Text(t) => Some(text_preview(t, max_chars)),
+                PreviewBlock::Image => None,
+            });
+            Some(match caption {
+                Some(c) if !c.is_empty() => format!("[image] {}", c),
+                _ => "[image]".to_string(),
+            })
+        }
+        PreviewBlock::Text(text) => Some(text_preview(text, max_chars)),
+    }
+}
+
+fn text_preview(text: &str, max_chars: usize) -> String {
+    let trimmed = text.trim();
+
+    if let Some(rest) = trimmed.strip_prefix("```") {
+        let lang = rest.lines().next().unwrap_or("").trim();
+        let fence = format!("```{}…```", lang);
+        // Append any prose that follows the closing fence
+        let after = rest
+            .find("```")
+            .map(|end| strip_markdown(&rest[end + 3..]))
+            .unwrap_or_default();
+        return if after.is_empty() {
+            fence
+        } else {
+            truncate_chars(&format!("{} {}", fence, after), max_chars)
+        };
+    }
+
+    if (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(trimmed).is_ok()
+    {
+        return "```json…```".to_string();
+    }
+
+    truncate_chars(&strip_markdown(trimmed), max_chars)
+}
+
+/// Remove common markdown syntax and collapse whitespace into single spaces
+fn strip_markdown(text: &str) -> String {
+    let mut out = String::new();
+    for line in text.lines() {
+        let mut line = line.trim();
+        line = line.trim_start_matches('#').trim_start();
+        line = line.trim_start_matches('>').trim_start();
+        for bullet in ["- ", "* ", "+ "] {
+            if let Some(rest) = line.strip_prefix(bullet) {
+                line = rest;
+                break;
+            }
+        }
+        if line.is_empty() {
+            continue;
+        }
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(&strip_links(line));
+    }
+
+    let out = out.replace("**", "").replace("__", "").replace('`', "");
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Replace `[label](url)` with `label`
+fn strip_links(line: &str) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+    while let Some(open) = rest.find('[') {
+        let after_open = &rest[open + 1..];
+        let (close, url_end) = match after_open.find("](") {
+            Some(c) => match after_open[c + 2..].find(')') {
+                Some(u) => (c, c + 2 + u),
+                None => break,
+            },
+            None => break,
+        };
+        out.push_str(&rest[..open]);
+        out.push_str(&after_open[..close]);
+        rest = &after_open[url_end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Cut `s` to at most `max_chars` characters (never inside a multibyte
+/// character), appending "…" when anything was cut
+pub fn truncate_chars(s: &str, max_chars: usize) -> String {
+    match s.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", &s[..end]),
+        None => s.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncation_never_splits_a_multibyte_character() {
+        assert_eq!(truncate_chars("日本語のテキスト", 3), "日本語…");
+        assert_eq!(truncate_chars("a😀b", 1), "a…");
+        assert_eq!(truncate_chars("a😀b", 2), "a😀…");
+        assert_eq!(truncate_chars("héllo", 5), "héllo");
+        assert_eq!(truncate_chars("héllo", 0), "…");
+    }
+
+    #[test]
+    fn text_preview_strips_markdown_and_truncates() {
+        let text = "## Plan\n\n- read the **config**\n- see [the docs](https://example.com)\n> `cargo test`";
+        assert_eq!(
+            build_preview(&[PreviewBlock::Text(text)], 200).as_deref(),
+            Some("Plan read the config see the docs cargo test")
+        );
+        assert_eq!(
+            build_preview(&[PreviewBlock::Text("one two three")], 7).as_deref(),
+            Some("one two…")
+        );
+        assert_eq!(build_preview(&[PreviewBlock::Text("  \n ")], 10), None);
+    }
+
+    #[test]
+    fn code_preview_shows_the_fence_language_and_trailing_prose() {
+        let code = "```rust\nfn main() {}\n```";
+        assert_eq!(
+            build_preview(&[PreviewBlock::Text(code)], 100).as_deref(),
+            Some("```rust…```")
+        );
+        let with_prose = "```sh\ncargo build\n```\nthen **run** it";
+        assert_eq!(
+            build_preview(&[PreviewBlock::Text(with_prose)], 100).as_deref(),
+            Some("```sh…``` then run it")
+        );
+        assert_eq!(
+            build_preview(&[PreviewBlock::Text(r#"{"key": [1, 2]}"#)], 100).as_deref(),
+            Some("```json…```")
+        );
+    }
+
+    #[test]
+    fn image_preview_carries_the_caption() {
+        assert_eq!(
+            build_preview(&[PreviewBlock::Image], 100).as_deref(),
+            Some("[image]")
+        );
+        let blocks = [
+            PreviewBlock::Text(" "),
+            PreviewBlock::Image,
+            PreviewBlock::Text("what is **this**?"),
+        ];
+        assert_eq!(
+            build_preview(&blocks, 100).as_deref(),
+            Some("[image] what is this?")
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+use crate::models::message::{DisplayContentBlock, DisplayMessage, ToolCall};
+
+/// Pair every tool invocation with its result (matched by call id) and attach
+/// the resulting `ToolCall`s to the message that made the invocation.
+///
+/// Works for both Claude (`tool_use`/`tool_result`) and Codex
+/// (`function_call`/`function_call_output`) blocks.
+pub fn link_tool_calls(messages: &mut [DisplayMessage]) {
+    let mut results: HashMap<String, (String, bool)> = HashMap::new();
+    for msg in messages.iter() {
+        for block in &msg.content {
+            match block {
+                DisplayContentBlock::ToolResult {
+                    tool_use_id,
+                    content,
+                    is_error,
+                } => {
+                    results.insert(tool_use_id.clone(), (content.clone(), *is_error));
+                }
+                DisplayContentBlock::FunctionCallOutput { call_id, output } => {
+                    results.insert(call_id.clone(), (output.clone(), false));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    for msg in messages.iter_mut() {
+        let mut calls = Vec::new();
+        for block in &msg.content {
+            let (id, name, input_json) = match block {
+                DisplayContentBlock::ToolUse { id, name, input } => (id, name, input),
+                DisplayContentBlock::FunctionCall {
+                    name,
+                    arguments,
+                    call_id,
+                } => (call_id, name, arguments),
+                _ => continue,
+            };
+            let result = results.get(id);
+            calls.push(ToolCall {
+                id: id.clone(),
+                name: name.clone(),
+                input_json: input_json.clone(),
+                result: result.map(|(content, _)| content.clone()),
+                is_error: result.map(|(_, is_error)| *is_error).unwrap_or(false),
+            });
+        }
+        msg.tool_calls = calls;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::models::message::DisplayMessage;
+    use crate::provider::{claude, codex};
+    use crate::test_support::{claude_user, codex_meta, temp_dir, write_lines};
+
+    fn record(kind: &str, uuid: &str, content: serde_json::Value) -> String {
+        json!({
+            "type": kind,
+            "uuid": uuid,
+            "message": { "role": kind, "content": content },
+        })
+        .to_string()
+    }
+
+    fn response_item(payload: serde_json::Value) -> String {
+        json!({ "timestamp": "2025-01-15T10:00:00Z", "type": "response_item", "payload": payload })
+            .to_string()
+    }
+
+    fn calls(messages: &[DisplayMessage]) -> Vec<(String, String, Option<String>, bool)> {
+        messages
+            .iter()
+            .flat_map(|m| &m.tool_calls)
+            .map(|c| {
+                (
+                    c.name.clone(),
+                    c.input_json.clone(),
+                    c.result.clone(),
+                    c.is_error,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn claude_tool_uses_are_paired_with_their_results() {
+        let dir = temp_dir();
+        let path = write_lines(
+            &dir.path().join("session.jsonl"),
+            &[
+                claude_user("u1", "check the build"),
+                record(
+                    "assistant",
+                    "a1",
+                    json!([
+                        { "type": "tool_use", "id": "t1", "name": "Read", "input": { "path": "a.rs" } },
+                        { "type": "tool_use", "id": "t2", "name": "Bash", "input": { "command": "make" } },
+                    ]),
+                ),
+                record(
+                    "user",
+                    "r1",
+                    json!([
+                        { "type": "tool_result", "tool_use_id": "t1", "content": "fn main() {}" },
+                        { "type": "tool_result", "tool_use_id": "t2", "content": "make: *** no rule", "is_error": true },
+                    ]),
+                ),
+            ],
+        );
+
+        let messages = claude::parse_all_messages(&path).unwrap();
+        let calls = calls(&messages);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].0, "Read");
+        let input: serde_json::Value = serde_json::from_str(&calls[0].1).unwrap();
+        assert_eq!(input, json!({ "path": "a.rs" }));
+        assert_eq!(calls[0].2.as_deref(), Some("fn main() {}"));
+        assert!(!calls[0].3);
+        assert_eq!(calls[1].0, "Bash");
+        assert_eq!(calls[1].2.as_deref(), Some("make: *** no rule"));
+        assert!(calls[1].3);
+        // The calls hang off the message that made them
+        assert_eq!(messages[1].tool_calls.len(), 2);
+        assert!(messages[2].tool_calls.is_empty());
+    }
+
+    #[test]
+    fn codex_function_calls_are_paired_by_call_id() {
+        let dir = temp_dir();
+        let path = write_lines(
+            &dir.path().join("rollout-2025-01-15T10-00-00-c1.jsonl"),
+            &[
+                codex_meta("c1", "/home/user/project"),
+                response_item(json!({
+                    "type": "function_call",
+                    "name": "shell",
+                    "arguments": "{\"command\":[\"ls\"]}",
+                    "call_id": "call_1",
+                })),
+                response_item(json!({
+                    "type": "function_call_output",
+                    "call_id": "call_1",
+                    "output": "Cargo.toml",
+                })),
+                response_item(json!({
+                    "type": "function_call",
+                    "name": "shell",
+                    "arguments": "{}",
+                    "call_id": "call_2",
+                })),
+            ],
+        );
+
+        let messages = codex::parse_all_messages(&path).unwrap();
+        let calls = calls(&messages);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].0, "shell");
+        let input: serde_json::Value = serde_json::from_str(&calls[0].1).unwrap();
+        assert_eq!(input, json!({ "command": ["ls"] }));
+        assert_eq!(calls[0].2.as_deref(), Some("Cargo.toml"));
+        assert!(!calls[0].3);
+        // Interrupted before a result was recorded
+        assert_eq!(calls[1].2, None);
+        assert!(!calls[1].3);
+    }
+}
@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::data_dir;
+use crate::models::project::ProjectEntry;
+use crate::project_info;
+
+/// User-chosen project names (`.session-viewer-project-aliases.json` in the data directory)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectAliasesFile {
+    pub version: u32,
+    /// source → project_id → alias
+    #[serde(default)]
+    pub aliases: HashMap<String, HashMap<String, String>>,
+}
+
+impl Default for ProjectAliasesFile {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            aliases: HashMap::new(),
+        }
+    }
+}
+
+fn aliases_path() -> Result<PathBuf, String> {
+    Ok(data_dir::data_dir()?.join(".session-viewer-project-aliases.json"))
+}
+
+/// Load project aliases; returns an empty store if the file is missing or invalid
+pub fn load_project_aliases() -> ProjectAliasesFile {
+    let path = match aliases_path() {
+        Ok(p) => p,
+        Err(_) => return ProjectAliasesFile::default(),
+    };
+    fs::read_to_string(&path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_project_aliases(file: &ProjectAliasesFile) -> Result<(), String> {
+    let path = aliases_path()?;
+    let json = serde_json::to_string_pretty(file)
+        .map_err(|e| format!("Failed to serialize project aliases: {}", e))?;
+
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, &json)
+        .map_err(|e| format!("Failed to write project aliases tmp: {}", e))?;
+    fs::rename(&tmp_path, &path)
+        .map_err(|e| format!("Failed to rename project aliases file: {}", e))?;
+    Ok(())
+}
+
+/// Alias of one project, if set
+pub fn get_project_alias(source: &str, project_id: &str) -> Option<String> {
+    load_project_aliases()
+        .aliases
+        .get(source)
+        .and_then(|by_id| by_id.get(project_id))
+        .cloned()
+}
+
+/// Set (or clear, with `None` or a blank name) a project's alias.
+/// Only the alias store is written; the project directory is left untouched.
+pub fn set_project_alias(
+    source: &str,
+    project_id: &str,
+    alias: Option<String>,
+) -> Result<(), String> {
+    if !matches!(source, "claude" | "codex") {
+        return Err(format!("Unknown source: {}", source));
+    }
+
+    let mut file = load_project_aliases();
+    let by_id = file.aliases.entry(source.to_string()).or_default();
+    match alias
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty())
+    {
+        Some(alias) => {
+            by_id.insert(project_id.to_string(), alias);
+        }
+        None => {
+            by_id.remove(project_id);
+        }
+    }
+    file.aliases.retain(|_, by_id| !by_id.is_empty());
+
+    save_project_aliases(&file)?;
+    project_info::clear_cache();
+    Ok(())
+}
+
+/// Fill in `alias` on each project that has one, and show it as the short name
+pub fn apply_aliases(source: &str, projects: &mut [ProjectEntry]) {
+    let file = load_project_aliases();
+    let by_id = match file.aliases.get(source) {
+        Some(m) => m,
+        None => return,
+    };
+    for project in projects.iter_mut() {
+        if let Some(alias) = by_id.get(&project.id) {
+            project.short_name = alias.clone();
+            project.alias = Some(alias.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::claude;
+    use crate::test_support::{claude_user, TestHome};
+
+    const PROJECT: &str = "-home-user-project";
+
+    #[test]
+    fn aliases_round_trip_per_source() {
+        let _home = TestHome::new();
+        assert_eq!(get_project_alias("claude", PROJECT), None);
+
+        set_project_alias("claude", PROJECT, Some("  Main app ".to_string())).unwrap();
+        assert_eq!(
+            get_project_alias("claude", PROJECT).as_deref(),
+            Some("Main app")
+        );
+        assert_eq!(get_project_alias("codex", PROJECT), None);
+
+        set_project_alias("claude", PROJECT, Some(" ".to_string())).unwrap();
+        assert_eq!(get_project_alias("claude", PROJECT), None);
+        assert!(load_project_aliases().aliases.is_empty());
+
+        let err = set_project_alias("gemini", PROJECT, Some("x".to_string())).unwrap_err();
+        assert_eq!(err, "Unknown source: gemini");
+    }
+
+    #[test]
+    fn listing_shows_the_alias_and_leaves_the_directory_alone() {
+        let home = TestHome::new();
+        let dir = home.claude_project(PROJECT);
+        home.claude_session(PROJECT, "s1", &[claude_user("u1", "hello")]);
+        set_project_alias("claude", PROJECT, Some("Main app".to_string())).unwrap();
+
+        let project = claude::get_projects().unwrap().remove(0);
+        assert_eq!(project.id, PROJECT);
+        assert_eq!(project.short_name, "Main app");
+        assert_eq!(project.alias.as_deref(), Some("Main app"));
+        assert_eq!(project.display_path, "/home/user/project");
+        assert!(dir.join("s1.jsonl").is_file());
+    }
+}
@@ -0,0 +1,164 @@
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use crate::metadata;
+use crate::models::project::ProjectInfo;
+use crate::parser::path_encoder::get_projects_dir;
+use crate::provider::{claude, codex};
+use crate::settings;
+
+/// How long a project's info is reused before it is rebuilt
+pub const PROJECT_INFO_TTL: Duration = Duration::from_secs(30);
+
+/// Cached project info keyed by "source\nproject_id"
+fn cache() -> &'static Mutex<HashMap<String, (Instant, ProjectInfo)>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, (Instant, ProjectInfo)>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn cache_key(source: &str, project_id: &str) -> String {
+    format!("{}\n{}", source, project_id)
+}
+
+/// Decoded path, session count and last activity for one project.
+///
+/// Results are memoized for `PROJECT_INFO_TTL`, and dropped early by
+/// `invalidate_path` when the file watcher sees the project change.
+pub fn get_project_info(source: &str, project_id: &str) -> Result<ProjectInfo, String> {
+    let key = cache_key(source, project_id);
+    if let Some((fetched_at, info)) = cache().lock().get(&key) {
+        if fetched_at.elapsed() < PROJECT_INFO_TTL {
+            return Ok(info.clone());
+        }
+    }
+
+    let entry = match source {
+        "claude" => claude::get_project(project_id)?,
+        // Codex sessions are stored by date, so a project is found by scanning them
+        "codex" => codex::get_project(project_id)?,
+        _ => return Err(format!("Unknown source: {}", source)),
+    };
+
+    let info = ProjectInfo::from(entry);
+    cache().lock().insert(key, (Instant::now(), info.clone()));
+    Ok(info)
+}
+
+/// Drop cached info affected by a change at `path`.
+///
+/// Paths under the Claude projects directory invalidate their project only.
+/// Codex files can't be mapped to a cwd without reading them, so any change
+/// under the Codex sessions directory invalidates every Codex project.
+pub fn invalidate_path(path: &Path) {
+    if let Some(rel) =
+        get_projects_dir().and_then(|dir| path.strip_prefix(dir).ok().map(Path::to_path_buf))
+    {
+        match rel.components().next() {
+            Some(Component::Normal(name)) => {
+                let key = cache_key("claude", &name.to_string_lossy());
+                cache().lock().remove(&key);
+            }
+            // The projects directory itself changed
+            _ => cache().lock().retain(|k, _| !k.starts_with("claude\n")),
+        }
+        return;
+    }
+
+    let in_codex = codex::get_sessions_dir()
+        .map(|dir| path.starts_with(dir))
+        .unwrap_or(false);
+    if in_codex {
+        cache().lock().retain(|k, _| !k.starts_with("codex\n"));
+    }
+}
+
+/// Drop all cached project info
+pub fn clear_cache() {
+    cache().lock().clear();
+}
+
+/// Cheap change detector for a project's session list: the newest mtime, file
+/// count and total size of the files it is built from, including the metadata
+/// file, so both new messages and alias/tag edits change it. The settings that
+/// shape the listing are part of it too, so changing them invalidates the ETag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionsFingerprint {
+    pub newest_mtime_nanos: u128,
+    pub file_count: usize,
+    pub total_bytes: u64,
+    /// `settings::preview_max_chars()` when fingerprinted
+    pub preview_max_chars: usize,
+    /// `settings::lowercase_tags()` when fingerprinted
+    pub lowercase_tags: bool,
+}
+
+impl SessionsFingerprint {
+    /// Strong ETag; `variant` distinguishes representations of the same list
+    pub fn etag(&self, variant: &str) -> String {
+        format!(
+            "\"{:x}-{:x}-{:x}-p{:x}{}-{}\"",
+            self.newest_mtime_nanos,
+            self.file_count,
+            self.total_bytes,
+            self.preview_max_chars,
+            if self.lowercase_tags { "l" } else { "" },
+            variant
+        )
+    }
+
+    /// Newest mtime in whole seconds, for `Last-Modified`
+    pub fn last_modified_secs(&self) -> i64 {
+        (self.newest_mtime_nanos / 1_000_000_000) as i64
+    }
+}
+
+/// Fingerprint the files behind `get_sessions` for a project without parsing
+/// them. For Claude that is every file in the project directory; Codex stores
+/// sessions by date, so any Codex session file counts.
+pub fn sessions_fingerprint(source: &str, project_id: &str) -> Result<SessionsFingerprint, String> {
+    let mut files: Vec<PathBuf> = match source {
+        "claude" => {
+            let dir = get_projects_dir()
+                .ok_or("Could not find Claude projects directory")?
+                .join(project_id);
+            fs::read_dir(&dir)
+                .map_err(|e| format!("Failed to read project dir: {}", e))?
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_file())
+                .collect()
+        }
+        "codex" => {
+            let mut files = codex::scan_all_session_files();
+            files.extend(metadata::metadata_path("codex", project_id, None));
+            files
+        }
+        _ => return Err(format!("Unknown source: {}", source)),
+    };
+    files.sort();
+    files.dedup();
+
+    let mut fingerprint = SessionsFingerprint {
+        newest_mtime_nanos: 0,
+        file_count: 0,
+        total_bytes: 0,
+        preview_max_chars: settings::preview_max_chars(),
+        lowercase_tags: settings::lowercase_tags(),
+    };
+    for meta in files.iter().filter_map(|p| fs::metadata(p).ok()) {
+        let mtime = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        fingerprint.newest_mtime_nanos = fingerprint.newest_mtime_nanos.max(mtime);
+        fingerprint.file_count += 1;
+        fingerprint.total_bytes += meta.len();
+    }
+    Ok(fingerprint)
+}
@@ -1,11 +1,19 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::models::message::{DisplayMessage, PaginatedMessages};
-use crate::models::project::ProjectEntry;
+use crate::models::project::{DataDirStatus, ProjectEntry};
 use crate::models::session::{SessionIndexEntry, SessionsIndex, SessionsIndexFileEntry};
+use crate::parallel;
+use crate::parser::format::{
+    detect_format, is_session_file, modified_after, read_records, SessionFormat,
+};
 use crate::parser::jsonl as claude_parser;
 use crate::parser::path_encoder::{decode_project_path, get_projects_dir, short_name_from_path};
+use crate::project_alias;
+use crate::provider::timestamps;
+use crate::session_cache;
+use crate::settings;
 
 /// Get all Claude projects
 pub fn get_projects() -> Result<Vec<ProjectEntry>, String> {
@@ -15,116 +23,161 @@ pub fn get_projects() -> Result<Vec<ProjectEntry>, String> {
         return Ok(Vec::new());
     }
 
-    let mut projects: Vec<ProjectEntry> = Vec::new();
+    let mut projects: Vec<ProjectEntry> =
+        parallel::map_bounded(project_dirs(&projects_dir)?, |(path, encoded_name)| {
+            project_entry(&path, encoded_name)
+        })
+        .into_iter()
+        .filter(|p| p.session_count > 0)
+        .collect();
+
+    let ignored = settings::ignored_projects();
+    projects.retain(|p| !settings::is_project_ignored(&ignored, &p.display_path));
+    project_alias::apply_aliases("claude", &mut projects);
+
+    // Tie-break on id so equal timestamps don't depend on scan order
+    projects.sort_by(|a, b| {
+        b.last_modified
+            .cmp(&a.last_modified)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    Ok(projects)
+}
 
+/// Project directories under `projects_dir` with their encoded names
+fn project_dirs(projects_dir: &Path) -> Result<Vec<(PathBuf, String)>, String> {
     let entries =
-        fs::read_dir(&projects_dir).map_err(|e| format!("Failed to read projects dir: {}", e))?;
+        fs::read_dir(projects_dir).map_err(|e| format!("Failed to read projects dir: {}", e))?;
+
+    Ok(entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .filter_map(|p| {
+            let name = p.file_name().and_then(|n| n.to_str())?.to_string();
+            Some((p, name))
+        })
+        .collect())
+}
 
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if !path.is_dir() {
-            continue;
-        }
+/// Look up a single Claude project by its encoded directory name.
+pub fn get_project(encoded_name: &str) -> Result<ProjectEntry, String> {
+    let projects_dir = get_projects_dir().ok_or("Could not find Claude projects directory")?;
+    let path = projects_dir.join(encoded_name);
+    if encoded_name.is_empty() || encoded_name.contains(['/', '\\']) || !path.is_dir() {
+        return Err(format!("Project directory not found: {}", encoded_name));
+    }
+    let mut entry = project_entry(&path, encoded_name.to_string());
+    project_alias::apply_aliases("claude", std::slice::from_mut(&mut entry));
+    Ok(entry)
+}
 
-        let encoded_name = match path.file_name().and_then(|n| n.to_str()) {
-            Some(name) => name.to_string(),
-            None => continue,
-        };
+/// Decoded path of a Claude project (sessions-index originalPath, else decoded name)
+pub fn display_path(encoded_name: &str) -> String {
+    match get_projects_dir() {
+        Some(dir) => project_display_path(&dir.join(encoded_name), encoded_name),
+        None => decode_project_path(encoded_name),
+    }
+}
 
-        // Read sessions-index.json for display path and accurate session count
-        let index_path = path.join("sessions-index.json");
-        let parsed_index = fs::read_to_string(&index_path)
-            .ok()
-            .and_then(|c| serde_json::from_str::<SessionsIndex>(&c).ok());
-
-        let display_path = parsed_index
-            .as_ref()
-            .and_then(|idx| idx.original_path.clone())
-            .unwrap_or_else(|| decode_project_path(&encoded_name));
-        let short_name = short_name_from_path(&display_path);
-
-        // Count sessions consistently with get_sessions(): only those with messages
-        let session_count = if let Some(ref index) = parsed_index {
-            if !index.entries.is_empty() {
-                // From index: count entries where message_count > 0 (or unknown)
-                let indexed_ids: std::collections::HashSet<&str> =
-                    index.entries.iter().map(|e| e.session_id.as_str()).collect();
-                let indexed_count = index
-                    .entries
-                    .iter()
-                    .filter(|e| e.message_count.map(|c| c > 0).unwrap_or(true))
-                    .count();
-                // Also count disk-only files not in index (non-empty)
-                let extra = fs::read_dir(&path)
-                    .map(|rd| {
-                        rd.flatten()
-                            .filter(|e| {
-                                let p = e.path();
-                                p.extension().map(|ext| ext == "jsonl").unwrap_or(false)
-                                    && p.file_stem()
-                                        .and_then(|s| s.to_str())
-                                        .map(|id| !indexed_ids.contains(id))
-                                        .unwrap_or(false)
-                                    && e.metadata().map(|m| m.len() > 0).unwrap_or(false)
-                            })
-                            .count()
-                    })
-                    .unwrap_or(0);
-                indexed_count + extra
-            } else {
-                // Empty index, fall back to counting all .jsonl files
-                count_jsonl_files(&path)
-            }
+/// Build the project entry for one project directory
+fn project_entry(path: &Path, encoded_name: String) -> ProjectEntry {
+    // Read sessions-index.json for display path and accurate session count
+    let index_path = path.join("sessions-index.json");
+    let parsed_index = fs::read_to_string(&index_path)
+        .ok()
+        .and_then(|c| serde_json::from_str::<SessionsIndex>(&c).ok());
+
+    let display_path = parsed_index
+        .as_ref()
+        .and_then(|idx| idx.original_path.clone())
+        .unwrap_or_else(|| decode_project_path(&encoded_name));
+    let short_name = short_name_from_path(&display_path);
+
+    // Count sessions consistently with get_sessions(): only those with messages
+    let session_count = if let Some(ref index) = parsed_index {
+        if !index.entries.is_empty() {
+            // From index: count entries where message_count > 0 (or unknown)
+            let indexed_ids: std::collections::HashSet<&str> = index
+                .entries
+                .iter()
+                .map(|e| e.session_id.as_str())
+                .collect();
+            let indexed_count = index
+                .entries
+                .iter()
+                .filter(|e| e.message_count.map(|c| c > 0).unwrap_or(true))
+                .count();
+            // Also count disk-only files not in index (non-empty)
+            let extra = fs::read_dir(path)
+                .map(|rd| {
+                    rd.flatten()
+                        .filter(|e| {
+                            let p = e.path();
+                            is_session_file(&p, "claude")
+                                && p.file_stem()
+                                    .and_then(|s| s.to_str())
+                                    .map(|id| !indexed_ids.contains(id))
+                                    .unwrap_or(false)
+                                && e.metadata().map(|m| m.len() > 0).unwrap_or(false)
+                        })
+                        .count()
+                })
+                .unwrap_or(0);
+            indexed_count + extra
         } else {
-            // No index file, fall back to counting all .jsonl files
-            count_jsonl_files(&path)
-        };
-
-        let last_modified = fs::metadata(&path)
-            .and_then(|m| m.modified())
-            .ok()
-            .map(|t| {
-                let duration = t
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default();
-                chrono::DateTime::from_timestamp(duration.as_secs() as i64, 0)
-                    .map(|dt| dt.to_rfc3339())
-                    .unwrap_or_default()
-            });
-
-        if session_count > 0 {
-            projects.push(ProjectEntry {
-                source: "claude".to_string(),
-                id: encoded_name,
-                display_path,
-                short_name,
-                session_count,
-                last_modified,
-                model_provider: None,
-            });
+            // Empty index, fall back to counting all session files
+            count_session_files(path)
         }
-    }
+    } else {
+        // No index file, fall back to counting all session files
+        count_session_files(path)
+    };
 
-    projects.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
-    Ok(projects)
+    let last_modified = fs::metadata(path).and_then(|m| m.modified()).ok().map(|t| {
+        let duration = t.duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
+        chrono::DateTime::from_timestamp(duration.as_secs() as i64, 0)
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_default()
+    });
+
+    ProjectEntry {
+        source: "claude".to_string(),
+        id: encoded_name,
+        display_path,
+        short_name,
+        session_count,
+        last_modified,
+        model_provider: None,
+        alias: None,
+    }
 }
 
-/// Get sessions for a Claude project
-pub fn get_sessions(encoded_name: &str) -> Result<Vec<SessionIndexEntry>, String> {
-    let projects_dir = get_projects_dir().ok_or("Could not find Claude projects directory")?;
+/// Get sessions for a Claude project.
+/// `projects_dir` overrides the default `~/.claude/projects` root (e.g. a restored backup).
+pub fn get_sessions(
+    encoded_name: &str,
+    projects_dir: Option<&Path>,
+) -> Result<Vec<SessionIndexEntry>, String> {
+    let projects_dir = match projects_dir {
+        Some(dir) => dir.to_path_buf(),
+        None => get_projects_dir().ok_or("Could not find Claude projects directory")?,
+    };
     let project_dir = projects_dir.join(encoded_name);
 
     if !project_dir.exists() {
         return Err(format!("Project directory not found: {}", encoded_name));
     }
 
-    // Collect all .jsonl files on disk: session_id -> path
+    let preview_chars = settings::preview_max_chars();
+
+    // Collect all session files on disk: session_id -> path
     let mut disk_sessions: std::collections::HashMap<String, PathBuf> =
         std::collections::HashMap::new();
     if let Ok(dir_entries) = fs::read_dir(&project_dir) {
         for entry in dir_entries.flatten() {
             let path = entry.path();
-            if path.extension().map(|e| e == "jsonl").unwrap_or(false) {
+            if is_session_file(&path, "claude") {
                 if let Some(session_id) = path.file_stem().and_then(|s| s.to_str()) {
                     if !session_id.is_empty() {
                         disk_sessions.insert(session_id.to_string(), path);
@@ -165,7 +218,9 @@ pub fn get_sessions(encoded_name: &str) -> Result<Vec<SessionIndexEntry>, String
             // Find sessions on disk but missing from index, scan them individually
             for (session_id, path) in &disk_sessions {
                 if !indexed_ids.contains(session_id) {
-                    if let Some(mut entry) = scan_single_session(path, session_id) {
+                    if let Some(mut entry) = session_cache::cached_entry(path, preview_chars, |f| {
+                        scan_single_session(f, session_id, preview_chars)
+                    }) {
                         if entry.project_path.is_none() {
                             entry.project_path = original_path.clone();
                         }
@@ -180,10 +235,82 @@ pub fn get_sessions(encoded_name: &str) -> Result<Vec<SessionIndexEntry>, String
         }
     }
 
-    // Fallback: scan JSONL files directly
-    scan_sessions_from_dir(&project_dir)
+    // Fallback: scan session files directly
+    scan_sessions_from_dir(&project_dir, preview_chars)
+}
+
+/// Sessions of a Claude project whose file changed after `since_epoch` (Unix
+/// seconds). Only those files are scanned, so polling an idle project is cheap.
+pub fn sessions_modified_since(
+    encoded_name: &str,
+    since_epoch: i64,
+) -> Result<Vec<SessionIndexEntry>, String> {
+    let projects_dir = get_projects_dir().ok_or("Could not find Claude projects directory")?;
+    let project_dir = projects_dir.join(encoded_name);
+    if !project_dir.exists() {
+        return Err(format!("Project directory not found: {}", encoded_name));
+    }
+
+    let preview_chars = settings::preview_max_chars();
+    let dir_entries =
+        fs::read_dir(&project_dir).map_err(|e| format!("Failed to read project dir: {}", e))?;
+    let mut entries: Vec<SessionIndexEntry> = dir_entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| is_session_file(p, "claude") && modified_after(p, since_epoch))
+        .filter_map(|p| {
+            let session_id = p.file_stem().and_then(|s| s.to_str())?.to_string();
+            session_cache::cached_entry(&p, preview_chars, |f| {
+                scan_single_session(f, &session_id, preview_chars)
+            })
+        })
+        .collect();
+
+    if entries.iter().any(|e| e.project_path.is_none()) {
+        let display_path = project_display_path(&project_dir, encoded_name);
+        for entry in entries.iter_mut().filter(|e| e.project_path.is_none()) {
+            entry.project_path = Some(display_path.clone());
+        }
+    }
+
+    entries.sort_by(|a, b| b.modified.cmp(&a.modified));
+    entries.retain(|e| e.message_count > 0);
+    Ok(entries)
+}
+
+/// Report whether `~/.claude/projects` exists and how many projects it holds
+pub fn claude_data_dir_status() -> DataDirStatus {
+    let projects_dir = match get_projects_dir() {
+        Some(d) if d.is_dir() => d,
+        _ => return DataDirStatus::Missing,
+    };
+
+    let count = fs::read_dir(&projects_dir)
+        .map(|rd| rd.flatten().filter(|e| e.path().is_dir()).count())
+        .unwrap_or(0);
+    if count == 0 {
+        DataDirStatus::Empty
+    } else {
+        DataDirStatus::Present(count)
+    }
 }
 
+/// List the encoded names of all Claude project directories
+pub fn list_project_ids() -> Vec<String> {
+    let projects_dir = match get_projects_dir() {
+        Some(d) if d.exists() => d,
+        _ => return Vec::new(),
+    };
+
+    fs::read_dir(&projects_dir)
+        .map(|rd| {
+            rd.flatten()
+                .filter(|e| e.path().is_dir())
+                .filter_map(|e| e.file_name().to_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
 
 /// Parse messages from a Claude JSONL file
 pub fn parse_session_messages(
@@ -200,7 +327,7 @@ pub fn parse_all_messages(path: &std::path::Path) -> Result<Vec<DisplayMessage>,
     claude_parser::parse_all_messages(path)
 }
 
-/// Collect all JSONL files for search
+/// Collect all session files (JSONL or legacy JSON) for search
 pub fn collect_all_jsonl_files() -> Vec<(String, String, PathBuf)> {
     let projects_dir = match get_projects_dir() {
         Some(d) if d.exists() => d,
@@ -231,7 +358,7 @@ pub fn collect_all_jsonl_files() -> Vec<(String, String, PathBuf)> {
         if let Ok(dir_files) = fs::read_dir(&path) {
             for file_entry in dir_files.flatten() {
                 let file_path = file_entry.path();
-                if file_path.extension().map(|e| e == "jsonl").unwrap_or(false) {
+                if is_session_file(&file_path, "claude") {
                     files.push((encoded_name.clone(), project_name.clone(), file_path));
                 }
             }
@@ -241,6 +368,117 @@ pub fn collect_all_jsonl_files() -> Vec<(String, String, PathBuf)> {
     files
 }
 
+/// Sum the on-disk size of every session file per project (metadata-only walk).
+pub fn project_disk_usage() -> Result<Vec<(String, u64)>, String> {
+    let projects_dir = get_projects_dir().ok_or("Could not find Claude projects directory")?;
+
+    if !projects_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut usage: Vec<(String, u64)> =
+        parallel::map_bounded(project_dirs(&projects_dir)?, |(path, encoded_name)| {
+            let bytes: u64 = fs::read_dir(&path)
+                .map(|rd| {
+                    rd.flatten()
+                        .filter(|e| is_session_file(&e.path(), "claude"))
+                        .filter_map(|e| e.metadata().ok())
+                        .map(|m| m.len())
+                        .sum()
+                })
+                .unwrap_or(0);
+            (encoded_name, bytes)
+        });
+
+    usage.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    Ok(usage)
+}
+
+/// Move a session file into another project's directory.
+///
+/// Creates the target directory if needed, rewrites each record's `cwd` to the
+/// target project path, drops the session from the source `sessions-index.json`
+/// and returns the new file path. On a name collision the file (and its embedded
+/// `sessionId`) gets a numeric suffix.
+pub fn move_session(path: &Path, target_project_id: &str) -> Result<PathBuf, String> {
+    if detect_format(path) != SessionFormat::Jsonl {
+        return Err("Only JSONL sessions can be moved".to_string());
+    }
+
+    if target_project_id.is_empty() || target_project_id.contains(['/', '\\']) {
+        return Err(format!("Invalid target project: {}", target_project_id));
+    }
+    let projects_dir = get_projects_dir().ok_or("Could not find Claude projects directory")?;
+    let target_dir = projects_dir.join(target_project_id);
+    // Rejects "." and "..", which would resolve outside the projects directory
+    let is_child = target_dir.parent() == Some(projects_dir.as_path())
+        && target_dir.file_name() == Some(std::ffi::OsStr::new(target_project_id));
+    if !is_child {
+        return Err(format!("Invalid target project: {}", target_project_id));
+    }
+    if path.parent() == Some(target_dir.as_path()) {
+        return Err("Session is already in the target project".to_string());
+    }
+
+    let session_id = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or("Invalid session file name")?
+        .to_string();
+
+    fs::create_dir_all(&target_dir)
+        .map_err(|e| format!("Failed to create project directory: {}", e))?;
+
+    // Pick a free file name, suffixing on collision
+    let mut new_id = session_id.clone();
+    let mut suffix = 1;
+    while target_dir.join(format!("{}.jsonl", new_id)).exists() {
+        new_id = format!("{}-{}", session_id, suffix);
+        suffix += 1;
+    }
+    let new_path = target_dir.join(format!("{}.jsonl", new_id));
+
+    let target_cwd = project_display_path(&target_dir, target_project_id);
+
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read session: {}", e))?;
+    let mut rewritten = String::with_capacity(content.len());
+    for line in content.lines() {
+        match serde_json::from_str::<serde_json::Value>(line) {
+            Ok(serde_json::Value::Object(mut obj)) => {
+                if obj.get("cwd").map(|v| v.is_string()).unwrap_or(false) {
+                    obj.insert(
+                        "cwd".to_string(),
+                        serde_json::Value::String(target_cwd.clone()),
+                    );
+                }
+                if new_id != session_id
+                    && obj.get("sessionId").and_then(|v| v.as_str()) == Some(session_id.as_str())
+                {
+                    obj.insert(
+                        "sessionId".to_string(),
+                        serde_json::Value::String(new_id.clone()),
+                    );
+                }
+                rewritten.push_str(&serde_json::Value::Object(obj).to_string());
+            }
+            // Keep unparseable / non-object lines verbatim
+            _ => rewritten.push_str(line),
+        }
+        rewritten.push('\n');
+    }
+
+    let tmp_path = new_path.with_extension("jsonl.tmp");
+    fs::write(&tmp_path, rewritten).map_err(|e| format!("Failed to write tmp: {}", e))?;
+    fs::rename(&tmp_path, &new_path).map_err(|e| format!("Failed to rename: {}", e))?;
+    fs::remove_file(path).map_err(|e| format!("Failed to remove original session: {}", e))?;
+
+    if let Some(source_dir) = path.parent() {
+        remove_from_index(source_dir, &session_id);
+    }
+
+    Ok(new_path)
+}
+
 // ── internal helpers ──
 
 fn convert_index_entry(e: SessionsIndexFileEntry, project_dir: &std::path::Path) -> SessionIndexEntry {
@@ -270,10 +508,18 @@ fn convert_index_entry(e: SessionsIndexFileEntry, project_dir: &std::path::Path)
         cli_version: None,
         alias: None,
         tags: None,
+        archived: false,
+        pinned_message_ids: Vec::new(),
+        token_usage: None,
+        rating: None,
+        content_hash: None,
     }
 }
 
-fn scan_sessions_from_dir(project_dir: &std::path::Path) -> Result<Vec<SessionIndexEntry>, String> {
+fn scan_sessions_from_dir(
+    project_dir: &std::path::Path,
+    preview_chars: usize,
+) -> Result<Vec<SessionIndexEntry>, String> {
     let mut entries: Vec<SessionIndexEntry> = Vec::new();
 
     let dir_entries =
@@ -281,7 +527,7 @@ fn scan_sessions_from_dir(project_dir: &std::path::Path) -> Result<Vec<SessionIn
 
     for entry in dir_entries.flatten() {
         let path = entry.path();
-        if path.extension().map(|e| e == "jsonl").unwrap_or(false) {
+        if is_session_file(&path, "claude") {
             let session_id = path
                 .file_stem()
                 .and_then(|s| s.to_str())
@@ -292,7 +538,9 @@ fn scan_sessions_from_dir(project_dir: &std::path::Path) -> Result<Vec<SessionIn
                 continue;
             }
 
-            if let Some(entry) = scan_single_session(&path, &session_id) {
+            if let Some(entry) = session_cache::cached_entry(&path, preview_chars, |f| {
+                scan_single_session(f, &session_id, preview_chars)
+            }) {
                 entries.push(entry);
             }
         }
@@ -303,34 +551,19 @@ fn scan_sessions_from_dir(project_dir: &std::path::Path) -> Result<Vec<SessionIn
     Ok(entries)
 }
 
-fn scan_single_session(path: &std::path::Path, session_id: &str) -> Option<SessionIndexEntry> {
-    let first_prompt = claude_parser::extract_first_prompt(path);
+pub(crate) fn scan_single_session(
+    path: &std::path::Path,
+    session_id: &str,
+    preview_chars: usize,
+) -> Option<SessionIndexEntry> {
+    let first_prompt = claude_parser::extract_first_prompt(path, preview_chars);
     let metadata = claude_parser::extract_session_metadata(path);
     let (_, git_branch, project_path) = metadata.unwrap_or((String::new(), None, None));
     let message_count = count_messages(path);
 
-    let file_meta = fs::metadata(path).ok();
-    let modified = file_meta.as_ref().and_then(|m| {
-        m.modified().ok().map(|t| {
-            let d = t
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default();
-            chrono::DateTime::from_timestamp(d.as_secs() as i64, 0)
-                .map(|dt| dt.to_rfc3339())
-                .unwrap_or_default()
-        })
-    });
-
-    let created = file_meta.as_ref().and_then(|m| {
-        m.created().ok().map(|t| {
-            let d = t
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default();
-            chrono::DateTime::from_timestamp(d.as_secs() as i64, 0)
-                .map(|dt| dt.to_rfc3339())
-                .unwrap_or_default()
-        })
-    });
+    let times = timestamps::session_times_or_file(path);
+    let created = times.created.and_then(timestamps::to_rfc3339);
+    let modified = times.last_message.and_then(timestamps::to_rfc3339);
 
     Some(SessionIndexEntry {
         source: "claude".to_string(),
@@ -348,34 +581,32 @@ fn scan_single_session(path: &std::path::Path, session_id: &str) -> Option<Sessi
         cli_version: None,
         alias: None,
         tags: None,
+        archived: false,
+        pinned_message_ids: Vec::new(),
+        token_usage: None,
+        rating: None,
+        content_hash: None,
     })
 }
 
-/// Count all .jsonl files in a directory (fallback when no sessions-index.json)
-fn count_jsonl_files(dir: &std::path::Path) -> usize {
+/// Count all session files in a directory (fallback when no sessions-index.json)
+fn count_session_files(dir: &std::path::Path) -> usize {
     fs::read_dir(dir)
         .map(|rd| {
             rd.flatten()
-                .filter(|e| {
-                    e.path()
-                        .extension()
-                        .map(|ext| ext == "jsonl")
-                        .unwrap_or(false)
-                })
+                .filter(|e| is_session_file(&e.path(), "claude"))
                 .count()
         })
         .unwrap_or(0)
 }
 
 fn count_messages(path: &std::path::Path) -> u32 {
-    use std::io::{BufRead, BufReader};
-    let file = match fs::File::open(path) {
-        Ok(f) => f,
+    let records = match read_records(path) {
+        Ok(r) => r,
         Err(_) => return 0,
     };
-    let reader = BufReader::new(file);
     let mut count: u32 = 0;
-    for line in reader.lines().map_while(Result::ok) {
+    for line in records.map_while(Result::ok) {
         let trimmed = line.trim();
         if trimmed.contains("\"type\":\"user\"") || trimmed.contains("\"type\":\"assistant\"") {
             count += 1;
@@ -383,3 +614,185 @@ fn count_messages(path: &std::path::Path) -> u32 {
     }
     count
 }
+
+/// Display path of a project: sessions-index.json originalPath, else decoded dir name
+fn project_display_path(project_dir: &Path, encoded_name: &str) -> String {
+    fs::read_to_string(project_dir.join("sessions-index.json"))
+        .ok()
+        .and_then(|c| serde_json::from_str::<SessionsIndex>(&c).ok())
+        .and_then(|idx| idx.original_path)
+        .unwrap_or_else(|| decode_project_path(encoded_name))
+}
+
+/// Drop a session from a project's sessions-index.json (no-op if absent or unparseable)
+fn remove_from_index(project_dir: &Path, session_id: &str) {
+    let index_path = project_dir.join("sessions-index.json");
+    let mut index: SessionsIndex = match fs::read_to_string(&index_path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+    {
+        Some(idx) => idx,
+        None => return,
+    };
+
+    let len_before = index.entries.len();
+    index.entries.retain(|e| e.session_id != session_id);
+    if index.entries.len() != len_before {
+        if let Ok(json) = serde_json::to_string_pretty(&index) {
+            let _ = fs::write(&index_path, json);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{claude_assistant, claude_user, TestHome};
+
+    const PROJECT: &str = "-home-user-project";
+
+    fn session_ids(home: &TestHome) -> Vec<String> {
+        let projects_dir = home.path().join(".claude/projects");
+        let mut ids: Vec<String> = get_sessions(PROJECT, Some(&projects_dir))
+            .unwrap()
+            .into_iter()
+            .map(|s| s.session_id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn bookkeeping_files_are_not_listed_as_sessions() {
+        let home = TestHome::new();
+        home.claude_session(
+            PROJECT,
+            "real-session",
+            &[
+                claude_user("u1", "hello"),
+                claude_assistant("a1", "hi", "claude-sonnet-4-6"),
+            ],
+        );
+        let dir = home.claude_project(PROJECT);
+        let meta = r#"{"sessions": {"real-session": {"alias": "kept"}}}"#;
+        std::fs::write(dir.join(".session-viewer-meta.json"), meta).unwrap();
+        std::fs::write(dir.join(".session-viewer-meta.json.lock"), "").unwrap();
+        std::fs::write(dir.join("half-written.json.tmp"), "{").unwrap();
+        std::fs::write(dir.join("half-written.jsonl.tmp"), "{").unwrap();
+        std::fs::write(dir.join("sessions-index.json"), r#"{"entries": []}"#).unwrap();
+
+        assert_eq!(session_ids(&home), ["real-session"]);
+    }
+
+    fn texts(messages: &[DisplayMessage]) -> Vec<String> {
+        messages
+            .iter()
+            .flat_map(|m| &m.content)
+            .filter_map(|b| match b {
+                crate::models::message::DisplayContentBlock::Text { text } => Some(text.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn json_and_jsonl_sessions_are_listed_and_parsed() {
+        let home = TestHome::new();
+        let jsonl = home.claude_session(
+            PROJECT,
+            "new-session",
+            &[
+                claude_user("u1", "from jsonl"),
+                claude_assistant("a1", "jsonl reply", "claude-sonnet-4-6"),
+            ],
+        );
+        let json = home.claude_project(PROJECT).join("old-session.json");
+        let records = format!(
+            "[{},{}]",
+            claude_user("u2", "from json"),
+            claude_assistant("a2", "json reply", "claude-sonnet-4-6")
+        );
+        std::fs::write(&json, records).unwrap();
+
+        assert_eq!(detect_format(&jsonl), SessionFormat::Jsonl);
+        assert_eq!(detect_format(&json), SessionFormat::Json);
+        assert_eq!(session_ids(&home), ["new-session", "old-session"]);
+
+        let messages = parse_all_messages(&jsonl).unwrap();
+        assert_eq!(texts(&messages), ["from jsonl", "jsonl reply"]);
+        let messages = parse_all_messages(&json).unwrap();
+        assert_eq!(texts(&messages), ["from json", "json reply"]);
+    }
+
+    #[test]
+    fn move_refuses_target_ids_outside_the_projects_dir() {
+        let home = TestHome::new();
+        let path = home.claude_session(PROJECT, "s1", &[claude_user("u1", "hello")]);
+        let before = std::fs::read_to_string(&path).unwrap();
+
+        for target in ["../../.ssh", "..", ".", "", "other/nested", r"..\evil"] {
+            let err = move_session(&path, target).unwrap_err();
+            assert!(
+                err.starts_with("Invalid target project"),
+                "{}: {}",
+                target,
+                err
+            );
+        }
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), before);
+        assert!(!home.path().join(".ssh").exists());
+        assert!(!home.path().join(".claude/other").exists());
+    }
+
+    fn project_ids() -> Vec<String> {
+        get_projects().unwrap().into_iter().map(|p| p.id).collect()
+    }
+
+    #[test]
+    fn ignored_projects_are_left_out_of_the_listing() {
+        let home = TestHome::new();
+        for project in ["-home-user-app", "-tmp-one", "-tmp-two"] {
+            home.claude_session(project, "s1", &[claude_user("u1", "hello")]);
+        }
+        assert_eq!(project_ids().len(), 3);
+
+        settings::add_ignored_project("/tmp/*").unwrap();
+        assert_eq!(project_ids(), ["-home-user-app"]);
+
+        let hidden = get_project("-tmp-one").unwrap();
+        assert_eq!(hidden.session_count, 1);
+
+        settings::remove_ignored_project("/tmp/*").unwrap();
+        assert_eq!(project_ids().len(), 3);
+        assert_eq!(settings::load_settings().ignored_projects, None);
+    }
+
+    fn first_prompt(home: &TestHome) -> String {
+        let projects_dir = home.path().join(".claude/projects");
+        get_sessions(PROJECT, Some(&projects_dir)).unwrap()[0]
+            .first_prompt
+            .clone()
+            .unwrap()
+    }
+
+    #[test]
+    fn first_prompt_preview_follows_the_preview_length_setting() {
+        let home = TestHome::new();
+        let prompt = "ü".repeat(settings::DEFAULT_PREVIEW_MAX_CHARS + 10);
+        home.claude_session(PROJECT, "s1", &[claude_user("u1", &prompt)]);
+
+        let default = first_prompt(&home);
+        assert_eq!(
+            default.chars().count(),
+            settings::DEFAULT_PREVIEW_MAX_CHARS + 1
+        );
+        assert!(default.ends_with("ü…"));
+
+        settings::save_settings(&settings::SettingsFile {
+            preview_max_chars: Some(4),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(first_prompt(&home), "üüüü…");
+    }
+}
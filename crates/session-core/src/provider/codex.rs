@@ -1,14 +1,22 @@
 use std::collections::HashMap;
 use std::fs;
-use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 
 use serde_json::Value;
 
-use crate::models::message::{DisplayContentBlock, DisplayMessage, PaginatedMessages};
+use crate::models::message::{DisplayContentBlock, DisplayMessage, PaginatedMessages, Role};
 use crate::models::project::ProjectEntry;
 use crate::models::session::SessionIndexEntry;
-use crate::models::stats::{DailyTokenEntry, TokenUsageSummary};
+use crate::models::stats::{DailyTokenEntry, ModelUsageEntry, TokenUsageSummary};
+use crate::parser::format::{
+    detect_format, is_session_file, modified_after, read_records, SessionFormat,
+};
+use crate::parser::preview::{build_preview, PreviewBlock};
+use crate::parser::tool_calls::link_tool_calls;
+use crate::project_alias;
+use crate::provider::timestamps;
+use crate::session_cache;
+use crate::settings;
 
 /// Maximum size for text content blocks sent to frontend (20KB)
 const MAX_TEXT_BLOCK_SIZE: usize = 20_000;
@@ -69,7 +77,7 @@ pub fn scan_all_session_files() -> Vec<PathBuf> {
                 };
                 for file_entry in jsonl_files.flatten() {
                     let file_path = file_entry.path();
-                    if file_path.extension().map(|e| e == "jsonl").unwrap_or(false) {
+                    if is_session_file(&file_path, "codex") {
                         files.push(file_path);
                     }
                 }
@@ -125,10 +133,9 @@ pub struct SessionMeta {
 }
 
 pub fn extract_session_meta(path: &Path) -> Option<SessionMeta> {
-    let file = fs::File::open(path).ok()?;
-    let reader = BufReader::new(file);
+    let records = read_records(path).ok()?;
 
-    for line in reader.lines().take(5) {
+    for line in records.take(5) {
         let line = match line {
             Ok(l) => l,
             Err(_) => continue,
@@ -185,81 +192,109 @@ pub fn extract_session_meta(path: &Path) -> Option<SessionMeta> {
 
 // ── Projects and sessions ──
 
-fn list_all_sessions() -> Result<Vec<SessionIndexEntry>, String> {
-    let files = scan_all_session_files();
-    let mut entries: Vec<SessionIndexEntry> = Vec::new();
-
-    for file_path in files {
-        let meta = extract_session_meta(&file_path);
-        let first_prompt = extract_first_prompt(&file_path);
-        let message_count = count_messages(&file_path);
-
-        let (session_id, cwd, model_provider, cli_version, git_branch) = match meta {
-            Some(m) => (m.id, m.cwd, m.model_provider, m.cli_version, m.git_branch),
-            None => {
-                let stem = file_path
-                    .file_stem()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("unknown")
-                    .to_string();
-                (stem, String::new(), None, None, None)
-            }
-        };
-
-        let short_name = if cwd.is_empty() {
-            "unknown".to_string()
-        } else {
-            short_name_from_path(&cwd)
-        };
-        let _ = short_name; // used indirectly via cwd
-
-        let file_meta = fs::metadata(&file_path).ok();
-        let modified = file_meta.as_ref().and_then(|m| {
-            m.modified().ok().map(|t| {
-                let d = t
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default();
-                chrono::DateTime::from_timestamp(d.as_secs() as i64, 0)
-                    .map(|dt| dt.to_rfc3339())
-                    .unwrap_or_default()
-            })
-        });
+pub fn list_all_sessions() -> Result<Vec<SessionIndexEntry>, String> {
+    let preview_chars = settings::preview_max_chars();
+    let mut entries: Vec<SessionIndexEntry> = scan_all_session_files()
+        .iter()
+        .filter_map(|p| {
+            session_cache::cached_entry(p, preview_chars, |f| Some(session_entry(f, preview_chars)))
+        })
+        .collect();
 
-        let created = file_meta.as_ref().and_then(|m| {
-            m.created().ok().map(|t| {
-                let d = t
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default();
-                chrono::DateTime::from_timestamp(d.as_secs() as i64, 0)
-                    .map(|dt| dt.to_rfc3339())
-                    .unwrap_or_default()
-            })
-        });
+    entries.sort_by(|a, b| b.modified.cmp(&a.modified));
+    Ok(entries)
+}
 
-        entries.push(SessionIndexEntry {
-            source: "codex".to_string(),
-            session_id,
-            file_path: file_path.to_string_lossy().to_string(),
-            first_prompt,
-            message_count,
-            created,
-            modified,
-            git_branch,
-            project_path: None,
-            is_sidechain: None,
-            cwd: Some(cwd),
-            model_provider,
-            cli_version,
-            alias: None,
-            tags: None,
-        });
-    }
+/// Sessions whose file changed after `since_epoch` (Unix seconds), optionally
+/// limited to one cwd. Unchanged files are skipped before any parsing.
+pub fn sessions_modified_since(cwd: Option<&str>, since_epoch: i64) -> Vec<SessionIndexEntry> {
+    let preview_chars = settings::preview_max_chars();
+    let mut entries: Vec<SessionIndexEntry> = scan_all_session_files()
+        .iter()
+        .filter(|p| modified_after(p, since_epoch))
+        .filter_map(|p| {
+            session_cache::cached_entry(p, preview_chars, |f| Some(session_entry(f, preview_chars)))
+        })
+        .filter(|e| cwd.is_none() || e.cwd.as_deref() == cwd)
+        .collect();
 
     entries.sort_by(|a, b| b.modified.cmp(&a.modified));
-    Ok(entries)
+    entries
+}
+
+/// Index entry for one session file, its first prompt cut to `preview_chars`
+pub(crate) fn session_entry(file_path: &Path, preview_chars: usize) -> SessionIndexEntry {
+    let meta = extract_session_meta(file_path);
+    let first_prompt = extract_first_prompt(file_path, preview_chars);
+    let message_count = count_messages(file_path);
+
+    let (session_id, cwd, model_provider, cli_version, git_branch) = match meta {
+        Some(m) => (m.id, m.cwd, m.model_provider, m.cli_version, m.git_branch),
+        None => {
+            let stem = file_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("unknown")
+                .to_string();
+            (stem, String::new(), None, None, None)
+        }
+    };
+
+    let short_name = if cwd.is_empty() {
+        "unknown".to_string()
+    } else {
+        short_name_from_path(&cwd)
+    };
+    let _ = short_name; // used indirectly via cwd
+
+    let times = timestamps::session_times_or_file(file_path);
+    let created = times.created.and_then(timestamps::to_rfc3339);
+    let modified = times.last_message.and_then(timestamps::to_rfc3339);
+
+    SessionIndexEntry {
+        source: "codex".to_string(),
+        session_id,
+        file_path: file_path.to_string_lossy().to_string(),
+        first_prompt,
+        message_count,
+        created,
+        modified,
+        git_branch,
+        project_path: None,
+        is_sidechain: None,
+        cwd: Some(cwd),
+        model_provider,
+        cli_version,
+        alias: None,
+        tags: None,
+        archived: false,
+        pinned_message_ids: Vec::new(),
+        token_usage: None,
+        rating: None,
+        content_hash: None,
+    }
 }
 
+/// Codex projects (one per session cwd), minus those matching the ignore list
 pub fn get_projects() -> Result<Vec<ProjectEntry>, String> {
+    let ignored = settings::ignored_projects();
+    let mut projects = all_projects()?;
+    projects.retain(|p| !settings::is_project_ignored(&ignored, &p.display_path));
+    project_alias::apply_aliases("codex", &mut projects);
+    Ok(projects)
+}
+
+/// Look up a single Codex project by cwd, whether or not it is ignored
+pub fn get_project(cwd: &str) -> Result<ProjectEntry, String> {
+    let mut entry = all_projects()?
+        .into_iter()
+        .find(|p| p.id == cwd)
+        .ok_or_else(|| format!("Project not found: {}", cwd))?;
+    project_alias::apply_aliases("codex", std::slice::from_mut(&mut entry));
+    Ok(entry)
+}
+
+fn all_projects() -> Result<Vec<ProjectEntry>, String> {
     let sessions = list_all_sessions()?;
 
     let mut project_map: HashMap<String, ProjectEntry> = HashMap::new();
@@ -280,6 +315,7 @@ pub fn get_projects() -> Result<Vec<ProjectEntry>, String> {
                 session_count: 0,
                 last_modified: None,
                 model_provider: session.model_provider.clone(),
+                alias: None,
             });
 
         entry.session_count += 1;
@@ -308,6 +344,57 @@ pub fn get_sessions(cwd: &str) -> Result<Vec<SessionIndexEntry>, String> {
     Ok(entries)
 }
 
+/// Sum the on-disk size of all Codex session files.
+/// Codex stores sessions by date rather than by project, so this is a single aggregate.
+pub fn disk_usage() -> u64 {
+    scan_all_session_files()
+        .iter()
+        .filter_map(|p| fs::metadata(p).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Reassign a Codex session to another project by rewriting the `cwd` in its
+/// `session_meta` record. Codex files are stored by date, so the path is unchanged.
+pub fn move_session(path: &Path, target_cwd: &str) -> Result<PathBuf, String> {
+    if detect_format(path) != SessionFormat::Jsonl {
+        return Err("Only JSONL sessions can be moved".to_string());
+    }
+
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read session: {}", e))?;
+
+    let mut rewritten = String::with_capacity(content.len());
+    let mut found = false;
+    for line in content.lines() {
+        if !found && line.contains("\"session_meta\"") {
+            if let Ok(mut row) = serde_json::from_str::<Value>(line) {
+                if row.get("type").and_then(|v| v.as_str()) == Some("session_meta") {
+                    if let Some(payload) = row.get_mut("payload").and_then(|p| p.as_object_mut()) {
+                        payload.insert("cwd".to_string(), Value::String(target_cwd.to_string()));
+                        found = true;
+                        rewritten.push_str(&row.to_string());
+                        rewritten.push('\n');
+                        continue;
+                    }
+                }
+            }
+        }
+        // Every other line is kept verbatim
+        rewritten.push_str(line);
+        rewritten.push('\n');
+    }
+
+    if !found {
+        return Err("Session has no session_meta record to update".to_string());
+    }
+
+    let tmp_path = path.with_extension("jsonl.tmp");
+    fs::write(&tmp_path, rewritten).map_err(|e| format!("Failed to write tmp: {}", e))?;
+    fs::rename(&tmp_path, path).map_err(|e| format!("Failed to rename: {}", e))?;
+
+    Ok(path.to_path_buf())
+}
+
 // ── Message parsing ──
 
 pub fn parse_session_messages(
@@ -360,11 +447,17 @@ pub fn parse_session_messages(
 }
 
 pub fn parse_all_messages(path: &Path) -> Result<Vec<DisplayMessage>, String> {
-    let file = fs::File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
-    let reader = BufReader::new(file);
+    let records = read_records(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    Ok(messages_from_records(records))
+}
+
+/// Convert Codex rollout rows (one JSON object each) into linked display messages
+pub fn messages_from_records(
+    records: impl Iterator<Item = std::io::Result<String>>,
+) -> Vec<DisplayMessage> {
     let mut messages: Vec<DisplayMessage> = Vec::new();
 
-    for line in reader.lines() {
+    for line in records {
         let line = match line {
             Ok(l) => l,
             Err(_) => continue,
@@ -395,20 +488,22 @@ pub fn parse_all_messages(path: &Path) -> Result<Vec<DisplayMessage>, String> {
                         .get("role")
                         .and_then(|v| v.as_str())
                         .unwrap_or("");
-                    if role == "developer" || role == "system" {
-                        continue;
-                    }
-                    if role == "user" || role == "assistant" {
-                        let content_blocks = extract_message_content(payload);
-                        if !content_blocks.is_empty() {
-                            messages.push(DisplayMessage {
-                                uuid: None,
-                                role: role.to_string(),
-                                timestamp: timestamp.clone(),
-                                model: None,
-                                content: content_blocks,
-                            });
-                        }
+                    // Developer/system prompts are not displayed
+                    let canonical = match Role::from_raw(role) {
+                        Some(r @ (Role::User | Role::Assistant)) => r,
+                        _ => continue,
+                    };
+                    let content_blocks = extract_message_content(payload);
+                    if !content_blocks.is_empty() {
+                        messages.push(DisplayMessage {
+                            uuid: None,
+                            role: canonical,
+                            raw_role: Some(role.to_string()),
+                            timestamp: timestamp.clone(),
+                            model: None,
+                            content: content_blocks,
+                            tool_calls: Vec::new(),
+                        });
                     }
                 }
                 "function_call" => {
@@ -440,7 +535,8 @@ pub fn parse_all_messages(path: &Path) -> Result<Vec<DisplayMessage>, String> {
 
                     messages.push(DisplayMessage {
                         uuid: None,
-                        role: "assistant".to_string(),
+                        role: Role::Assistant,
+                        raw_role: Some(payload_type.to_string()),
                         timestamp: timestamp.clone(),
                         model: None,
                         content: vec![DisplayContentBlock::FunctionCall {
@@ -448,6 +544,7 @@ pub fn parse_all_messages(path: &Path) -> Result<Vec<DisplayMessage>, String> {
                             arguments: truncate_string(&arguments, MAX_ARGS_SIZE),
                             call_id,
                         }],
+                        tool_calls: Vec::new(),
                     });
                 }
                 "function_call_output" => {
@@ -469,13 +566,15 @@ pub fn parse_all_messages(path: &Path) -> Result<Vec<DisplayMessage>, String> {
 
                     messages.push(DisplayMessage {
                         uuid: None,
-                        role: "tool".to_string(),
+                        role: Role::Tool,
+                        raw_role: Some(payload_type.to_string()),
                         timestamp: timestamp.clone(),
                         model: None,
                         content: vec![DisplayContentBlock::FunctionCallOutput {
                             call_id,
                             output: truncate_string(&output, MAX_OUTPUT_BLOCK_SIZE),
                         }],
+                        tool_calls: Vec::new(),
                     });
                 }
                 "reasoning" => {
@@ -499,10 +598,12 @@ pub fn parse_all_messages(path: &Path) -> Result<Vec<DisplayMessage>, String> {
                     if !text.is_empty() {
                         messages.push(DisplayMessage {
                             uuid: None,
-                            role: "assistant".to_string(),
+                            role: Role::Assistant,
+                            raw_role: Some(payload_type.to_string()),
                             timestamp: timestamp.clone(),
                             model: None,
                             content: vec![DisplayContentBlock::Reasoning { text }],
+                            tool_calls: Vec::new(),
                         });
                     }
                 }
@@ -511,7 +612,9 @@ pub fn parse_all_messages(path: &Path) -> Result<Vec<DisplayMessage>, String> {
         }
     }
 
-    Ok(messages)
+    link_tool_calls(&mut messages);
+
+    messages
 }
 
 fn extract_message_content(payload: &Value) -> Vec<DisplayContentBlock> {
@@ -559,11 +662,10 @@ fn extract_message_content(payload: &Value) -> Vec<DisplayContentBlock> {
     blocks
 }
 
-pub fn extract_first_prompt(path: &Path) -> Option<String> {
-    let file = fs::File::open(path).ok()?;
-    let reader = BufReader::new(file);
+pub fn extract_first_prompt(path: &Path, max_chars: usize) -> Option<String> {
+    let records = read_records(path).ok()?;
 
-    for line in reader.lines() {
+    for line in records {
         let line = match line {
             Ok(l) => l,
             Err(_) => continue,
@@ -597,15 +699,21 @@ pub fn extract_first_prompt(path: &Path) -> Option<String> {
             }
 
             if let Some(content) = payload.get("content").and_then(|c| c.as_array()) {
-                for item in content {
-                    let item_type = item.get("type").and_then(|v| v.as_str()).unwrap_or("");
-                    if item_type == "input_text" || item_type == "text" {
-                        if let Some(text) = item.get("text").and_then(|v| v.as_str()) {
-                            if !text.is_empty() {
-                                return Some(truncate_string(text, 200));
-                            }
+                let blocks: Vec<PreviewBlock> = content
+                    .iter()
+                    .filter_map(|item| {
+                        match item.get("type").and_then(|v| v.as_str()).unwrap_or("") {
+                            "input_text" | "text" => item
+                                .get("text")
+                                .and_then(|v| v.as_str())
+                                .map(PreviewBlock::Text),
+                            "input_image" | "image" => Some(PreviewBlock::Image),
+                            _ => None,
                         }
-                    }
+                    })
+                    .collect();
+                if let Some(preview) = build_preview(&blocks, max_chars) {
+                    return Some(preview);
                 }
             }
         }
@@ -614,14 +722,13 @@ pub fn extract_first_prompt(path: &Path) -> Option<String> {
 }
 
 pub fn count_messages(path: &Path) -> u32 {
-    let file = match fs::File::open(path) {
-        Ok(f) => f,
+    let records = match read_records(path) {
+        Ok(r) => r,
         Err(_) => return 0,
     };
-    let reader = BufReader::new(file);
     let mut count: u32 = 0;
 
-    for line in reader.lines() {
+    for line in records {
         let line = match line {
             Ok(l) => l,
             Err(_) => continue,
@@ -651,11 +758,10 @@ pub struct TokenInfo {
 }
 
 pub fn extract_token_info(path: &Path) -> Option<TokenInfo> {
-    let file = fs::File::open(path).ok()?;
-    let reader = BufReader::new(file);
+    let records = read_records(path).ok()?;
     let mut last_token_info: Option<TokenInfo> = None;
 
-    for line in reader.lines() {
+    for line in records {
         let line = match line {
             Ok(l) => l,
             Err(_) => continue,
@@ -698,6 +804,120 @@ pub fn extract_token_info(path: &Path) -> Option<TokenInfo> {
     last_token_info
 }
 
+/// Token usage per model. `token_count` events carry running totals, so each
+/// event's growth is credited to the model of the latest `turn_context`.
+/// Cached input is reported as cache reads and excluded from plain input.
+pub fn extract_token_usage_by_model(path: &Path) -> HashMap<String, ModelUsageEntry> {
+    let mut by_model: HashMap<String, ModelUsageEntry> = HashMap::new();
+    let records = match read_records(path) {
+        Ok(r) => r,
+        Err(_) => return by_model,
+    };
+    let mut model = String::new();
+    let mut previous = ModelUsageEntry::default();
+
+    for line in records {
+        let line = match line {
+            Ok(l) => l,
+            Err(_) => continue,
+        };
+        let trimmed = line.trim();
+        if trimmed.is_empty()
+            || !(trimmed.contains("\"token_count\"") || trimmed.contains("\"turn_context\""))
+        {
+            continue;
+        }
+
+        let row: Value = match serde_json::from_str(trimmed) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let payload = match row.get("payload") {
+            Some(p) => p,
+            None => continue,
+        };
+
+        match row.get("type").and_then(|v| v.as_str()).unwrap_or("") {
+            "turn_context" => {
+                if let Some(m) = payload.get("model").and_then(|v| v.as_str()) {
+                    if !m.is_empty() {
+                        model = m.to_string();
+                    }
+                }
+            }
+            "event_msg" => {
+                if payload.get("type").and_then(|v| v.as_str()) != Some("token_count") {
+                    continue;
+                }
+                let info = match payload.get("info").and_then(|i| i.get("total_token_usage")) {
+                    Some(i) => i,
+                    None => continue,
+                };
+                let field = |name: &str| info.get(name).and_then(|v| v.as_u64()).unwrap_or(0);
+                let cached = field("cached_input_tokens");
+                let current = ModelUsageEntry {
+                    input_tokens: field("input_tokens").saturating_sub(cached),
+                    output_tokens: field("output_tokens"),
+                    cache_read_input_tokens: cached,
+                    cache_creation_input_tokens: 0,
+                };
+                if model.is_empty() {
+                    previous = current;
+                    continue;
+                }
+
+                let total = by_model.entry(model.clone()).or_default();
+                total.input_tokens += current.input_tokens.saturating_sub(previous.input_tokens);
+                total.output_tokens += current.output_tokens.saturating_sub(previous.output_tokens);
+                total.cache_read_input_tokens += current
+                    .cache_read_input_tokens
+                    .saturating_sub(previous.cache_read_input_tokens);
+                previous = current;
+            }
+            _ => {}
+        }
+    }
+    by_model
+}
+
+/// Distinct model ids a session ran with (from `turn_context` records), sorted
+pub fn extract_models(path: &Path) -> Vec<String> {
+    let records = match read_records(path) {
+        Ok(r) => r,
+        Err(_) => return Vec::new(),
+    };
+    let mut models = std::collections::BTreeSet::new();
+
+    for line in records {
+        let line = match line {
+            Ok(l) => l,
+            Err(_) => continue,
+        };
+        let trimmed = line.trim();
+        if trimmed.is_empty() || !trimmed.contains("\"turn_context\"") {
+            continue;
+        }
+
+        let row: Value = match serde_json::from_str(trimmed) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        if row.get("type").and_then(|v| v.as_str()) != Some("turn_context") {
+            continue;
+        }
+        if let Some(model) = row
+            .get("payload")
+            .and_then(|p| p.get("model"))
+            .and_then(|v| v.as_str())
+        {
+            if !model.is_empty() {
+                models.insert(model.to_string());
+            }
+        }
+    }
+    models.into_iter().collect()
+}
+
 // ── Stats ──
 
 pub fn get_stats() -> Result<TokenUsageSummary, String> {
@@ -765,3 +985,115 @@ fn truncate_string(s: &str, max_len: usize) -> String {
         format!("{}...", truncated)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{codex_message, codex_meta, TestHome};
+    use serde_json::json;
+
+    fn roles(payloads: &[Value]) -> Vec<(Role, Option<String>)> {
+        let rows = payloads
+            .iter()
+            .map(|p| Ok(json!({ "type": "response_item", "payload": p }).to_string()));
+        messages_from_records(rows)
+            .into_iter()
+            .map(|m| (m.role, m.raw_role))
+            .collect()
+    }
+
+    fn message(role: &str, text: &str) -> Value {
+        json!({
+            "type": "message",
+            "role": role,
+            "content": [{ "type": "input_text", "text": text }],
+        })
+    }
+
+    #[test]
+    fn maps_codex_roles_and_keeps_the_raw_label() {
+        let payloads = [
+            message("user", "fix the build"),
+            message("assistant", "done"),
+            json!({ "type": "function_call", "name": "shell", "arguments": "{}", "call_id": "c1" }),
+            json!({ "type": "function_call_output", "call_id": "c1", "output": "ok" }),
+            json!({ "type": "reasoning", "text": "thinking it over" }),
+        ];
+        assert_eq!(
+            roles(&payloads),
+            vec![
+                (Role::User, Some("user".to_string())),
+                (Role::Assistant, Some("assistant".to_string())),
+                (Role::Assistant, Some("function_call".to_string())),
+                (Role::Tool, Some("function_call_output".to_string())),
+                (Role::Assistant, Some("reasoning".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn developer_and_system_prompts_are_not_displayed() {
+        let payloads = [
+            message("developer", "you are codex"),
+            message("system", "sandbox: read-only"),
+            message("user", "hi"),
+        ];
+        assert_eq!(
+            roles(&payloads),
+            vec![(Role::User, Some("user".to_string()))]
+        );
+    }
+
+    fn texts(messages: &[DisplayMessage]) -> Vec<String> {
+        messages
+            .iter()
+            .flat_map(|m| &m.content)
+            .filter_map(|b| match b {
+                DisplayContentBlock::Text { text } => Some(text.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn json_and_jsonl_rollouts_are_listed_and_parsed() {
+        let home = TestHome::new();
+        let jsonl = home.codex_session(
+            "new-rollout",
+            &[
+                codex_meta("new-rollout", "/home/user/project"),
+                codex_message("user", "from jsonl"),
+                codex_message("assistant", "jsonl reply"),
+            ],
+        );
+        // Early Codex versions wrote one document with the metadata and items
+        let json = jsonl.with_file_name("rollout-2025-01-15T09-00-00-old-rollout.json");
+        let doc = json!({
+            "session": {
+                "id": "old-rollout",
+                "cwd": "/home/user/project",
+                "timestamp": "2025-01-15T09:00:00Z",
+            },
+            "items": [message("user", "from json"), message("assistant", "json reply")],
+        });
+        fs::write(&json, doc.to_string()).unwrap();
+
+        assert_eq!(detect_format(&jsonl), SessionFormat::Jsonl);
+        assert_eq!(detect_format(&json), SessionFormat::Json);
+        let mut files = scan_all_session_files();
+        files.sort();
+        assert_eq!(files, [json.clone(), jsonl.clone()]);
+        let mut ids: Vec<String> = get_sessions("/home/user/project")
+            .unwrap()
+            .into_iter()
+            .map(|s| s.session_id)
+            .collect();
+        ids.sort();
+        assert_eq!(ids, ["new-rollout", "old-rollout"]);
+
+        let messages = parse_all_messages(&jsonl).unwrap();
+        assert_eq!(texts(&messages), ["from jsonl", "jsonl reply"]);
+        let messages = parse_all_messages(&json).unwrap();
+        assert_eq!(texts(&messages), ["from json", "json reply"]);
+    }
+}
@@ -1,2 +1,3 @@
 pub mod claude;
 pub mod codex;
+pub mod timestamps;
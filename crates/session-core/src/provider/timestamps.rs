@@ -0,0 +1,182 @@
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::Value;
+
+use crate::parser::format::read_records;
+
+/// Values above this are taken as epoch milliseconds (year 5138 in seconds)
+const MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+/// Created and last-message times of a session, in epoch seconds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SessionTimes {
+    pub created: Option<i64>,
+    pub last_message: Option<i64>,
+}
+
+/// Parse a timestamp string: RFC 3339, naive `YYYY-MM-DDTHH:MM:SS` (UTC), or
+/// epoch seconds/millis written as digits
+pub fn parse_timestamp(s: &str) -> Option<i64> {
+    let s = s.trim();
+    if let Ok(n) = s.parse::<i64>() {
+        return Some(epoch_secs(n));
+    }
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
+        return Some(dt.timestamp());
+    }
+    chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+        .ok()
+        .map(|dt| dt.and_utc().timestamp())
+}
+
+/// Timestamp of a JSON value: a string or a number (seconds or millis)
+pub fn timestamp_value(value: &Value) -> Option<i64> {
+    match value {
+        Value::String(s) => parse_timestamp(s),
+        Value::Number(n) => n
+            .as_i64()
+            .or_else(|| n.as_f64().map(|f| f as i64))
+            .map(epoch_secs),
+        _ => None,
+    }
+}
+
+/// Timestamp of a record: top-level `timestamp`, else `payload.timestamp`
+pub fn record_timestamp(record: &Value) -> Option<i64> {
+    record
+        .get("timestamp")
+        .and_then(timestamp_value)
+        .or_else(|| {
+            record
+                .get("payload")
+                .and_then(|p| p.get("timestamp"))
+                .and_then(timestamp_value)
+        })
+}
+
+/// Earliest and latest record timestamps of a session file, for either provider.
+/// Records may carry ISO strings or epoch seconds/millis, at the top level or
+/// under `payload`. Fields are `None` only when no record carries a timestamp.
+pub fn session_times(path: &Path) -> SessionTimes {
+    let records = match read_records(path) {
+        Ok(r) => r,
+        Err(_) => return SessionTimes::default(),
+    };
+
+    let mut times = SessionTimes::default();
+    for line in records.map_while(Result::ok) {
+        if !line.contains("\"timestamp\"") {
+            continue;
+        }
+        let ts = match serde_json::from_str::<Value>(&line)
+            .ok()
+            .as_ref()
+            .and_then(record_timestamp)
+        {
+            Some(ts) => ts,
+            None => continue,
+        };
+        times.created = Some(times.created.map_or(ts, |c| c.min(ts)));
+        times.last_message = Some(times.last_message.map_or(ts, |l| l.max(ts)));
+    }
+    times
+}
+
+/// Session times with the file's created/modified times filling any gap
+pub fn session_times_or_file(path: &Path) -> SessionTimes {
+    let times = session_times(path);
+    if times.created.is_some() && times.last_message.is_some() {
+        return times;
+    }
+    let file_meta = std::fs::metadata(path).ok();
+    let file_time = |t: std::io::Result<SystemTime>| {
+        t.ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64)
+    };
+    SessionTimes {
+        created: times
+            .created
+            .or_else(|| file_meta.as_ref().and_then(|m| file_time(m.created()))),
+        last_message: times
+            .last_message
+            .or_else(|| file_meta.as_ref().and_then(|m| file_time(m.modified()))),
+    }
+}
+
+/// Format epoch seconds as RFC 3339 (the form `SessionIndexEntry` carries)
+pub fn to_rfc3339(secs: i64) -> Option<String> {
+    chrono::DateTime::from_timestamp(secs, 0).map(|dt| dt.to_rfc3339())
+}
+
+fn epoch_secs(n: i64) -> i64 {
+    if n.abs() >= MILLIS_THRESHOLD {
+        n / 1000
+    } else {
+        n
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{claude, codex};
+    use crate::test_support::{
+        claude_assistant, claude_user, codex_message, codex_meta, with_timestamp, TestHome,
+    };
+
+    /// 2025-01-15T10:00:00Z
+    const BASE: i64 = 1_736_935_200;
+
+    #[test]
+    fn claude_times_span_the_earliest_and_latest_records() {
+        let home = TestHome::new();
+        let path = home.claude_session(
+            "-home-user-project",
+            "s1",
+            &[
+                // Written out of order, and in every form the parser accepts
+                with_timestamp(&claude_user("u1", "hi"), "2025-01-15T10:05:00.250Z"),
+                with_timestamp(&claude_assistant("a1", "hello", "m"), "2025-01-15T10:00:00"),
+                with_timestamp(&claude_user("u2", "more"), "1736935800000"),
+                with_timestamp(&claude_assistant("a2", "ok", "m"), "not a time"),
+            ],
+        );
+
+        let times = session_times(&path);
+        assert_eq!(times.created, Some(BASE));
+        assert_eq!(times.last_message, Some(BASE + 600));
+
+        let projects_dir = home.path().join(".claude/projects");
+        let entry = claude::get_sessions("-home-user-project", Some(&projects_dir))
+            .unwrap()
+            .remove(0);
+        assert_eq!(entry.created, to_rfc3339(BASE));
+        assert_eq!(entry.modified, to_rfc3339(BASE + 600));
+    }
+
+    #[test]
+    fn codex_times_include_the_session_meta_record() {
+        let home = TestHome::new();
+        let path = home.codex_session(
+            "c1",
+            &[
+                with_timestamp(
+                    &codex_meta("c1", "/home/user/project"),
+                    "2025-01-15T10:00:00Z",
+                ),
+                with_timestamp(&codex_message("user", "hi"), "2025-01-15T11:00:00+01:00"),
+                with_timestamp(&codex_message("assistant", "hello"), "2025-01-15T10:02:00Z"),
+            ],
+        );
+
+        let times = session_times(&path);
+        assert_eq!(times.created, Some(BASE));
+        assert_eq!(times.last_message, Some(BASE + 120));
+
+        let entry = codex::get_sessions("/home/user/project").unwrap().remove(0);
+        assert_eq!(entry.created, to_rfc3339(BASE));
+        assert_eq!(entry.modified, to_rfc3339(BASE + 120));
+    }
+}
@@ -1,26 +1,119 @@
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use crate::cli_config;
+use crate::http;
+use crate::model_list;
+use crate::settings;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMsg {
     pub role: String,
     pub content: String,
+    /// Anthropic content blocks sent in place of `content` when set, e.g. the
+    /// `tool_use`/`tool_result` blocks of a session loaded with its tools
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocks: Option<Vec<serde_json::Value>>,
+}
+
+/// Optional behaviour for `stream_chat`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatOptions {
+    /// If the connection drops after text has started streaming, re-issue the
+    /// request once with the partial reply as an assistant prefill and keep going.
+    /// The resumed reply may show a slight seam where the two responses join.
+    #[serde(default)]
+    pub auto_resume: bool,
+    /// Mark the last message with `cache_control: ephemeral` so Anthropic
+    /// caches the conversation prefix; follow-up turns then read it from cache.
+    #[serde(default)]
+    pub prompt_caching: bool,
+    /// Log every SSE data line (event type and payload, before any filtering)
+    /// at `tracing` debug level, for diagnosing proxies that mangle the stream.
+    /// Only the response body is logged; request headers, and so the API key,
+    /// never are.
+    #[serde(default)]
+    pub debug_raw: bool,
+}
+
+/// Token usage reported by the API for a chat (summed across a resume)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Input tokens written to the prompt cache
+    #[serde(default)]
+    pub cache_creation_input_tokens: u64,
+    /// Input tokens served from the prompt cache
+    #[serde(default)]
+    pub cache_read_input_tokens: u64,
+    /// Provider request id of the last attempt, for support tickets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl ChatUsage {
+    fn add(&mut self, other: &ChatUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_input_tokens += other.cache_creation_input_tokens;
+        self.cache_read_input_tokens += other.cache_read_input_tokens;
+        if other.request_id.is_some() {
+            self.request_id = other.request_id.clone();
+        }
+    }
+}
+
+/// Connect timeout for chat requests, longer than the diagnostic default
+const CHAT_CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+/// Total time allowed for one streamed reply
+const CHAT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Result of a single streaming attempt
+enum StreamOutcome {
+    Complete,
+    /// The stream broke after the request succeeded (carries the I/O error)
+    Interrupted(String),
 }
 
 /// Stream a chat completion from Claude (Anthropic) API.
 ///
 /// Calls `on_chunk` with each text delta as it arrives.
-/// The `model` parameter must be a full API model ID (e.g. "claude-sonnet-4-6"),
-/// not a CLI alias (e.g. "sonnet").
+/// The `model` parameter is a full API model ID (e.g. "claude-sonnet-4-6") or a
+/// CLI alias ("sonnet", "opus", "haiku", "latest") resolved via
+/// `model_list::resolve_model_alias`.
 pub async fn stream_chat(
-    _source: &str,
+    source: &str,
     messages: Vec<ChatMsg>,
     model: &str,
+    options: &ChatOptions,
     on_chunk: impl Fn(&str),
 ) -> Result<(), String> {
+    stream_chat_with_usage(source, messages, model, options, on_chunk)
+        .await
+        .map(|_| ())
+}
+
+/// Same as `stream_chat`, but also returns the token usage the API reported.
+pub async fn stream_chat_with_usage(
+    source: &str,
+    messages: Vec<ChatMsg>,
+    model: &str,
+    options: &ChatOptions,
+    on_chunk: impl Fn(&str),
+) -> Result<ChatUsage, String> {
+    // Unknown names are passed through so the API reports them
+    let model = model_list::resolve_model_alias(source, model)
+        .await
+        .unwrap_or_else(|| model.to_string());
+    let model = model.as_str();
+
     let (api_key, base_url) = cli_config::get_credentials("claude");
     if api_key.is_empty() {
         return Err(
@@ -31,16 +124,11 @@ pub async fn stream_chat(
     eprintln!("[quick_chat] model={}, base_url={}", model, base_url);
 
     let url = format!("{}/v1/messages", base_url.trim_end_matches('/'));
-    let client = Client::builder()
-        .connect_timeout(Duration::from_secs(15))
-        .timeout(Duration::from_secs(300))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let client = http::client_with_connect_timeout(CHAT_CONNECT_TIMEOUT)?;
 
-    let api_messages: Vec<serde_json::Value> = messages
-        .into_iter()
-        .map(|m| serde_json::json!({ "role": m.role, "content": m.content }))
-        .collect();
+    // Keep the prompt only when a transcript log is configured
+    let log = settings::chat_log_path().map(|path| (path, messages.clone()));
+    let mut api_messages = to_api_messages(messages, options.prompt_caching);
 
     let body = serde_json::json!({
         "model": model,
@@ -49,21 +137,239 @@ pub async fn stream_chat(
         "messages": api_messages,
     });
 
-    let resp = client
-        .post(&url)
-        .header("x-api-key", &api_key)
+    let mut partial = String::new();
+    let mut usage = ChatUsage::default();
+    let err = match stream_once(
+        &client,
+        &url,
+        &api_key,
+        &body,
+        &on_chunk,
+        options.debug_raw,
+        &mut partial,
+        &mut usage,
+    )
+    .await?
+    {
+        StreamOutcome::Complete => {
+            log_exchange(log.as_ref(), model, &partial, &usage);
+            return Ok(usage);
+        }
+        StreamOutcome::Interrupted(e) => e,
+    };
+
+    // Nothing was streamed yet, or the caller didn't opt in: surface the error
+    if !options.auto_resume || partial.trim().is_empty() {
+        return Err(format!("Stream interrupted: {}", err));
+    }
+
+    eprintln!(
+        "[quick_chat] stream interrupted after {} chars, resuming: {}",
+        partial.len(),
+        err
+    );
+
+    // The API rejects an assistant prefill that ends with whitespace
+    api_messages.push(serde_json::json!({
+        "role": "assistant",
+        "content": partial.trim_end(),
+    }));
+    let resume_body = serde_json::json!({
+        "model": model,
+        "max_tokens": 16384,
+        "stream": true,
+        "messages": api_messages,
+    });
+
+    match stream_once(
+        &client,
+        &url,
+        &api_key,
+        &resume_body,
+        &on_chunk,
+        options.debug_raw,
+        &mut partial,
+        &mut usage,
+    )
+    .await?
+    {
+        StreamOutcome::Complete => {
+            log_exchange(log.as_ref(), model, &partial, &usage);
+            Ok(usage)
+        }
+        StreamOutcome::Interrupted(e) => Err(format!("Stream interrupted after resume: {}", e)),
+    }
+}
+
+/// Append a completed exchange to the configured log; failures are reported
+/// but never fail the chat itself
+fn log_exchange(
+    log: Option<&(PathBuf, Vec<ChatMsg>)>,
+    model: &str,
+    reply: &str,
+    usage: &ChatUsage,
+) {
+    if let Some((path, messages)) = log {
+        if let Err(e) = append_chat_log(path, model, messages, reply, usage) {
+            eprintln!("[quick_chat] {}", e);
+        }
+    }
+}
+
+/// Append one exchange as a single NDJSON line: timestamp, model, the prompt
+/// messages, the assembled reply and token usage. Credentials are never written.
+/// The line goes out in one append write so concurrent chats don't interleave.
+pub fn append_chat_log(
+    path: &Path,
+    model: &str,
+    messages: &[ChatMsg],
+    reply: &str,
+    usage: &ChatUsage,
+) -> Result<(), String> {
+    let entry = serde_json::json!({
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "model": model,
+        "messages": messages,
+        "reply": reply,
+        "usage": usage,
+    });
+    let mut line = serde_json::to_string(&entry)
+        .map_err(|e| format!("Failed to serialize chat log entry: {}", e))?;
+    line.push('\n');
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create chat log directory: {}", e))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open chat log: {}", e))?;
+    file.write_all(line.as_bytes())
+        .map_err(|e| format!("Failed to write chat log: {}", e))
+}
+
+/// Current format version of exported chat templates
+pub const CHAT_TEMPLATE_VERSION: u32 = 1;
+
+/// A saved request configuration: everything `stream_chat` needs apart from
+/// credentials, which are resolved from the CLI config at send time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChatTemplate {
+    version: u32,
+    model: String,
+    #[serde(default)]
+    options: ChatOptions,
+    messages: Vec<ChatMsg>,
+}
+
+/// Serialize a chat setup (model, options and messages) as a versioned,
+/// pretty-printed JSON template.
+pub fn export_chat_template(messages: &[ChatMsg], model: &str, options: &ChatOptions) -> String {
+    let template = ChatTemplate {
+        version: CHAT_TEMPLATE_VERSION,
+        model: model.to_string(),
+        options: options.clone(),
+        messages: messages.to_vec(),
+    };
+    serde_json::to_string_pretty(&template).unwrap_or_default()
+}
+
+/// Load a template written by `export_chat_template`. Templates from a newer
+/// version are rejected rather than half-read.
+pub fn import_chat_template(json: &str) -> Result<(Vec<ChatMsg>, String, ChatOptions), String> {
+    let template: ChatTemplate =
+        serde_json::from_str(json).map_err(|e| format!("Invalid chat template: {}", e))?;
+    if template.version > CHAT_TEMPLATE_VERSION {
+        return Err(format!(
+            "Chat template version {} is newer than supported version {}",
+            template.version, CHAT_TEMPLATE_VERSION
+        ));
+    }
+    if template.model.trim().is_empty() {
+        return Err("Chat template has no model".to_string());
+    }
+    Ok((template.messages, template.model, template.options))
+}
+
+/// Convert chat messages to API messages. Messages with `blocks` send them
+/// as-is. With `prompt_caching`, the last block of the last message carries an
+/// ephemeral `cache_control` breakpoint, which caches everything up to and
+/// including it.
+fn to_api_messages(messages: Vec<ChatMsg>, prompt_caching: bool) -> Vec<serde_json::Value> {
+    let last = messages.len().saturating_sub(1);
+    messages
+        .into_iter()
+        .enumerate()
+        .map(|(i, m)| {
+            let cache = prompt_caching && i == last;
+            let mut blocks = match m.blocks {
+                Some(blocks) if !blocks.is_empty() => blocks,
+                _ if !cache => return serde_json::json!({ "role": m.role, "content": m.content }),
+                _ => vec![serde_json::json!({ "type": "text", "text": m.content })],
+            };
+            if cache {
+                if let Some(serde_json::Value::Object(block)) = blocks.last_mut() {
+                    block.insert(
+                        "cache_control".to_string(),
+                        serde_json::json!({ "type": "ephemeral" }),
+                    );
+                }
+            }
+            serde_json::json!({ "role": m.role, "content": blocks })
+        })
+        .collect()
+}
+
+/// Run a completion and collect the streamed reply into one string.
+pub async fn chat(source: &str, messages: Vec<ChatMsg>, model: &str) -> Result<String, String> {
+    let reply = parking_lot::Mutex::new(String::new());
+    stream_chat(source, messages, model, &ChatOptions::default(), |chunk| {
+        reply.lock().push_str(chunk);
+    })
+    .await?;
+    Ok(reply.into_inner())
+}
+
+/// Send one streaming request and forward text deltas to `on_chunk`,
+/// appending them to `partial` so the caller can resume after a disconnect.
+/// The attempt's token usage is added to `usage`. With `debug_raw` every data
+/// line is logged before it is parsed or filtered.
+#[allow(clippy::too_many_arguments)]
+async fn stream_once(
+    client: &Client,
+    url: &str,
+    api_key: &str,
+    body: &serde_json::Value,
+    on_chunk: &impl Fn(&str),
+    debug_raw: bool,
+    partial: &mut String,
+    usage: &mut ChatUsage,
+) -> Result<StreamOutcome, String> {
+    let request = client
+        .post(url)
+        .timeout(CHAT_TIMEOUT)
+        .header("x-api-key", api_key)
         .header("anthropic-version", "2023-06-01")
-        .header("content-type", "application/json")
-        .json(&body)
+        .header("content-type", "application/json");
+    let resp = http::with_custom_headers(request)
+        .json(body)
         .send()
         .await
         .map_err(|e| format!("Anthropic API request failed: {}", e))?;
 
+    let request_id = http::request_id(&resp);
     if !resp.status().is_success() {
         let status = resp.status();
-        let text = resp.text().await.unwrap_or_default();
-        eprintln!("[quick_chat] Anthropic API error {}: {}", status, text);
-        return Err(format!("API Error: {} {}", status, text));
+        let suffix = http::request_id_suffix(request_id.as_deref());
+        let text = http::read_text_capped(resp).await;
+        eprintln!(
+            "[quick_chat] Anthropic API error {}{}: {}",
+            status, suffix, text
+        );
+        return Err(format!("API Error: {} {}{}", status, text, suffix));
     }
 
     // Parse SSE stream
@@ -74,8 +380,21 @@ pub async fn stream_chat(
     let stream = resp.bytes_stream().map_err(std::io::Error::other);
     let reader = StreamReader::new(stream);
     let mut lines = reader.lines();
+    // message_delta carries a running output total, so track this attempt separately
+    let mut attempt = ChatUsage {
+        request_id,
+        ..Default::default()
+    };
 
-    while let Ok(Some(line)) = lines.next_line().await {
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(l)) => l,
+            Ok(None) => break,
+            Err(e) => {
+                usage.add(&attempt);
+                return Ok(StreamOutcome::Interrupted(e.to_string()));
+            }
+        };
         let line = line.trim().to_string();
         if !line.starts_with("data: ") {
             continue;
@@ -87,24 +406,414 @@ pub async fn stream_chat(
 
         let json: serde_json::Value = match serde_json::from_str(data) {
             Ok(v) => v,
-            Err(_) => continue,
+            Err(e) => {
+                if debug_raw {
+                    tracing::debug!("[quick_chat] raw unparsable ({}): {}", e, data);
+                }
+                continue;
+            }
         };
+        if debug_raw {
+            let event_type = json.get("type").and_then(|v| v.as_str()).unwrap_or("?");
+            tracing::debug!("[quick_chat] raw {}: {}", event_type, data);
+        }
 
-        // Anthropic SSE: content_block_delta with delta.text
-        if let Some(event_type) = json.get("type").and_then(|v| v.as_str()) {
-            if event_type == "content_block_delta" {
+        let token_count = |v: &serde_json::Value, key: &str| {
+            v.get("usage")
+                .and_then(|u| u.get(key))
+                .and_then(|n| n.as_u64())
+        };
+        // Anthropic SSE: content_block_delta with delta.text, usage on message_start/message_delta
+        match json.get("type").and_then(|v| v.as_str()) {
+            Some("content_block_delta") => {
                 if let Some(text) = json
                     .get("delta")
                     .and_then(|d| d.get("text"))
                     .and_then(|v| v.as_str())
                 {
                     if !text.is_empty() {
+                        partial.push_str(text);
                         on_chunk(text);
                     }
                 }
             }
+            Some("message_start") => {
+                if let Some(message) = json.get("message") {
+                    attempt.input_tokens = token_count(message, "input_tokens").unwrap_or(0);
+                    attempt.output_tokens = token_count(message, "output_tokens").unwrap_or(0);
+                    attempt.cache_creation_input_tokens =
+                        token_count(message, "cache_creation_input_tokens").unwrap_or(0);
+                    attempt.cache_read_input_tokens =
+                        token_count(message, "cache_read_input_tokens").unwrap_or(0);
+                }
+            }
+            Some("message_delta") => {
+                if let Some(n) = token_count(&json, "output_tokens") {
+                    attempt.output_tokens = n;
+                }
+            }
+            _ => {}
         }
     }
 
-    Ok(())
+    usage.add(&attempt);
+    Ok(StreamOutcome::Complete)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{sse_events, MockResponse, MockServer, TestHome};
+    use serde_json::json;
+
+    fn text_delta(text: &str) -> serde_json::Value {
+        json!({ "type": "content_block_delta", "delta": { "type": "text_delta", "text": text } })
+    }
+
+    /// Start a server whose first reply drops after "Hello, wor" and whose
+    /// second completes the sentence, and point the Anthropic env at it
+    fn disconnecting_server() -> MockServer {
+        let server = MockServer::start(vec![
+            MockResponse::sse_cut_off(&sse_events(&[text_delta("Hello, "), text_delta("wor")])),
+            MockResponse::sse(&sse_events(&[
+                text_delta("ld!"),
+                json!({ "type": "message_stop" }),
+            ])),
+        ]);
+        std::env::set_var("ANTHROPIC_API_KEY", "sk-test");
+        std::env::set_var("ANTHROPIC_BASE_URL", &server.url);
+        server
+    }
+
+    async fn run(options: &ChatOptions) -> (Result<ChatUsage, String>, String) {
+        let reply = parking_lot::Mutex::new(String::new());
+        let messages = vec![ChatMsg {
+            role: "user".to_string(),
+            content: "Say hello".to_string(),
+            blocks: None,
+        }];
+        let result = stream_chat_with_usage("claude", messages, "claude-test", options, |chunk| {
+            reply.lock().push_str(chunk)
+        })
+        .await;
+        (result, reply.into_inner())
+    }
+
+    #[tokio::test]
+    async fn auto_resume_continues_after_a_mid_stream_disconnect() {
+        let _home = TestHome::new();
+        let server = disconnecting_server();
+        let options = ChatOptions {
+            auto_resume: true,
+            ..Default::default()
+        };
+
+        let (result, reply) = run(&options).await;
+        assert!(result.is_ok(), "{:?}", result);
+        assert_eq!(reply, "Hello, world!");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        let resumed: serde_json::Value = serde_json::from_str(&requests[1].body).unwrap();
+        let messages = resumed["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[1],
+            json!({ "role": "assistant", "content": "Hello, wor" })
+        );
+    }
+
+    #[tokio::test]
+    async fn disconnect_is_reported_without_auto_resume() {
+        let _home = TestHome::new();
+        let server = disconnecting_server();
+
+        let (result, reply) = run(&ChatOptions::default()).await;
+        let err = result.unwrap_err();
+        assert!(err.starts_with("Stream interrupted:"), "{}", err);
+        assert_eq!(reply, "Hello, wor");
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    fn user(content: &str) -> ChatMsg {
+        ChatMsg {
+            role: "user".to_string(),
+            content: content.to_string(),
+            blocks: None,
+        }
+    }
+
+    /// Start a server that streams one short reply whose usage includes cache
+    /// reads and writes
+    fn caching_server() -> MockServer {
+        let server = MockServer::start(vec![MockResponse::sse(&sse_events(&[
+            json!({ "type": "message_start", "message": { "usage": {
+                "input_tokens": 12,
+                "output_tokens": 1,
+                "cache_creation_input_tokens": 2048,
+                "cache_read_input_tokens": 4096,
+            } } }),
+            text_delta("ok"),
+            json!({ "type": "message_delta", "usage": { "output_tokens": 3 } }),
+        ]))]);
+        std::env::set_var("ANTHROPIC_API_KEY", "sk-test");
+        std::env::set_var("ANTHROPIC_BASE_URL", &server.url);
+        server
+    }
+
+    async fn sent_messages(prompt_caching: bool) -> (serde_json::Value, ChatUsage) {
+        let server = caching_server();
+        let options = ChatOptions {
+            prompt_caching,
+            ..Default::default()
+        };
+        let messages = vec![user("large context"), user("question about it")];
+        let usage = stream_chat_with_usage("claude", messages, "claude-test", &options, |_| {})
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_str(&server.requests()[0].body).unwrap();
+        (body["messages"].clone(), usage)
+    }
+
+    #[tokio::test]
+    async fn prompt_caching_marks_the_last_message() {
+        let _home = TestHome::new();
+        let (messages, usage) = sent_messages(true).await;
+
+        assert_eq!(
+            messages[0],
+            json!({ "role": "user", "content": "large context" })
+        );
+        assert_eq!(
+            messages[1]["content"],
+            json!([{
+                "type": "text",
+                "text": "question about it",
+                "cache_control": { "type": "ephemeral" },
+            }])
+        );
+        assert_eq!(usage.cache_creation_input_tokens, 2048);
+        assert_eq!(usage.cache_read_input_tokens, 4096);
+        assert_eq!((usage.input_tokens, usage.output_tokens), (12, 3));
+    }
+
+    #[tokio::test]
+    async fn no_cache_marker_by_default() {
+        let _home = TestHome::new();
+        let (messages, _) = sent_messages(false).await;
+
+        assert!(!messages.to_string().contains("cache_control"));
+        assert_eq!(
+            messages[1],
+            json!({ "role": "user", "content": "question about it" })
+        );
+    }
+
+    /// Turn on the transcript log and return its path
+    fn enable_chat_log(home: &TestHome) -> PathBuf {
+        let path = home.path().join("logs/chat.ndjson");
+        settings::save_settings(&settings::SettingsFile {
+            chat_log_path: Some(path.display().to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn a_completed_exchange_is_logged_as_one_ndjson_line() {
+        let home = TestHome::new();
+        let log = enable_chat_log(&home);
+        let (_, usage) = sent_messages(false).await;
+
+        let content = std::fs::read_to_string(&log).unwrap();
+        assert!(content.ends_with('\n'));
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 1);
+        let entry: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(entry["model"], "claude-test");
+        assert_eq!(entry["messages"][1]["content"], "question about it");
+        assert_eq!(entry["reply"], "ok");
+        assert_eq!(entry["usage"], serde_json::to_value(&usage).unwrap());
+        assert!(chrono::DateTime::parse_from_rfc3339(entry["timestamp"].as_str().unwrap()).is_ok());
+        assert!(!content.contains("sk-test"));
+    }
+
+    #[tokio::test]
+    async fn a_failed_exchange_is_not_logged() {
+        let home = TestHome::new();
+        let log = enable_chat_log(&home);
+        let _server = disconnecting_server();
+
+        let (result, _) = run(&ChatOptions::default()).await;
+        assert!(result.is_err());
+        assert!(!log.exists());
+    }
+
+    /// Collects log output written on this thread while the guard from `start` is held
+    #[derive(Clone, Default)]
+    struct CapturedLogs(std::sync::Arc<parking_lot::Mutex<Vec<u8>>>);
+
+    impl Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl CapturedLogs {
+        fn start(&self) -> tracing::subscriber::DefaultGuard {
+            let logs = self.clone();
+            let subscriber = tracing_subscriber::fmt()
+                .with_max_level(tracing::Level::DEBUG)
+                .with_ansi(false)
+                .with_writer(move || logs.clone())
+                .finish();
+            tracing::subscriber::set_default(subscriber)
+        }
+
+        fn text(&self) -> String {
+            String::from_utf8_lossy(&self.0.lock()).into_owned()
+        }
+    }
+
+    const EVENT_TYPES: [&str; 7] = [
+        "message_start",
+        "content_block_start",
+        "ping",
+        "content_block_delta",
+        "content_block_stop",
+        "message_delta",
+        "message_stop",
+    ];
+
+    async fn chat_logging_raw_events(debug_raw: bool) -> String {
+        let mut events: Vec<serde_json::Value> =
+            EVENT_TYPES.iter().map(|t| json!({ "type": t })).collect();
+        events[3] = text_delta("hi");
+        let body = sse_events(&events) + "data: {not json\n\n";
+        let server = MockServer::start(vec![MockResponse::sse(&body)]);
+        std::env::set_var("ANTHROPIC_API_KEY", "sk-secret-key");
+        std::env::set_var("ANTHROPIC_BASE_URL", &server.url);
+
+        let logs = CapturedLogs::default();
+        let _guard = logs.start();
+        let options = ChatOptions {
+            debug_raw,
+            ..Default::default()
+        };
+        let (result, reply) = run(&options).await;
+        assert!(result.is_ok(), "{:?}", result);
+        assert_eq!(reply, "hi");
+        logs.text()
+    }
+
+    #[tokio::test]
+    async fn debug_raw_logs_every_event_type() {
+        let _home = TestHome::new();
+        let logs = chat_logging_raw_events(true).await;
+
+        for event_type in EVENT_TYPES {
+            assert!(
+                logs.contains(&format!("raw {}: ", event_type)),
+                "{} missing from {}",
+                event_type,
+                logs
+            );
+        }
+        assert!(logs.contains("raw unparsable"));
+        assert!(!logs.contains("sk-secret-key"));
+    }
+
+    #[tokio::test]
+    async fn raw_events_are_not_logged_by_default() {
+        let _home = TestHome::new();
+        let logs = chat_logging_raw_events(false).await;
+        assert!(!logs.contains("[quick_chat] raw"), "{}", logs);
+    }
+
+    #[test]
+    fn chat_template_round_trip_keeps_every_option() {
+        let messages = vec![
+            user("read a.rs"),
+            ChatMsg {
+                role: "assistant".to_string(),
+                content: String::new(),
+                blocks: Some(vec![json!({
+                    "type": "tool_use", "id": "t1", "name": "Read", "input": { "path": "a.rs" }
+                })]),
+            },
+        ];
+        let options = ChatOptions {
+            auto_resume: true,
+            prompt_caching: true,
+            debug_raw: true,
+        };
+
+        let template = export_chat_template(&messages, "claude-opus-4-6", &options);
+        let (loaded, model, loaded_options) = import_chat_template(&template).unwrap();
+        assert_eq!(model, "claude-opus-4-6");
+        assert_eq!(json!(loaded), json!(messages));
+        assert_eq!(json!(loaded_options), json!(options));
+        let value: serde_json::Value = serde_json::from_str(&template).unwrap();
+        assert_eq!(value["version"], CHAT_TEMPLATE_VERSION);
+    }
+
+    #[test]
+    fn newer_or_modelless_templates_are_rejected() {
+        let newer = json!({ "version": CHAT_TEMPLATE_VERSION + 1, "model": "m", "messages": [] });
+        let err = import_chat_template(&newer.to_string()).unwrap_err();
+        assert!(err.contains("newer than supported"), "{}", err);
+        let blank = json!({ "version": 1, "model": " ", "messages": [] });
+        assert!(import_chat_template(&blank.to_string()).is_err());
+        assert!(import_chat_template("not json").is_err());
+    }
+
+    #[tokio::test]
+    async fn custom_headers_are_sent_without_replacing_the_key() {
+        let _home = TestHome::new();
+        settings::save_settings(&settings::SettingsFile {
+            custom_headers: Some(std::collections::HashMap::from([
+                ("X-Org-Id".to_string(), "org-42".to_string()),
+                ("x-api-key".to_string(), "sk-override".to_string()),
+            ])),
+            ..Default::default()
+        })
+        .unwrap();
+        let server = MockServer::start(vec![MockResponse::sse(&sse_events(&[text_delta("hi")]))]);
+        std::env::set_var("ANTHROPIC_API_KEY", "sk-test");
+        std::env::set_var("ANTHROPIC_BASE_URL", &server.url);
+
+        let (result, _) = run(&ChatOptions::default()).await;
+        assert!(result.is_ok(), "{:?}", result);
+        let requests = server.requests();
+        assert_eq!(requests[0].header("x-org-id"), Some("org-42"));
+        assert_eq!(requests[0].header("x-api-key"), Some("sk-test"));
+        assert_eq!(requests[0].header("anthropic-version"), Some("2023-06-01"));
+    }
+
+    #[tokio::test]
+    async fn api_errors_quote_the_request_id() {
+        let _home = TestHome::new();
+        let server = MockServer::start(vec![MockResponse::json(
+            529,
+            r#"{"error": {"type": "overloaded_error"}}"#,
+            &[("request-id", "req_011CSHoEeqs5C35K2UUqR7Fy")],
+        )]);
+        std::env::set_var("ANTHROPIC_API_KEY", "sk-secret-key");
+        std::env::set_var("ANTHROPIC_BASE_URL", &server.url);
+
+        let err = run(&ChatOptions::default()).await.0.unwrap_err();
+        assert!(err.starts_with("API Error: 529"), "{}", err);
+        assert!(
+            err.ends_with(" (request-id: req_011CSHoEeqs5C35K2UUqR7Fy)"),
+            "{}",
+            err
+        );
+        assert!(!err.contains("sk-secret-key"));
+    }
 }
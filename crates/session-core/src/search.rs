@@ -1,10 +1,19 @@
 use rayon::prelude::*;
 use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
+use crate::fuzzy;
 use crate::metadata;
-use crate::models::message::DisplayContentBlock;
+use crate::models::message::{DisplayContentBlock, DisplayMessage, Role};
+use crate::models::session::SessionIndexEntry;
+use crate::parser::preview::truncate_chars;
 use crate::provider::{claude, codex};
+use crate::settings;
+use crate::stats;
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -20,16 +29,9 @@ pub struct SearchResult {
     pub role: String,
     pub timestamp: Option<String>,
     pub file_path: String,
-}
-
-/// Safely truncate a string to approximately `max_chars` characters
-fn safe_truncate(s: &str, max_chars: usize) -> String {
-    let truncated: String = s.chars().take(max_chars).collect();
-    if truncated.len() < s.len() {
-        format!("{}...", truncated)
-    } else {
-        truncated
-    }
+    /// Match score (fuzzy searches only; higher is better)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<u32>,
 }
 
 /// Extract a context window around a match, operating on characters (not bytes)
@@ -51,12 +53,25 @@ fn extract_context(text: &str, query_lower: &str, context_chars: usize) -> Strin
             let end = (pos + query_len + context_chars).min(text_chars.len());
             text_chars[start..end].iter().collect()
         }
-        None => safe_truncate(text, context_chars * 2),
+        None => truncate_chars(text, context_chars * 2),
+    }
+}
+
+/// Score of `text` against the query, `Some` when it matches. Exact mode only
+/// accepts substring matches; fuzzy mode also accepts near matches.
+fn match_score(text: &str, query_lower: &str, fuzzy: bool) -> Option<u32> {
+    let text_lower = text.to_lowercase();
+    if fuzzy {
+        fuzzy::score(query_lower, &text_lower)
+    } else if text_lower.contains(query_lower) {
+        Some(fuzzy::EXACT_SCORE)
+    } else {
+        None
     }
 }
 
 /// Extract searchable text from a DisplayContentBlock
-fn block_text(block: &DisplayContentBlock) -> &str {
+pub(crate) fn block_text(block: &DisplayContentBlock) -> &str {
     match block {
         DisplayContentBlock::Text { text } => text,
         DisplayContentBlock::Thinking { thinking } => thinking,
@@ -68,201 +83,516 @@ fn block_text(block: &DisplayContentBlock) -> &str {
     }
 }
 
+/// Search all sessions of a source. Exact substring matching by default;
+/// `fuzzy` tolerates typos and word reordering and ranks results by score.
 pub fn global_search(
     source: &str,
     query: &str,
     max_results: usize,
+    fuzzy: bool,
 ) -> Result<Vec<SearchResult>, String> {
     let query_lower = query.to_lowercase();
 
     let results: Vec<SearchResult> = match source {
-        "claude" => search_claude(&query_lower, max_results),
-        "codex" => search_codex(&query_lower, max_results),
+        "claude" => search_claude(&query_lower, max_results, fuzzy),
+        "codex" => search_codex(&query_lower, max_results, fuzzy),
         _ => return Err(format!("Unknown source: {}", source)),
     };
 
     Ok(results)
 }
 
-fn search_claude(query_lower: &str, max_results: usize) -> Vec<SearchResult> {
-    let jsonl_files = claude::collect_all_jsonl_files();
+/// Like `global_search`, but hands each match to `on_match` as soon as its
+/// session has been scanned instead of collecting them all first.
+///
+/// The scan stops after `max_results` matches, when `on_match` returns false
+/// (e.g. the receiver went away), or when `is_cancelled` returns true, which
+/// is checked before each file. Returns the number of matches delivered.
+pub fn global_search_streaming(
+    source: &str,
+    query: &str,
+    max_results: usize,
+    is_cancelled: impl Fn() -> bool + Sync,
+    on_match: impl Fn(SearchResult) -> bool + Sync,
+) -> Result<usize, String> {
+    let query_lower = query.to_lowercase();
+    let sent = AtomicUsize::new(0);
 
-    // Pre-load metadata per project for alias lookup
-    let mut meta_cache: std::collections::HashMap<String, metadata::MetadataFile> =
-        std::collections::HashMap::new();
-    for (encoded_name, _, _) in &jsonl_files {
-        meta_cache
-            .entry(encoded_name.clone())
-            .or_insert_with(|| metadata::load_metadata("claude", encoded_name));
+    // Err(()) short-circuits the parallel scan
+    let deliver = |file_results: Vec<SearchResult>| -> Result<(), ()> {
+        for result in file_results {
+            if sent.fetch_add(1, Ordering::SeqCst) >= max_results || !on_match(result) {
+                return Err(());
+            }
+        }
+        Ok(())
+    };
+    let should_stop = || is_cancelled() || sent.load(Ordering::SeqCst) >= max_results;
+    let preview_chars = settings::preview_max_chars();
+
+    match source {
+        "claude" => {
+            let files = claude::collect_all_jsonl_files();
+            let meta_cache = claude_meta_cache(&files);
+            let _ = files
+                .par_iter()
+                .try_for_each(|(encoded_name, project_name, file_path)| {
+                    if should_stop() {
+                        return Err(());
+                    }
+                    deliver(search_claude_file(
+                        &query_lower,
+                        false,
+                        encoded_name,
+                        project_name,
+                        file_path,
+                        &meta_cache,
+                        preview_chars,
+                    ))
+                });
+        }
+        "codex" => {
+            let files = codex::scan_all_session_files();
+            let codex_meta = metadata::load_metadata("codex", "");
+            let _ = files.par_iter().try_for_each(|file_path| {
+                if should_stop() {
+                    return Err(());
+                }
+                deliver(search_codex_file(
+                    &query_lower,
+                    false,
+                    file_path,
+                    &codex_meta,
+                    preview_chars,
+                ))
+            });
+        }
+        _ => return Err(format!("Unknown source: {}", source)),
     }
 
+    Ok(sent.load(Ordering::SeqCst).min(max_results))
+}
+
+fn search_claude(query_lower: &str, max_results: usize, fuzzy: bool) -> Vec<SearchResult> {
+    let jsonl_files = claude::collect_all_jsonl_files();
+    let meta_cache = claude_meta_cache(&jsonl_files);
+    let preview_chars = settings::preview_max_chars();
+
     let results: Vec<SearchResult> = jsonl_files
         .par_iter()
         .flat_map(|(encoded_name, project_name, file_path)| {
-            let session_id = file_path
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("")
-                .to_string();
+            search_claude_file(
+                query_lower,
+                fuzzy,
+                encoded_name,
+                project_name,
+                file_path,
+                &meta_cache,
+                preview_chars,
+            )
+        })
+        .collect();
 
-            let mut file_results: Vec<SearchResult> = Vec::new();
+    rank_and_truncate(results, max_results, fuzzy)
+}
 
-            let content = match fs::read_to_string(file_path) {
-                Ok(c) => c,
-                Err(_) => return file_results,
-            };
+/// Keep the first `max_results`, best scores first in fuzzy mode
+fn rank_and_truncate(
+    mut results: Vec<SearchResult>,
+    max_results: usize,
+    fuzzy: bool,
+) -> Vec<SearchResult> {
+    if fuzzy {
+        results.sort_by_key(|r| std::cmp::Reverse(r.score));
+    }
+    results.truncate(max_results);
+    results
+}
 
-            if !content.to_lowercase().contains(query_lower) {
-                return file_results;
-            }
+/// Pre-load metadata per project for alias lookup
+fn claude_meta_cache(
+    jsonl_files: &[(String, String, PathBuf)],
+) -> HashMap<String, metadata::MetadataFile> {
+    let mut meta_cache: HashMap<String, metadata::MetadataFile> = HashMap::new();
+    for (encoded_name, _, _) in jsonl_files {
+        meta_cache
+            .entry(encoded_name.clone())
+            .or_insert_with(|| metadata::load_metadata("claude", encoded_name));
+    }
+    meta_cache
+}
 
-            // Lookup alias and tags from metadata
-            let session_meta = meta_cache
-                .get(encoded_name)
-                .and_then(|m| m.sessions.get(&session_id));
-            let alias = session_meta.and_then(|s| s.alias.clone());
-            let tags = session_meta
-                .map(|s| s.tags.clone())
-                .filter(|t| !t.is_empty());
-
-            if let Ok(messages) = claude::parse_all_messages(file_path) {
-                let mut first_prompt = None;
-                for msg in &messages {
-                    if msg.role == "user" && first_prompt.is_none() {
-                        for block in &msg.content {
-                            if let DisplayContentBlock::Text { text } = block {
-                                first_prompt = Some(safe_truncate(text, 100));
-                                break;
-                            }
-                        }
-                    }
+/// Up to 5 matches from one Claude session file
+fn search_claude_file(
+    query_lower: &str,
+    fuzzy: bool,
+    encoded_name: &str,
+    project_name: &str,
+    file_path: &Path,
+    meta_cache: &HashMap<String, metadata::MetadataFile>,
+    preview_chars: usize,
+) -> Vec<SearchResult> {
+    let session_id = file_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_string();
+
+    let mut file_results: Vec<SearchResult> = Vec::new();
+
+    let content = match fs::read_to_string(file_path) {
+        Ok(c) => c,
+        Err(_) => return file_results,
+    };
 
-                    for block in &msg.content {
-                        let text = block_text(block);
-
-                        if text.to_lowercase().contains(query_lower) {
-                            let matched_text = extract_context(text, query_lower, 50);
-
-                            file_results.push(SearchResult {
-                                source: "claude".to_string(),
-                                project_id: encoded_name.clone(),
-                                project_name: project_name.clone(),
-                                session_id: session_id.clone(),
-                                first_prompt: first_prompt.clone(),
-                                alias: alias.clone(),
-                                tags: tags.clone(),
-                                matched_text,
-                                role: msg.role.clone(),
-                                timestamp: msg.timestamp.clone(),
-                                file_path: file_path.to_string_lossy().to_string(),
-                            });
-
-                            if file_results.len() >= 5 {
-                                return file_results;
-                            }
-                        }
+    // Fuzzy matches can't be prefiltered by substring
+    if !fuzzy && !content.to_lowercase().contains(query_lower) {
+        return file_results;
+    }
+
+    // Lookup alias and tags from metadata
+    let session_meta = meta_cache
+        .get(encoded_name)
+        .and_then(|m| m.sessions.get(&session_id));
+    let alias = session_meta.and_then(|s| s.alias.clone());
+    let tags = session_meta
+        .map(|s| s.tags.clone())
+        .filter(|t| !t.is_empty());
+
+    if let Ok(messages) = claude::parse_all_messages(file_path) {
+        let mut first_prompt = None;
+        for msg in &messages {
+            if msg.role == Role::User && first_prompt.is_none() {
+                for block in &msg.content {
+                    if let DisplayContentBlock::Text { text } = block {
+                        first_prompt = Some(truncate_chars(text, preview_chars));
+                        break;
                     }
                 }
             }
 
-            file_results
-        })
-        .collect();
+            for block in &msg.content {
+                let text = block_text(block);
+
+                if let Some(score) = match_score(text, query_lower, fuzzy) {
+                    let matched_text = extract_context(text, query_lower, preview_chars / 2);
+
+                    file_results.push(SearchResult {
+                        source: "claude".to_string(),
+                        project_id: encoded_name.to_string(),
+                        project_name: project_name.to_string(),
+                        session_id: session_id.clone(),
+                        first_prompt: first_prompt.clone(),
+                        alias: alias.clone(),
+                        tags: tags.clone(),
+                        matched_text,
+                        role: msg.role.as_str().to_string(),
+                        timestamp: msg.timestamp.clone(),
+                        file_path: file_path.to_string_lossy().to_string(),
+                        score: fuzzy.then_some(score),
+                    });
+
+                    if file_results.len() >= 5 {
+                        return file_results;
+                    }
+                }
+            }
+        }
+    }
 
-    let mut results = results;
-    results.truncate(max_results);
-    results
+    file_results
 }
 
-fn search_codex(query_lower: &str, max_results: usize) -> Vec<SearchResult> {
+fn search_codex(query_lower: &str, max_results: usize, fuzzy: bool) -> Vec<SearchResult> {
     let files = codex::scan_all_session_files();
 
     // Pre-load codex metadata (single file for all sessions)
     let codex_meta = metadata::load_metadata("codex", "");
+    let preview_chars = settings::preview_max_chars();
 
     let results: Vec<SearchResult> = files
         .par_iter()
         .flat_map(|file_path| {
-            let mut file_results: Vec<SearchResult> = Vec::new();
+            search_codex_file(query_lower, fuzzy, file_path, &codex_meta, preview_chars)
+        })
+        .collect();
 
-            let content = match fs::read_to_string(file_path) {
-                Ok(c) => c,
-                Err(_) => return file_results,
-            };
+    rank_and_truncate(results, max_results, fuzzy)
+}
 
-            if !content.to_lowercase().contains(query_lower) {
-                return file_results;
-            }
+/// Up to 5 matches from one Codex session file
+fn search_codex_file(
+    query_lower: &str,
+    fuzzy: bool,
+    file_path: &Path,
+    codex_meta: &metadata::MetadataFile,
+    preview_chars: usize,
+) -> Vec<SearchResult> {
+    let mut file_results: Vec<SearchResult> = Vec::new();
+
+    let content = match fs::read_to_string(file_path) {
+        Ok(c) => c,
+        Err(_) => return file_results,
+    };
 
-            let meta = codex::extract_session_meta(file_path);
-            let (session_id, cwd) = match &meta {
-                Some(m) => (m.id.clone(), m.cwd.clone()),
-                None => {
-                    let stem = file_path
-                        .file_stem()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or("")
-                        .to_string();
-                    (stem, String::new())
-                }
-            };
-            let short_name = cwd
-                .rsplit(['/', '\\'])
-                .find(|s| !s.is_empty())
-                .unwrap_or(&cwd)
-                .to_string();
+    // Fuzzy matches can't be prefiltered by substring
+    if !fuzzy && !content.to_lowercase().contains(query_lower) {
+        return file_results;
+    }
 
-            let session_meta = codex_meta.sessions.get(&session_id);
-            let alias = session_meta.and_then(|s| s.alias.clone());
-            let tags = session_meta
-                .map(|s| s.tags.clone())
-                .filter(|t| !t.is_empty());
-
-            if let Ok(messages) = codex::parse_all_messages(file_path) {
-                let mut first_prompt = None;
-                for msg in &messages {
-                    if msg.role == "user" && first_prompt.is_none() {
-                        for block in &msg.content {
-                            if let DisplayContentBlock::Text { text } = block {
-                                first_prompt = Some(safe_truncate(text, 100));
-                                break;
-                            }
-                        }
+    let meta = codex::extract_session_meta(file_path);
+    let (session_id, cwd) = match &meta {
+        Some(m) => (m.id.clone(), m.cwd.clone()),
+        None => {
+            let stem = file_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("")
+                .to_string();
+            (stem, String::new())
+        }
+    };
+    let short_name = cwd
+        .rsplit(['/', '\\'])
+        .find(|s| !s.is_empty())
+        .unwrap_or(&cwd)
+        .to_string();
+
+    let session_meta = codex_meta.sessions.get(&session_id);
+    let alias = session_meta.and_then(|s| s.alias.clone());
+    let tags = session_meta
+        .map(|s| s.tags.clone())
+        .filter(|t| !t.is_empty());
+
+    if let Ok(messages) = codex::parse_all_messages(file_path) {
+        let mut first_prompt = None;
+        for msg in &messages {
+            if msg.role == Role::User && first_prompt.is_none() {
+                for block in &msg.content {
+                    if let DisplayContentBlock::Text { text } = block {
+                        first_prompt = Some(truncate_chars(text, preview_chars));
+                        break;
                     }
+                }
+            }
 
-                    for block in &msg.content {
-                        let text = block_text(block);
-
-                        if text.to_lowercase().contains(query_lower) {
-                            let matched_text = extract_context(text, query_lower, 50);
-
-                            file_results.push(SearchResult {
-                                source: "codex".to_string(),
-                                project_id: cwd.clone(),
-                                project_name: short_name.clone(),
-                                session_id: session_id.clone(),
-                                first_prompt: first_prompt.clone(),
-                                alias: alias.clone(),
-                                tags: tags.clone(),
-                                matched_text,
-                                role: msg.role.clone(),
-                                timestamp: msg.timestamp.clone(),
-                                file_path: file_path.to_string_lossy().to_string(),
-                            });
-
-                            if file_results.len() >= 5 {
-                                return file_results;
-                            }
-                        }
+            for block in &msg.content {
+                let text = block_text(block);
+
+                if let Some(score) = match_score(text, query_lower, fuzzy) {
+                    let matched_text = extract_context(text, query_lower, preview_chars / 2);
+
+                    file_results.push(SearchResult {
+                        source: "codex".to_string(),
+                        project_id: cwd.clone(),
+                        project_name: short_name.clone(),
+                        session_id: session_id.clone(),
+                        first_prompt: first_prompt.clone(),
+                        alias: alias.clone(),
+                        tags: tags.clone(),
+                        matched_text,
+                        role: msg.role.as_str().to_string(),
+                        timestamp: msg.timestamp.clone(),
+                        file_path: file_path.to_string_lossy().to_string(),
+                        score: fuzzy.then_some(score),
+                    });
+
+                    if file_results.len() >= 5 {
+                        return file_results;
                     }
                 }
             }
+        }
+    }
+
+    file_results
+}
+
+/// Keys of tool inputs that hold a file path (Claude tools and Codex calls)
+const PATH_ARGUMENT_KEYS: &[&str] = &["file_path", "filePath", "notebook_path", "path"];
+
+/// A session that mentions a file path, with where it was found
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathMention {
+    #[serde(flatten)]
+    pub session: SessionIndexEntry,
+    /// Text around the first mention
+    pub snippet: String,
+    /// Tool whose call or result mentioned the path (None = message text)
+    pub tool_name: Option<String>,
+}
+
+/// Sessions of a project that mention `needle` (a path or path fragment).
+///
+/// A tool call taking the path as a file argument (`file_path`, `path`, ...)
+/// is preferred as the reported mention; otherwise any tool input, tool
+/// result or message text containing it counts. Slashes are compared
+/// without regard to direction.
+pub fn find_sessions_mentioning_path(
+    source: &str,
+    project_id: &str,
+    needle: &str,
+) -> Result<Vec<PathMention>, String> {
+    let needle = needle.trim().replace('\\', "/");
+    if needle.is_empty() {
+        return Err("Path must not be empty".to_string());
+    }
+    let sessions = stats::list_sessions(source, Some(project_id))?;
+    let context_chars = settings::preview_max_chars() / 2;
+
+    Ok(sessions
+        .into_par_iter()
+        .filter_map(|session| {
+            let messages = match source {
+                "claude" => claude::parse_all_messages(Path::new(&session.file_path)),
+                _ => codex::parse_all_messages(Path::new(&session.file_path)),
+            }
+            .ok()?;
+            let (snippet, tool_name) = find_path_mention(&messages, &needle, context_chars)?;
+            Some(PathMention {
+                session,
+                snippet,
+                tool_name,
+            })
+        })
+        .collect())
+}
 
-            file_results
+/// First mention of `needle` in a session as (snippet, tool name)
+fn find_path_mention(
+    messages: &[DisplayMessage],
+    needle: &str,
+    context_chars: usize,
+) -> Option<(String, Option<String>)> {
+    let contains = |text: &str| text.replace('\\', "/").contains(needle);
+    let needle_lower = needle.to_lowercase();
+    let snippet =
+        |text: &str| extract_context(&text.replace('\\', "/"), &needle_lower, context_chars);
+
+    let calls = messages.iter().flat_map(|m| m.tool_calls.iter());
+    for call in calls.clone() {
+        let input: Value = match serde_json::from_str(&call.input_json) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let path_arg = PATH_ARGUMENT_KEYS
+            .iter()
+            .filter_map(|key| input.get(*key).and_then(|v| v.as_str()))
+            .find(|path| contains(path));
+        if let Some(path) = path_arg {
+            return Some((snippet(path), Some(call.name.clone())));
+        }
+    }
+
+    for call in calls {
+        if contains(&call.input_json) {
+            return Some((snippet(&call.input_json), Some(call.name.clone())));
+        }
+        if let Some(result) = call.result.as_deref().filter(|r| contains(r)) {
+            return Some((snippet(result), Some(call.name.clone())));
+        }
+    }
+
+    messages
+        .iter()
+        .flat_map(|m| m.content.iter())
+        .filter_map(|block| match block {
+            DisplayContentBlock::Text { text } => Some(text.as_str()),
+            _ => None,
         })
-        .collect();
+        .find(|text| contains(text))
+        .map(|text| (snippet(text), None))
+}
 
-    let mut results = results;
-    results.truncate(max_results);
-    results
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{claude_assistant, claude_user, TestHome};
+
+    fn seed(home: &TestHome) {
+        home.claude_session(
+            "-home-user-alpha",
+            "s1",
+            &[
+                claude_user("u1", "please refactor the parser module"),
+                claude_assistant("a1", "Parser refactored.", "claude-sonnet-4-6"),
+            ],
+        );
+        home.claude_session(
+            "-home-user-beta",
+            "s2",
+            &[claude_user("u2", "write the release notes")],
+        );
+    }
+
+    #[test]
+    fn exact_search_misses_a_typo_that_fuzzy_search_finds() {
+        let home = TestHome::new();
+        seed(&home);
+
+        assert!(global_search("claude", "refactr", 10, false)
+            .unwrap()
+            .is_empty());
+        let results = global_search("claude", "refactr", 10, true).unwrap();
+        assert!(!results.is_empty());
+        assert!(results.iter().all(|r| r.session_id == "s1"));
+        assert!(results.iter().all(|r| r.score.is_some()));
+    }
+
+    #[test]
+    fn fuzzy_results_rank_exact_matches_first() {
+        let home = TestHome::new();
+        seed(&home);
+
+        let results = global_search("claude", "parser refactor", 10, true).unwrap();
+        let scores: Vec<u32> = results.iter().filter_map(|r| r.score).collect();
+        assert_eq!(scores.len(), 2);
+        assert!(scores.windows(2).all(|w| w[0] >= w[1]), "{:?}", scores);
+        assert_eq!(
+            global_search("claude", "parser", 10, false).unwrap().len(),
+            2
+        );
+    }
+
+    #[test]
+    fn path_in_a_tool_input_finds_the_session() {
+        let home = TestHome::new();
+        seed(&home);
+        let edit = serde_json::json!({
+            "type": "assistant",
+            "uuid": "a2",
+            "message": {
+                "role": "assistant",
+                "content": [{
+                    "type": "tool_use",
+                    "id": "t1",
+                    "name": "Edit",
+                    "input": { "file_path": "/home/user/alpha/src/parser.rs", "old_string": "a" },
+                }],
+            },
+        });
+        home.claude_session(
+            "-home-user-alpha",
+            "s3",
+            &[claude_user("u3", "fix the lexer"), edit.to_string()],
+        );
+        home.claude_session(
+            "-home-user-alpha",
+            "s4",
+            &[claude_user("u4", r"see C:\work\src\parser.rs for details")],
+        );
+
+        let mut mentions =
+            find_sessions_mentioning_path("claude", "-home-user-alpha", "src/parser.rs").unwrap();
+        mentions.sort_by(|a, b| a.session.session_id.cmp(&b.session.session_id));
+        let found: Vec<(&str, Option<&str>)> = mentions
+            .iter()
+            .map(|m| (m.session.session_id.as_str(), m.tool_name.as_deref()))
+            .collect();
+        assert_eq!(found, [("s3", Some("Edit")), ("s4", None)]);
+        assert!(mentions[0]
+            .snippet
+            .contains("/home/user/alpha/src/parser.rs"));
+    }
 }
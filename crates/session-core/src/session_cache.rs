@@ -0,0 +1,262 @@
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::SystemTime;
+
+use crate::models::session::SessionIndexEntry;
+use crate::parser::format::is_session_file;
+use crate::parser::path_encoder::get_projects_dir;
+use crate::provider::{claude, codex};
+use crate::settings;
+
+/// File mtime and size an entry was built from
+type Stamp = (SystemTime, u64);
+
+/// Hex SHA-256 of session files keyed by path
+fn hash_cache() -> &'static Mutex<HashMap<PathBuf, (Stamp, String)>> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, (Stamp, String)>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// An index entry with the stamp and preview length it was built with
+type CachedEntry = (Stamp, usize, SessionIndexEntry);
+
+/// Parsed session index entries keyed by session file path
+fn cache() -> &'static Mutex<HashMap<PathBuf, CachedEntry>> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, CachedEntry>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn stamp(path: &Path) -> Option<Stamp> {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+/// Index entry for a session file, re-parsed with `build` only when the file's
+/// mtime or size, or the preview length, differs from the cached copy.
+pub(crate) fn cached_entry(
+    path: &Path,
+    preview_chars: usize,
+    build: impl FnOnce(&Path) -> Option<SessionIndexEntry>,
+) -> Option<SessionIndexEntry> {
+    let current = match stamp(path) {
+        Some(s) => s,
+        None => {
+            cache().lock().remove(path);
+            return None;
+        }
+    };
+    if let Some((cached, chars, entry)) = cache().lock().get(path) {
+        if *cached == current && *chars == preview_chars {
+            return Some(entry.clone());
+        }
+    }
+
+    let entry = build(path)?;
+    cache()
+        .lock()
+        .insert(path.to_path_buf(), (current, preview_chars, entry.clone()));
+    Some(entry)
+}
+
+/// Bring one session file's cached entry up to date: a deleted file drops its
+/// entry, anything else is re-parsed. Run for the file watchers by
+/// `flush_reindex` so `get_sessions` never has to rescan a project the CLI
+/// is writing to.
+pub fn reindex_file(file_path: &str, source: &str) -> Result<(), String> {
+    reindex(Path::new(file_path), source, settings::preview_max_chars())
+}
+
+fn reindex(path: &Path, source: &str, preview_chars: usize) -> Result<(), String> {
+    cache().lock().remove(path);
+    hash_cache().lock().remove(path);
+    if !path.exists() {
+        return Ok(());
+    }
+
+    match source {
+        "claude" => {
+            let session_id = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .ok_or_else(|| format!("Invalid session file name: {}", path.display()))?
+                .to_string();
+            cached_entry(path, preview_chars, |p| {
+                claude::scan_single_session(p, &session_id, preview_chars)
+            });
+        }
+        "codex" => {
+            cached_entry(path, preview_chars, |p| {
+                Some(codex::session_entry(p, preview_chars))
+            });
+        }
+        _ => return Err(format!("Unknown source: {}", source)),
+    }
+    Ok(())
+}
+
+/// Session files reported changed since the last `flush_reindex`
+fn pending() -> &'static Mutex<HashSet<PathBuf>> {
+    static PENDING: OnceLock<Mutex<HashSet<PathBuf>>> = OnceLock::new();
+    PENDING.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Source of a Claude or Codex session file, None for any other path
+fn session_source(path: &Path) -> Option<&'static str> {
+    let source = if get_projects_dir().is_some_and(|dir| path.starts_with(dir)) {
+        "claude"
+    } else if codex::get_sessions_dir().is_some_and(|dir| path.starts_with(dir)) {
+        "codex"
+    } else {
+        return None;
+    };
+    is_session_file(path, source).then_some(source)
+}
+
+/// Queue `path` for the next `flush_reindex` if it is a session file. Cheap
+/// enough to call on every raw watcher event; repeats collapse into one entry.
+pub fn queue_reindex(path: &Path) {
+    if session_source(path).is_some() {
+        pending().lock().insert(path.to_path_buf());
+    }
+}
+
+/// Reindex every queued file once and return how many there were. The
+/// watchers call this once per debounce window, so a session the CLI is
+/// appending to is parsed once per window rather than once per write.
+pub fn flush_reindex() -> usize {
+    let paths: Vec<PathBuf> = pending().lock().drain().collect();
+    if paths.is_empty() {
+        return 0;
+    }
+    let preview_chars = settings::preview_max_chars();
+    for path in &paths {
+        if let Some(source) = session_source(path) {
+            let _ = reindex(path, source, preview_chars);
+        }
+    }
+    paths.len()
+}
+
+/// Hex SHA-256 of a session file's contents, reused until its mtime or size
+/// changes. Unlike the mtime, it only changes when the content does.
+pub fn session_content_hash(file_path: &str) -> Result<String, String> {
+    use sha2::{Digest, Sha256};
+
+    let path = Path::new(file_path);
+    let current = stamp(path).ok_or_else(|| format!("File not found: {}", file_path))?;
+    if let Some((cached, hash)) = hash_cache().lock().get(path) {
+        if *cached == current {
+            return Ok(hash.clone());
+        }
+    }
+
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to open session: {}", e))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).map_err(|e| format!("Failed to read session: {}", e))?;
+    let hash = format!("{:x}", hasher.finalize());
+
+    hash_cache()
+        .lock()
+        .insert(path.to_path_buf(), (current, hash.clone()));
+    Ok(hash)
+}
+
+/// Set `content_hash` on each session; files that can't be read keep `None`
+pub fn fill_content_hashes(sessions: &mut [SessionIndexEntry]) {
+    for session in sessions.iter_mut() {
+        session.content_hash = session_content_hash(&session.file_path).ok();
+    }
+}
+
+/// Drop all cached session entries and hashes
+pub fn clear_cache() {
+    cache().lock().clear();
+    hash_cache().lock().clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{claude_user, set_mtime, write_lines, TestHome};
+
+    const PROJECT: &str = "-home-user-project";
+    const EPOCH: u64 = 1_700_000_000;
+
+    fn first_prompts() -> Vec<String> {
+        claude::get_sessions(PROJECT, None)
+            .unwrap()
+            .into_iter()
+            .filter_map(|s| s.first_prompt)
+            .collect()
+    }
+
+    fn cached_prompt(path: &Path) -> Option<String> {
+        cache()
+            .lock()
+            .get(path)
+            .and_then(|(_, _, entry)| entry.first_prompt.clone())
+    }
+
+    #[test]
+    fn reindexing_a_file_updates_only_its_cached_entry() {
+        let home = TestHome::new();
+        let path = home.claude_session(PROJECT, "s1", &[claude_user("u1", "hello")]);
+        let other = home.claude_session(PROJECT, "s2", &[claude_user("u2", "other")]);
+        set_mtime(&path, EPOCH);
+        assert_eq!(first_prompts().len(), 2);
+
+        // Same size and mtime: listings keep serving the cached entry
+        write_lines(&path, &[claude_user("u1", "howdy")]);
+        set_mtime(&path, EPOCH);
+        assert!(first_prompts().contains(&"hello".to_string()));
+
+        reindex_file(path.to_str().unwrap(), "claude").unwrap();
+        assert_eq!(cached_prompt(&path).as_deref(), Some("howdy"));
+        assert_eq!(cached_prompt(&other).as_deref(), Some("other"));
+        assert!(first_prompts().contains(&"howdy".to_string()));
+
+        fs::remove_file(&path).unwrap();
+        reindex_file(path.to_str().unwrap(), "claude").unwrap();
+        assert!(!cache().lock().contains_key(&path));
+        assert_eq!(first_prompts(), ["other"]);
+    }
+
+    #[test]
+    fn watcher_events_are_reindexed_once_per_flush() {
+        let home = TestHome::new();
+        let path = home.claude_session(PROJECT, "s1", &[claude_user("u1", "hello")]);
+
+        queue_reindex(&path);
+        queue_reindex(&path);
+        queue_reindex(&home.path().join("notes.txt"));
+        assert_eq!(flush_reindex(), 1);
+        assert_eq!(cached_prompt(&path).as_deref(), Some("hello"));
+        assert_eq!(flush_reindex(), 0);
+    }
+
+    #[test]
+    fn content_hash_follows_content_not_mtime() {
+        let home = TestHome::new();
+        let lines = [claude_user("u1", "hello")];
+        let a = home.claude_session(PROJECT, "a", &lines);
+        let b = home.claude_session(PROJECT, "b", &lines);
+        let hash = |p: &Path| session_content_hash(p.to_str().unwrap()).unwrap();
+
+        let original = hash(&a);
+        assert_eq!(original.len(), 64);
+        assert_eq!(original, hash(&b));
+
+        // A touch alone keeps the hash
+        set_mtime(&a, EPOCH);
+        assert_eq!(hash(&a), original);
+
+        write_lines(&a, &[claude_user("u1", "hello!")]);
+        assert_ne!(hash(&a), original);
+        assert_eq!(hash(&b), original);
+
+        assert!(session_content_hash(home.path().join("gone.jsonl").to_str().unwrap()).is_err());
+    }
+}
@@ -0,0 +1,1436 @@
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::bookmarks;
+use crate::metadata;
+use crate::models::message::{DisplayContentBlock, DisplayMessage, Role};
+use crate::parser::format::{self, ParseError};
+use crate::parser::jsonl as claude_parser;
+use crate::parser::preview::truncate_chars;
+use crate::provider::{claude, codex};
+use crate::quick_chat::ChatMsg;
+use crate::settings;
+
+/// Longest tool input/output kept when a message is flattened for chat
+const MAX_TOOL_TEXT_CHARS: usize = 2_000;
+
+/// Messages of a session together with the first parse error found, if any
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckedMessages {
+    /// Messages parsed before the error (all messages when the file is clean)
+    pub messages: Vec<DisplayMessage>,
+    pub error: Option<ParseError>,
+}
+
+/// Parse a session strictly: locate the first malformed record and return the
+/// messages from the records preceding it, so corruption can be pinpointed.
+pub fn parse_session_checked(file_path: &str, source: &str) -> Result<CheckedMessages, String> {
+    let path = Path::new(file_path);
+    if !path.exists() {
+        return Err(format!("File not found: {}", file_path));
+    }
+    if source != "claude" && source != "codex" {
+        return Err(format!("Unknown source: {}", source));
+    }
+
+    let error =
+        format::find_parse_error(path).map_err(|e| format!("Failed to read session: {}", e))?;
+
+    // A malformed single-JSON document has no usable prefix
+    let prefix_lines = match (&error, format::detect_format(path)) {
+        (None, _) => usize::MAX,
+        (Some(e), format::SessionFormat::Jsonl) => e.line - 1,
+        (Some(_), format::SessionFormat::Json) => 0,
+    };
+
+    let messages = if prefix_lines == 0 {
+        Vec::new()
+    } else {
+        let records = format::read_records(path)
+            .map_err(|e| format!("Failed to open file: {}", e))?
+            .take(prefix_lines);
+        match source {
+            "codex" => codex::messages_from_records(records),
+            _ => claude_parser::messages_from_records(records),
+        }
+    };
+
+    Ok(CheckedMessages { messages, error })
+}
+
+/// Cheap size check of a session file, from `session_size_info`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeInfo {
+    pub bytes: u64,
+    /// Record count from a line count (JSONL only; None for single-JSON files).
+    /// Includes non-message records, so it overestimates the messages shown.
+    pub estimated_messages: Option<usize>,
+    /// Threshold `is_large` was measured against (the `largeSessionBytes` setting)
+    pub threshold_bytes: u64,
+    pub is_large: bool,
+}
+
+/// Size of a session file and whether it exceeds the large-session threshold,
+/// so the UI can warn or switch to paged loading before a full parse. Only
+/// counts newlines; no record is decoded.
+pub fn session_size_info(file_path: &str) -> Result<SizeInfo, String> {
+    let path = Path::new(file_path);
+    let bytes = fs::metadata(path)
+        .map_err(|e| format!("Failed to stat session {}: {}", file_path, e))?
+        .len();
+    let estimated_messages = match format::detect_format(path) {
+        format::SessionFormat::Jsonl => {
+            Some(format::count_lines(path).map_err(|e| format!("Failed to read session: {}", e))?)
+        }
+        format::SessionFormat::Json => None,
+    };
+    let threshold_bytes = settings::large_session_bytes();
+    Ok(SizeInfo {
+        bytes,
+        estimated_messages,
+        threshold_bytes,
+        is_large: bytes > threshold_bytes,
+    })
+}
+
+/// Integrity report for one session file, from `validate_session`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationReport {
+    /// Messages the tolerant parser recovered
+    pub message_count: usize,
+    /// Every malformed record, in file order
+    pub parse_errors: Vec<ParseError>,
+    /// Tool call ids with no recorded result
+    pub unmatched_tool_uses: Vec<String>,
+    /// Tool result ids with no matching call
+    pub orphan_tool_results: Vec<String>,
+    /// Problems with the order of messages (e.g. a result before its call)
+    pub sequence_problems: Vec<String>,
+    /// No parse errors, unmatched tool blocks or sequence problems
+    pub valid: bool,
+}
+
+/// Check a session file for corruption without failing on it: every malformed
+/// line, tool calls and results that don't pair up, and an incoherent message
+/// order are collected into the report. Errors only if the file can't be read.
+pub fn validate_session(file_path: &str, source: &str) -> Result<ValidationReport, String> {
+    let path = Path::new(file_path);
+    if !path.exists() {
+        return Err(format!("File not found: {}", file_path));
+    }
+
+    let parse_errors =
+        format::find_parse_errors(path).map_err(|e| format!("Failed to read session: {}", e))?;
+
+    let parsed = match source {
+        "claude" => claude::parse_all_messages(path),
+        "codex" => codex::parse_all_messages(path),
+        _ => return Err(format!("Unknown source: {}", source)),
+    };
+    // A broken single-JSON document yields nothing, which parse_errors explains
+    let messages = match parsed {
+        Ok(m) => m,
+        Err(_) if !parse_errors.is_empty() => Vec::new(),
+        Err(e) => return Err(e),
+    };
+
+    let mut calls: Vec<String> = Vec::new();
+    let mut results: Vec<String> = Vec::new();
+    // Results seen before the call with their id
+    let mut early_results: Vec<String> = Vec::new();
+    for msg in &messages {
+        for block in &msg.content {
+            match block {
+                DisplayContentBlock::ToolUse { id, .. }
+                | DisplayContentBlock::FunctionCall { call_id: id, .. } => {
+                    calls.push(id.clone());
+                }
+                DisplayContentBlock::ToolResult {
+                    tool_use_id: id, ..
+                }
+                | DisplayContentBlock::FunctionCallOutput { call_id: id, .. } => {
+                    if !calls.contains(id) {
+                        early_results.push(id.clone());
+                    }
+                    results.push(id.clone());
+                }
+                _ => {}
+            }
+        }
+    }
+    let unmatched_tool_uses: Vec<String> = calls
+        .iter()
+        .filter(|id| !results.contains(id))
+        .cloned()
+        .collect();
+    let orphan_tool_results: Vec<String> = results
+        .iter()
+        .filter(|id| !calls.contains(id))
+        .cloned()
+        .collect();
+
+    let mut sequence_problems = Vec::new();
+    if let Some(first) = messages.iter().find(|m| m.role != Role::System) {
+        if first.role != Role::User {
+            sequence_problems.push(format!(
+                "Conversation starts with a {} message",
+                first.role.as_str()
+            ));
+        }
+    }
+    // Orphans are reported on their own, not also as out of order
+    sequence_problems.extend(
+        early_results
+            .iter()
+            .filter(|id| !orphan_tool_results.contains(id))
+            .map(|id| format!("Tool result {} comes before its call", id)),
+    );
+
+    let valid = parse_errors.is_empty()
+        && unmatched_tool_uses.is_empty()
+        && orphan_tool_results.is_empty()
+        && sequence_problems.is_empty();
+    Ok(ValidationReport {
+        message_count: messages.len(),
+        parse_errors,
+        unmatched_tool_uses,
+        orphan_tool_results,
+        sequence_problems,
+        valid,
+    })
+}
+
+/// Move a session to another project and return its new file path.
+///
+/// Claude: the file is relocated into the target project directory and its
+/// `cwd` fields are rewritten. Codex: the `session_meta` cwd is rewritten in
+/// place. In both cases the session's alias/tags follow it.
+pub fn move_session(
+    file_path: &str,
+    source: &str,
+    target_project_id: &str,
+) -> Result<String, String> {
+    let path = Path::new(file_path);
+    if !path.exists() {
+        return Err(format!("File not found: {}", file_path));
+    }
+
+    match source {
+        "claude" => {
+            let from_project = path
+                .parent()
+                .and_then(|p| p.file_name())
+                .and_then(|n| n.to_str())
+                .ok_or("Cannot determine source project")?
+                .to_string();
+            let from_session = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .ok_or("Invalid session file name")?
+                .to_string();
+
+            let new_path = claude::move_session(path, target_project_id)?;
+            let to_session = new_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or(&from_session)
+                .to_string();
+
+            metadata::move_session_meta(
+                "claude",
+                &from_project,
+                &from_session,
+                target_project_id,
+                &to_session,
+            )?;
+            Ok(new_path.to_string_lossy().to_string())
+        }
+        // Codex metadata is a single file keyed by session id, so nothing to migrate
+        "codex" => {
+            codex::move_session(path, target_project_id).map(|p| p.to_string_lossy().to_string())
+        }
+        _ => Err(format!("Unknown source: {}", source)),
+    }
+}
+
+/// Append a user turn to a session file in the provider's native format, so
+/// the next CLI resume continues from it.
+///
+/// Only clean JSONL files are accepted, and the file is refused while a
+/// `.lock`/`.tmp` sibling exists or its last line is incomplete (the CLI is
+/// mid-write). The record is written with a single `O_APPEND` write rather
+/// than tmp + rename, so a CLI holding the file open keeps appending to it.
+pub fn append_user_message(file_path: &str, source: &str, text: &str) -> Result<(), String> {
+    let path = Path::new(file_path);
+    if !path.exists() {
+        return Err(format!("File not found: {}", file_path));
+    }
+    if text.trim().is_empty() {
+        return Err("Message is empty".to_string());
+    }
+    if format::detect_format(path) != format::SessionFormat::Jsonl {
+        return Err("Only JSONL sessions can be appended to".to_string());
+    }
+    for suffix in ["lock", "tmp"] {
+        let name = format!(
+            "{}.{}",
+            path.file_name().unwrap_or_default().to_string_lossy(),
+            suffix
+        );
+        if path.with_file_name(name).exists() {
+            return Err("Session file is locked by another writer".to_string());
+        }
+    }
+    if !ends_with_newline(path)? {
+        return Err("Session file is being written (incomplete last line)".to_string());
+    }
+    if let Some(e) =
+        format::find_parse_error(path).map_err(|e| format!("Failed to read session: {}", e))?
+    {
+        return Err(format!("Session file is malformed at {}", e));
+    }
+
+    let record = match source {
+        "claude" => claude_user_record(path, text)?,
+        "codex" => codex_user_record(text),
+        _ => return Err(format!("Unknown source: {}", source)),
+    };
+
+    let mut line = record.to_string();
+    line.push('\n');
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open session: {}", e))?;
+    file.write_all(line.as_bytes())
+        .map_err(|e| format!("Failed to append message: {}", e))?;
+    file.sync_data()
+        .map_err(|e| format!("Failed to flush session: {}", e))?;
+    Ok(())
+}
+
+/// Whether the file is empty or its last byte is a newline
+fn ends_with_newline(path: &Path) -> Result<bool, String> {
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to open session: {}", e))?;
+    let len = file
+        .metadata()
+        .map_err(|e| format!("Failed to read session: {}", e))?
+        .len();
+    if len == 0 {
+        return Ok(true);
+    }
+    let mut last = [0u8; 1];
+    file.seek(SeekFrom::End(-1))
+        .and_then(|_| file.read_exact(&mut last))
+        .map_err(|e| format!("Failed to read session: {}", e))?;
+    Ok(last[0] == b'\n')
+}
+
+/// Claude user record chained to the last message, carrying over the
+/// session's cwd, version and git branch as the CLI does
+fn claude_user_record(path: &Path, text: &str) -> Result<Value, String> {
+    let session_id = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or("Invalid session file name")?
+        .to_string();
+
+    let mut parent_uuid = Value::Null;
+    let mut context = serde_json::Map::new();
+    let records = format::read_records(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    for line in records.map_while(Result::ok) {
+        let record: Value = match serde_json::from_str(&line) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        if let Some(uuid) = record.get("uuid").filter(|v| v.is_string()) {
+            parent_uuid = uuid.clone();
+        }
+        for key in ["cwd", "version", "gitBranch", "sessionId"] {
+            if let Some(v) = record.get(key).filter(|v| v.is_string()) {
+                context.insert(key.to_string(), v.clone());
+            }
+        }
+    }
+
+    let mut record = json!({
+        "parentUuid": parent_uuid,
+        "isSidechain": false,
+        "userType": "external",
+        "sessionId": session_id,
+        "type": "user",
+        "message": { "role": "user", "content": text },
+        "uuid": uuid::Uuid::new_v4().to_string(),
+        "timestamp": now_timestamp(),
+    });
+    if let Some(obj) = record.as_object_mut() {
+        obj.extend(context);
+    }
+    Ok(record)
+}
+
+/// Codex `response_item` carrying a user message
+fn codex_user_record(text: &str) -> Value {
+    json!({
+        "timestamp": now_timestamp(),
+        "type": "response_item",
+        "payload": {
+            "type": "message",
+            "role": "user",
+            "content": [{ "type": "input_text", "text": text }],
+        },
+    })
+}
+
+/// Current time in the CLIs' format (RFC 3339, millisecond precision, `Z`)
+fn now_timestamp() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+/// Metadata key (project_id, session_id) for a session file
+pub(crate) fn session_key(path: &Path, source: &str) -> Result<(String, String), String> {
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or("Invalid session file name")?
+        .to_string();
+
+    match source {
+        "claude" => {
+            let project_id = path
+                .parent()
+                .and_then(|p| p.file_name())
+                .and_then(|n| n.to_str())
+                .ok_or("Cannot determine project")?
+                .to_string();
+            Ok((project_id, stem))
+        }
+        // Codex metadata is a single file keyed by the session_meta id
+        "codex" => {
+            let session_id = codex::extract_session_meta(path)
+                .map(|m| m.id)
+                .filter(|id| !id.is_empty())
+                .unwrap_or(stem);
+            Ok((String::new(), session_id))
+        }
+        _ => Err(format!("Unknown source: {}", source)),
+    }
+}
+
+/// Copy messages `start_index..=end_index` of a session into a new session
+/// file with a fresh id, next to the original, and return its path.
+///
+/// Indices are positions in the parsed message list. The range may not start
+/// on a tool result whose call was cut off. Leading header records (e.g. the
+/// Codex `session_meta`) are kept; other non-message records are dropped.
+/// The session's tags are copied to the new session.
+pub fn trim_session(
+    file_path: &str,
+    source: &str,
+    start_index: usize,
+    end_index: usize,
+) -> Result<String, String> {
+    let path = Path::new(file_path);
+    let content = read_derivable_session(path, source)?;
+    let (header, messages) = indexed_records(&content, source);
+
+    let total = messages.len();
+    if start_index > end_index || end_index >= total {
+        return Err(format!(
+            "Invalid range {}..={} for a session of {} messages",
+            start_index, end_index, total
+        ));
+    }
+    let kept = &messages[start_index..=end_index];
+    if matches!(kept.first(), Some((_, m)) if m.role == Role::Tool) {
+        return Err("Range cannot start on a tool result".to_string());
+    }
+
+    let (project_id, old_id) = session_key(path, source)?;
+    let new_path = write_derived_session(path, source, &project_id, &old_id, &header, kept)?;
+    Ok(new_path.to_string_lossy().to_string())
+}
+
+/// Split a session into consecutive parts, starting a new part at each of
+/// `split_indices`, and write every part to a new session file with a fresh
+/// id next to the original. Returns the new paths in order; the original is
+/// left as it is.
+///
+/// Indices must be strictly ascending, within `1..message_count`, and may not
+/// fall on a tool result (which would separate it from its call). Every index
+/// is checked before anything is written. Each part gets the session's tags.
+pub fn split_session(
+    file_path: &str,
+    source: &str,
+    split_indices: Vec<usize>,
+) -> Result<Vec<String>, String> {
+    let path = Path::new(file_path);
+    let content = read_derivable_session(path, source)?;
+    let (header, messages) = indexed_records(&content, source);
+
+    let total = messages.len();
+    if split_indices.is_empty() {
+        return Err("No split indices given".to_string());
+    }
+    if split_indices.windows(2).any(|w| w[0] >= w[1]) {
+        return Err("Split indices must be strictly ascending".to_string());
+    }
+    for &i in &split_indices {
+        if i == 0 || i >= total {
+            return Err(format!(
+                "Split index {} out of range for a session of {} messages",
+                i, total
+            ));
+        }
+        if messages[i].1.role == Role::Tool {
+            return Err(format!(
+                "Split index {} falls on a tool result and would orphan it from its call",
+                i
+            ));
+        }
+    }
+
+    let (project_id, old_id) = session_key(path, source)?;
+    let bounds: Vec<usize> = std::iter::once(0)
+        .chain(split_indices)
+        .chain(std::iter::once(total))
+        .collect();
+    bounds
+        .windows(2)
+        .map(|w| {
+            let part = &messages[w[0]..w[1]];
+            write_derived_session(path, source, &project_id, &old_id, &header, part)
+                .map(|p| p.to_string_lossy().to_string())
+        })
+        .collect()
+}
+
+/// Read a session that `trim_session`/`split_session` can derive new files from
+fn read_derivable_session(path: &Path, source: &str) -> Result<String, String> {
+    if !path.exists() {
+        return Err(format!("File not found: {}", path.display()));
+    }
+    if format::detect_format(path) != format::SessionFormat::Jsonl {
+        return Err("Only JSONL sessions can be trimmed or split".to_string());
+    }
+    if source != "claude" && source != "codex" {
+        return Err(format!("Unknown source: {}", source));
+    }
+    fs::read_to_string(path).map_err(|e| format!("Failed to read session: {}", e))
+}
+
+/// Leading non-message records, and every message with the line it came from.
+/// Each record yields at most one message, so messages map back to lines;
+/// other non-message records are dropped.
+fn indexed_records<'a>(
+    content: &'a str,
+    source: &str,
+) -> (Vec<&'a str>, Vec<(&'a str, DisplayMessage)>) {
+    let parse = |line: &str| -> Vec<DisplayMessage> {
+        let record = std::iter::once(Ok(line.to_string()));
+        match source {
+            "codex" => codex::messages_from_records(record),
+            _ => claude_parser::messages_from_records(record),
+        }
+    };
+
+    let mut header: Vec<&str> = Vec::new();
+    let mut messages: Vec<(&str, DisplayMessage)> = Vec::new();
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        match parse(line).into_iter().next() {
+            Some(m) => messages.push((line, m)),
+            None if messages.is_empty() => header.push(line),
+            None => {}
+        }
+    }
+    (header, messages)
+}
+
+/// Write `header` and `kept` as a new session with a fresh id next to `path`,
+/// rewriting the session id and starting a new message chain, and copy the
+/// original session's tags to it. Returns the new file's path.
+fn write_derived_session(
+    path: &Path,
+    source: &str,
+    project_id: &str,
+    old_id: &str,
+    header: &[&str],
+    kept: &[(&str, DisplayMessage)],
+) -> Result<PathBuf, String> {
+    let new_id = uuid::Uuid::new_v4().to_string();
+    let dir = path.parent().ok_or("Cannot determine session directory")?;
+    let new_path = match source {
+        "claude" => dir.join(format!("{}.jsonl", new_id)),
+        _ => dir.join(format!(
+            "rollout-{}-{}.jsonl",
+            chrono::Local::now().format("%Y-%m-%dT%H-%M-%S"),
+            new_id
+        )),
+    };
+
+    let mut out = String::new();
+    let lines = header
+        .iter()
+        .map(|l| (*l, false))
+        .chain(kept.iter().enumerate().map(|(i, (l, _))| (*l, i == 0)));
+    for (line, first_message) in lines {
+        match serde_json::from_str::<Value>(line) {
+            Ok(Value::Object(mut obj)) => {
+                match source {
+                    "claude" => {
+                        if obj.contains_key("sessionId") {
+                            obj.insert("sessionId".to_string(), json!(new_id));
+                        }
+                        // The derived transcript starts a new chain
+                        if first_message {
+                            obj.insert("parentUuid".to_string(), Value::Null);
+                        }
+                    }
+                    _ => {
+                        if obj.get("type").and_then(|t| t.as_str()) == Some("session_meta") {
+                            if let Some(payload) =
+                                obj.get_mut("payload").and_then(|p| p.as_object_mut())
+                            {
+                                payload.insert("id".to_string(), json!(new_id));
+                            }
+                        }
+                    }
+                }
+                out.push_str(&Value::Object(obj).to_string());
+            }
+            _ => out.push_str(line),
+        }
+        out.push('\n');
+    }
+
+    let tmp_path = new_path.with_extension("jsonl.tmp");
+    fs::write(&tmp_path, out).map_err(|e| format!("Failed to write tmp: {}", e))?;
+    fs::rename(&tmp_path, &new_path).map_err(|e| format!("Failed to rename: {}", e))?;
+
+    // The tags are copied as stored: they were accepted when first saved, so
+    // rules tightened since then must not block the copy
+    let mut meta = metadata::load_metadata(source, project_id);
+    let tags = meta
+        .sessions
+        .get(old_id)
+        .map(|m| m.tags.clone())
+        .unwrap_or_default();
+    if !tags.is_empty() {
+        meta.sessions.insert(
+            new_id,
+            metadata::SessionMeta {
+                tags,
+                ..Default::default()
+            },
+        );
+        if let Err(e) = metadata::save_metadata(source, project_id, &meta) {
+            let _ = fs::remove_file(&new_path);
+            return Err(e);
+        }
+    }
+
+    Ok(new_path)
+}
+
+/// One message of a session as a `ChatMsg`, ready to seed a quick chat.
+///
+/// `message_id` is a message uuid, or "user-<index>" for messages without one.
+/// Text blocks are kept as-is, tool calls and results are summarized with
+/// their (truncated) payload, and thinking/reasoning is dropped.
+pub fn message_to_chat_msg(
+    file_path: &str,
+    source: &str,
+    message_id: &str,
+) -> Result<ChatMsg, String> {
+    let path = Path::new(file_path);
+    if !path.exists() {
+        return Err(format!("File not found: {}", file_path));
+    }
+
+    let messages = bookmarks::load_messages(source, path)?;
+    let msg = bookmarks::find_message(&messages, message_id)
+        .map(|i| &messages[i])
+        .ok_or_else(|| format!("Message not found: {}", message_id))?;
+
+    let parts: Vec<String> = msg
+        .content
+        .iter()
+        .filter_map(|block| match block {
+            DisplayContentBlock::Text { text } => Some(text.trim().to_string()),
+            DisplayContentBlock::ToolUse { name, input, .. }
+            | DisplayContentBlock::FunctionCall {
+                name,
+                arguments: input,
+                ..
+            } => Some(format!(
+                "[Tool call: {}]\n{}",
+                name,
+                truncate_chars(input.trim(), MAX_TOOL_TEXT_CHARS)
+            )),
+            DisplayContentBlock::ToolResult {
+                content, is_error, ..
+            } => {
+                let label = if *is_error {
+                    "Tool error"
+                } else {
+                    "Tool result"
+                };
+                Some(format!(
+                    "[{}]\n{}",
+                    label,
+                    truncate_chars(content.trim(), MAX_TOOL_TEXT_CHARS)
+                ))
+            }
+            DisplayContentBlock::FunctionCallOutput { output, .. } => Some(format!(
+                "[Tool result]\n{}",
+                truncate_chars(output.trim(), MAX_TOOL_TEXT_CHARS)
+            )),
+            DisplayContentBlock::Thinking { .. } | DisplayContentBlock::Reasoning { .. } => None,
+        })
+        .filter(|s| !s.is_empty())
+        .collect();
+    if parts.is_empty() {
+        return Err("Message has no content to copy".to_string());
+    }
+
+    // The chat API only knows user and assistant turns
+    let role = match msg.role {
+        Role::Assistant => "assistant",
+        _ => "user",
+    };
+    Ok(ChatMsg {
+        role: role.to_string(),
+        content: parts.join("\n\n"),
+        blocks: None,
+    })
+}
+
+/// A whole session as quick-chat messages, for continuing it in a chat.
+///
+/// Text is kept and thinking/reasoning dropped; consecutive turns of the same
+/// role are merged. With `include_tools`, tool calls and results (Codex function
+/// calls included) are kept as Anthropic `tool_use`/`tool_result` blocks rather
+/// than left out, which can make the request much larger. Results are moved to
+/// the start of the user turn right after their call, as the API requires;
+/// calls and results that can't be paired that way are kept as text instead.
+pub fn load_session_as_messages(
+    file_path: &str,
+    source: &str,
+    include_tools: bool,
+) -> Result<Vec<ChatMsg>, String> {
+    let path = Path::new(file_path);
+    if !path.exists() {
+        return Err(format!("File not found: {}", file_path));
+    }
+
+    let messages = bookmarks::load_messages(source, path)?;
+    let mut turns: Vec<(&'static str, Vec<Value>)> = Vec::new();
+    for msg in &messages {
+        let role = match msg.role {
+            Role::Assistant => "assistant",
+            Role::User | Role::Tool => "user",
+            Role::System => continue,
+        };
+        let blocks: Vec<Value> = msg
+            .content
+            .iter()
+            .filter_map(|block| api_block(block, include_tools))
+            .collect();
+        if blocks.is_empty() {
+            continue;
+        }
+        if let Some((last_role, last)) = turns.last_mut() {
+            if *last_role == role {
+                last.extend(blocks);
+                continue;
+            }
+        }
+        turns.push((role, blocks));
+    }
+    if include_tools {
+        pair_tool_blocks(&mut turns);
+    }
+
+    Ok(turns
+        .into_iter()
+        .map(|(role, blocks)| {
+            let text: Vec<&str> = blocks
+                .iter()
+                .filter(|b| b["type"] == "text")
+                .filter_map(|b| b["text"].as_str())
+                .collect();
+            ChatMsg {
+                role: role.to_string(),
+                content: text.join("\n\n"),
+                blocks: include_tools.then_some(blocks),
+            }
+        })
+        .collect())
+}
+
+/// A display block as an Anthropic content block (None = left out)
+fn api_block(block: &DisplayContentBlock, include_tools: bool) -> Option<Value> {
+    match block {
+        DisplayContentBlock::Text { text } if !text.trim().is_empty() => {
+            Some(json!({ "type": "text", "text": text }))
+        }
+        DisplayContentBlock::ToolUse { id, name, input } if include_tools => {
+            Some(tool_use_block(id, name, input))
+        }
+        DisplayContentBlock::FunctionCall {
+            name,
+            arguments,
+            call_id,
+        } if include_tools => Some(tool_use_block(call_id, name, arguments)),
+        DisplayContentBlock::ToolResult {
+            tool_use_id,
+            content,
+            is_error,
+        } if include_tools => Some(json!({
+            "type": "tool_result",
+            "tool_use_id": tool_use_id,
+            "content": content,
+            "is_error": is_error,
+        })),
+        DisplayContentBlock::FunctionCallOutput { call_id, output } if include_tools => {
+            Some(json!({ "type": "tool_result", "tool_use_id": call_id, "content": output }))
+        }
+        _ => None,
+    }
+}
+
+fn tool_use_block(id: &str, name: &str, input: &str) -> Value {
+    // The API only accepts an object as tool input
+    let input = match serde_json::from_str::<Value>(input) {
+        Ok(v @ Value::Object(_)) => v,
+        _ => json!({ "input": input }),
+    };
+    json!({ "type": "tool_use", "id": id, "name": name, "input": input })
+}
+
+/// Id of a `tool_use` or `tool_result` block
+fn tool_block_id(block: &Value, kind: &str) -> Option<String> {
+    if block["type"] != kind {
+        return None;
+    }
+    let id = if kind == "tool_use" {
+        &block["id"]
+    } else {
+        &block["tool_use_id"]
+    };
+    id.as_str().map(String::from)
+}
+
+/// A tool block turned into a plain text block
+fn tool_block_as_text(block: &Value) -> Value {
+    let text = if block["type"] == "tool_use" {
+        format!(
+            "[Tool call: {}]\n{}",
+            block["name"].as_str().unwrap_or(""),
+            block["input"]
+        )
+    } else {
+        format!("[Tool result]\n{}", block["content"].as_str().unwrap_or(""))
+    };
+    json!({ "type": "text", "text": text })
+}
+
+/// Enforce the API's tool ordering on alternating turns: each `tool_result`
+/// must answer a `tool_use` of the directly preceding assistant turn and come
+/// before any other block of its user turn, and every `tool_use` needs such a
+/// result. Blocks that break this are turned into text.
+fn pair_tool_blocks(turns: &mut [(&'static str, Vec<Value>)]) {
+    let len = turns.len();
+    for i in 0..len {
+        let (before, rest) = turns.split_at_mut(i);
+        let (role, blocks) = &mut rest[0];
+        if *role == "assistant" {
+            // Nothing can answer calls in the final turn
+            if i + 1 == len {
+                for block in blocks.iter_mut() {
+                    if tool_block_id(block, "tool_use").is_some() {
+                        *block = tool_block_as_text(block);
+                    }
+                }
+            }
+            continue;
+        }
+
+        let prev = before.last_mut().filter(|(r, _)| *r == "assistant");
+        let call_ids: Vec<String> = prev
+            .as_ref()
+            .map(|(_, b)| {
+                b.iter()
+                    .filter_map(|b| tool_block_id(b, "tool_use"))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut answered: Vec<String> = Vec::new();
+        let mut results = Vec::new();
+        let mut others = Vec::new();
+        for block in blocks.drain(..) {
+            match tool_block_id(&block, "tool_result") {
+                Some(id) if call_ids.contains(&id) && !answered.contains(&id) => {
+                    answered.push(id);
+                    results.push(block);
+                }
+                Some(_) => others.push(tool_block_as_text(&block)),
+                None => others.push(block),
+            }
+        }
+        results.extend(others);
+        *blocks = results;
+
+        if let Some((_, prev_blocks)) = prev {
+            for block in prev_blocks.iter_mut() {
+                if let Some(id) = tool_block_id(block, "tool_use") {
+                    if !answered.contains(&id) {
+                        *block = tool_block_as_text(block);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quick_chat::{self, ChatOptions};
+    use crate::test_support::{
+        claude_assistant, claude_user, codex_message, codex_meta, sse_events, temp_dir,
+        write_lines, MockResponse, MockServer, TestHome,
+    };
+
+    #[test]
+    fn checked_parse_keeps_the_messages_before_the_bad_line() {
+        let dir = temp_dir();
+        let path = write_lines(
+            &dir.path().join("session.jsonl"),
+            &[
+                claude_user("u1", "first question"),
+                claude_assistant("a1", "first answer", "claude-sonnet-4-6"),
+                "{\"type\": \"user\", truncated".to_string(),
+                claude_user("u2", "never reached"),
+            ],
+        );
+
+        let checked = parse_session_checked(path.to_str().unwrap(), "claude").unwrap();
+        let uuids: Vec<_> = checked
+            .messages
+            .iter()
+            .map(|m| m.uuid.as_deref().unwrap_or(""))
+            .collect();
+        assert_eq!(uuids, ["u1", "a1"]);
+        assert_eq!(checked.error.unwrap().line, 3);
+    }
+
+    #[test]
+    fn size_info_flags_files_over_the_threshold() {
+        let home = TestHome::new();
+        let padding = "x".repeat(500);
+        let lines: Vec<String> = (0..4000)
+            .map(|i| claude_user(&format!("u{}", i), &padding))
+            .collect();
+        let large = home.claude_session("-home-user-project", "large", &lines);
+        let small = home.claude_session("-home-user-project", "small", &lines[..10]);
+
+        let info = session_size_info(large.to_str().unwrap()).unwrap();
+        assert_eq!(info.bytes, fs::metadata(&large).unwrap().len());
+        assert_eq!(info.estimated_messages, Some(4000));
+        assert_eq!(info.threshold_bytes, settings::DEFAULT_LARGE_SESSION_BYTES);
+        assert!(!info.is_large);
+
+        settings::save_settings(&settings::SettingsFile {
+            large_session_bytes: Some(1024 * 1024),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(session_size_info(large.to_str().unwrap()).unwrap().is_large);
+        let info = session_size_info(small.to_str().unwrap()).unwrap();
+        assert_eq!(info.estimated_messages, Some(10));
+        assert!(!info.is_large);
+
+        let json = write_lines(&home.path().join("session.json"), &["[]".to_string()]);
+        let info = session_size_info(json.to_str().unwrap()).unwrap();
+        assert_eq!(info.estimated_messages, None);
+        assert!(session_size_info("/no/such/session.jsonl").is_err());
+    }
+
+    #[test]
+    fn checked_parse_of_a_clean_file_has_no_error() {
+        let dir = temp_dir();
+        let path = write_lines(
+            &dir.path().join("session.jsonl"),
+            &[claude_user("u1", "hi"), claude_user("u2", "again")],
+        );
+
+        let checked = parse_session_checked(path.to_str().unwrap(), "claude").unwrap();
+        assert_eq!(checked.messages.len(), 2);
+        assert!(checked.error.is_none());
+    }
+
+    fn last_user_text(file_path: &str, source: &str) -> String {
+        let checked = parse_session_checked(file_path, source).unwrap();
+        assert!(checked.error.is_none());
+        let last = checked.messages.last().unwrap();
+        assert_eq!(last.role, Role::User);
+        match &last.content[..] {
+            [DisplayContentBlock::Text { text }] => text.clone(),
+            other => panic!("unexpected content {:?}", other),
+        }
+    }
+
+    fn last_record(path: &Path) -> Value {
+        let content = fs::read_to_string(path).unwrap();
+        serde_json::from_str(content.lines().last().unwrap()).unwrap()
+    }
+
+    fn validate(path: &Path, source: &str) -> ValidationReport {
+        validate_session(path.to_str().unwrap(), source).unwrap()
+    }
+
+    #[test]
+    fn clean_sessions_validate() {
+        let dir = temp_dir();
+        let claude = write_lines(&dir.path().join("claude.jsonl"), &tool_transcript());
+        let codex = write_lines(
+            &dir.path().join("codex.jsonl"),
+            &[
+                codex_meta("c1", "/home/user/project"),
+                codex_message("user", "hello"),
+                codex_message("assistant", "hi"),
+            ],
+        );
+
+        let report = validate(&claude, "claude");
+        assert!(report.valid, "{:?}", report);
+        assert_eq!(report.message_count, 6);
+        let report = validate(&codex, "codex");
+        assert!(report.valid, "{:?}", report);
+        assert_eq!(report.message_count, 2);
+    }
+
+    #[test]
+    fn broken_session_reports_every_problem() {
+        let dir = temp_dir();
+        let blocks = |kind: &str, uuid: &str, content: serde_json::Value| {
+            json!({ "type": kind, "uuid": uuid, "message": { "role": kind, "content": content } })
+                .to_string()
+        };
+        let path = write_lines(
+            &dir.path().join("broken.jsonl"),
+            &[
+                claude_assistant("a0", "picking up where we left off", "claude-sonnet-4-6"),
+                r#"{"type":"user","uuid":"#.to_string(),
+                blocks(
+                    "user",
+                    "r1",
+                    json!([{ "type": "tool_result", "tool_use_id": "t1", "content": "early" }]),
+                ),
+                blocks(
+                    "assistant",
+                    "a1",
+                    json!([
+                        { "type": "tool_use", "id": "t1", "name": "Read", "input": {} },
+                        { "type": "tool_use", "id": "t2", "name": "Bash", "input": {} },
+                    ]),
+                ),
+                blocks(
+                    "user",
+                    "r2",
+                    json!([{ "type": "tool_result", "tool_use_id": "t9", "content": "orphan" }]),
+                ),
+            ],
+        );
+
+        let report = validate(&path, "claude");
+        assert!(!report.valid);
+        assert_eq!(report.message_count, 4);
+        let lines: Vec<usize> = report.parse_errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, [2]);
+        assert_eq!(report.unmatched_tool_uses, ["t2"]);
+        assert_eq!(report.orphan_tool_results, ["t9"]);
+        assert_eq!(
+            report.sequence_problems,
+            [
+                "Conversation starts with a assistant message",
+                "Tool result t1 comes before its call",
+            ]
+        );
+    }
+
+    #[test]
+    fn broken_json_document_is_reported_not_rejected() {
+        let dir = temp_dir();
+        let path = dir.path().join("broken.json");
+        fs::write(&path, "[{\"type\":\"user\",}]").unwrap();
+
+        let report = validate(&path, "claude");
+        assert!(!report.valid);
+        assert_eq!(report.message_count, 0);
+        assert_eq!(report.parse_errors.len(), 1);
+    }
+
+    #[test]
+    fn appended_claude_message_round_trips() {
+        let dir = temp_dir();
+        let path = write_lines(
+            &dir.path().join("abc-123.jsonl"),
+            &[
+                claude_user("u1", "first question"),
+                claude_assistant("a1", "first answer", "claude-sonnet-4-6"),
+            ],
+        );
+        let file_path = path.to_str().unwrap();
+
+        append_user_message(file_path, "claude", "follow-up question").unwrap();
+
+        assert_eq!(last_user_text(file_path, "claude"), "follow-up question");
+        let record = last_record(&path);
+        assert_eq!(record["parentUuid"], "a1");
+        assert_eq!(record["sessionId"], "abc-123");
+        assert_eq!(record["cwd"], "/home/user/project");
+    }
+
+    #[test]
+    fn appended_codex_message_round_trips() {
+        let dir = temp_dir();
+        let path = write_lines(
+            &dir.path().join("rollout-2025-01-15T10-00-00-s1.jsonl"),
+            &[
+                codex_meta("s1", "/home/user/project"),
+                codex_message("user", "first question"),
+                codex_message("assistant", "first answer"),
+            ],
+        );
+        let file_path = path.to_str().unwrap();
+
+        append_user_message(file_path, "codex", "follow-up question").unwrap();
+
+        assert_eq!(last_user_text(file_path, "codex"), "follow-up question");
+        assert_eq!(last_record(&path)["type"], "response_item");
+    }
+
+    #[test]
+    fn append_refuses_files_being_written() {
+        let dir = temp_dir();
+        let path = write_lines(&dir.path().join("s.jsonl"), &[claude_user("u1", "hi")]);
+        let file_path = path.to_str().unwrap();
+
+        fs::write(dir.path().join("s.jsonl.lock"), "").unwrap();
+        let err = append_user_message(file_path, "claude", "more").unwrap_err();
+        assert!(err.contains("locked"), "{}", err);
+        fs::remove_file(dir.path().join("s.jsonl.lock")).unwrap();
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"type\": \"assist").unwrap();
+        let err = append_user_message(file_path, "claude", "more").unwrap_err();
+        assert!(err.contains("incomplete last line"), "{}", err);
+
+        let json = dir.path().join("old.json");
+        fs::write(&json, "[]").unwrap();
+        let err = append_user_message(json.to_str().unwrap(), "claude", "more").unwrap_err();
+        assert!(err.contains("Only JSONL"), "{}", err);
+    }
+
+    const PROJECT: &str = "-home-user-project";
+
+    /// Six messages: a question, a tool call, its result, the answer, a
+    /// follow-up and its reply. The tool result sits at index 2.
+    fn tool_transcript() -> Vec<String> {
+        let tool_use = json!({
+            "type": "assistant",
+            "uuid": "a1",
+            "sessionId": "orig",
+            "message": {
+                "role": "assistant",
+                "content": [{ "type": "tool_use", "id": "t1", "name": "Read", "input": { "path": "a.rs" } }],
+            },
+        });
+        let tool_result = json!({
+            "type": "user",
+            "uuid": "r1",
+            "parentUuid": "a1",
+            "sessionId": "orig",
+            "message": {
+                "role": "user",
+                "content": [{ "type": "tool_result", "tool_use_id": "t1", "content": "fn main() {}" }],
+            },
+        });
+        vec![
+            claude_user("u1", "what does a.rs do?"),
+            tool_use.to_string(),
+            tool_result.to_string(),
+            claude_assistant("a2", "it is empty", "claude-sonnet-4-6"),
+            claude_user("u2", "fill it in"),
+            claude_assistant("a3", "done", "claude-sonnet-4-6"),
+        ]
+    }
+
+    fn uuids(file_path: &str) -> Vec<String> {
+        parse_session_checked(file_path, "claude")
+            .unwrap()
+            .messages
+            .iter()
+            .map(|m| m.uuid.clone().unwrap_or_default())
+            .collect()
+    }
+
+    #[test]
+    fn trimmed_session_keeps_the_range_under_a_fresh_id() {
+        let home = TestHome::new();
+        let path = home.claude_session(PROJECT, "orig", &tool_transcript());
+        metadata::update_session_meta(
+            "claude",
+            PROJECT,
+            "orig",
+            Some("original".to_string()),
+            vec!["keep".to_string()],
+            None,
+        )
+        .unwrap();
+
+        let trimmed = trim_session(path.to_str().unwrap(), "claude", 3, 5).unwrap();
+
+        let trimmed_path = Path::new(&trimmed);
+        let new_id = trimmed_path.file_stem().unwrap().to_str().unwrap();
+        assert_ne!(new_id, "orig");
+        assert_eq!(trimmed_path.parent(), path.parent());
+        assert_eq!(uuids(&trimmed), ["a2", "u2", "a3"]);
+        let first: Value = serde_json::from_str(
+            fs::read_to_string(trimmed_path)
+                .unwrap()
+                .lines()
+                .next()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(first["parentUuid"], Value::Null);
+
+        let meta = metadata::load_metadata("claude", PROJECT);
+        assert_eq!(meta.sessions[new_id].tags, ["keep"]);
+        assert_eq!(uuids(path.to_str().unwrap()).len(), 6);
+    }
+
+    #[test]
+    fn trim_rejects_bad_ranges_and_dangling_tool_results() {
+        let home = TestHome::new();
+        let path = home.claude_session(PROJECT, "orig", &tool_transcript());
+        let file_path = path.to_str().unwrap();
+
+        let err = trim_session(file_path, "claude", 2, 4).unwrap_err();
+        assert_eq!(err, "Range cannot start on a tool result");
+        let err = trim_session(file_path, "claude", 4, 6).unwrap_err();
+        assert_eq!(err, "Invalid range 4..=6 for a session of 6 messages");
+        assert!(trim_session(file_path, "claude", 3, 1).is_err());
+        // Only the original is left in the project
+        let entries = fs::read_dir(path.parent().unwrap()).unwrap().count();
+        assert_eq!(entries, 1);
+    }
+
+    #[test]
+    fn trim_copies_tags_that_break_rules_tightened_since() {
+        let home = TestHome::new();
+        let path = home.claude_session(PROJECT, "orig", &tool_transcript());
+        metadata::update_session_meta(
+            "claude",
+            PROJECT,
+            "orig",
+            None,
+            vec!["legacy tag!".to_string()],
+            None,
+        )
+        .unwrap();
+        settings::save_settings(&settings::SettingsFile {
+            tag_rules: Some(settings::TagRules {
+                allowed_chars: Some("[a-z]".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let trimmed = trim_session(path.to_str().unwrap(), "claude", 3, 5).unwrap();
+
+        let new_id = Path::new(&trimmed).file_stem().unwrap().to_str().unwrap();
+        let meta = metadata::load_metadata("claude", PROJECT);
+        assert_eq!(meta.sessions[new_id].tags, ["legacy tag!"]);
+    }
+
+    #[test]
+    fn split_writes_each_part_with_the_parent_tags() {
+        let home = TestHome::new();
+        let path = home.claude_session(PROJECT, "orig", &tool_transcript());
+        metadata::update_session_meta(
+            "claude",
+            PROJECT,
+            "orig",
+            None,
+            vec!["keep".to_string()],
+            None,
+        )
+        .unwrap();
+
+        let parts = split_session(path.to_str().unwrap(), "claude", vec![1, 3]).unwrap();
+
+        let part_uuids: Vec<Vec<String>> = parts.iter().map(|p| uuids(p)).collect();
+        assert_eq!(
+            part_uuids,
+            [vec!["u1"], vec!["a1", "r1"], vec!["a2", "u2", "a3"]]
+        );
+        let meta = metadata::load_metadata("claude", PROJECT);
+        for part in &parts {
+            let id = Path::new(part).file_stem().unwrap().to_str().unwrap();
+            assert_ne!(id, "orig");
+            assert_eq!(meta.sessions[id].tags, ["keep"]);
+        }
+        assert_eq!(uuids(path.to_str().unwrap()).len(), 6);
+    }
+
+    #[test]
+    fn split_rejects_bad_indices_before_writing() {
+        let home = TestHome::new();
+        let path = home.claude_session(PROJECT, "orig", &tool_transcript());
+        let file_path = path.to_str().unwrap();
+
+        let err = split_session(file_path, "claude", vec![1, 2]).unwrap_err();
+        assert_eq!(
+            err,
+            "Split index 2 falls on a tool result and would orphan it from its call"
+        );
+        let err = split_session(file_path, "claude", vec![3, 1]).unwrap_err();
+        assert_eq!(err, "Split indices must be strictly ascending");
+        assert!(split_session(file_path, "claude", vec![0]).is_err());
+        assert!(split_session(file_path, "claude", vec![3, 6]).is_err());
+        assert!(split_session(file_path, "claude", vec![]).is_err());
+        let entries = fs::read_dir(path.parent().unwrap()).unwrap().count();
+        assert_eq!(entries, 1);
+    }
+
+    #[test]
+    fn single_messages_become_chat_messages() {
+        let home = TestHome::new();
+        let mut lines = tool_transcript();
+        let thinking = json!({
+            "type": "assistant",
+            "uuid": "k1",
+            "message": {
+                "role": "assistant",
+                "content": [
+                    { "type": "thinking", "thinking": "hidden reasoning" },
+                    { "type": "text", "text": "  visible answer  " },
+                ],
+            },
+        });
+        lines.push(thinking.to_string());
+        let path = home.claude_session(PROJECT, "orig", &lines);
+        let file_path = path.to_str().unwrap();
+        let copy = |id: &str| message_to_chat_msg(file_path, "claude", id);
+
+        let answer = copy("a2").unwrap();
+        assert_eq!(
+            (answer.role.as_str(), answer.content.as_str()),
+            ("assistant", "it is empty")
+        );
+
+        let call = copy("a1").unwrap();
+        assert!(
+            call.content.starts_with("[Tool call: Read]\n"),
+            "{}",
+            call.content
+        );
+        assert!(call.content.contains("a.rs"));
+
+        let result = copy("r1").unwrap();
+        assert_eq!(result.role, "user");
+        assert_eq!(result.content, "[Tool result]\nfn main() {}");
+
+        assert_eq!(copy("k1").unwrap().content, "visible answer");
+        assert_eq!(copy("zz9").unwrap_err(), "Message not found: zz9");
+    }
+
+    fn roles(messages: &[ChatMsg]) -> Vec<&str> {
+        messages.iter().map(|m| m.role.as_str()).collect()
+    }
+
+    #[test]
+    fn tools_are_left_out_unless_asked_for() {
+        let home = TestHome::new();
+        let path = home.claude_session(PROJECT, "orig", &tool_transcript());
+
+        let messages = load_session_as_messages(path.to_str().unwrap(), "claude", false).unwrap();
+        assert_eq!(roles(&messages), ["user", "assistant", "user", "assistant"]);
+        assert_eq!(messages[1].content, "it is empty");
+        assert!(messages.iter().all(|m| m.blocks.is_none()));
+    }
+
+    #[test]
+    fn a_call_left_unanswered_at_the_end_becomes_text() {
+        let home = TestHome::new();
+        let path = home.claude_session(PROJECT, "orig", &tool_transcript()[..2]);
+
+        let messages = load_session_as_messages(path.to_str().unwrap(), "claude", true).unwrap();
+        assert_eq!(roles(&messages), ["user", "assistant"]);
+        let call = &messages[1].blocks.as_ref().unwrap()[0];
+        assert_eq!(call["type"], "text");
+        assert!(call["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("[Tool call: Read]"));
+    }
+
+    #[tokio::test]
+    async fn tool_blocks_reach_the_api_paired_and_in_order() {
+        let home = TestHome::new();
+        let path = home.claude_session(PROJECT, "orig", &tool_transcript());
+        let server = MockServer::start(vec![MockResponse::sse(&sse_events(&[
+            json!({ "type": "message_stop" }),
+        ]))]);
+        std::env::set_var("ANTHROPIC_API_KEY", "sk-test");
+        std::env::set_var("ANTHROPIC_BASE_URL", &server.url);
+
+        let messages = load_session_as_messages(path.to_str().unwrap(), "claude", true).unwrap();
+        quick_chat::stream_chat(
+            "claude",
+            messages,
+            "claude-test",
+            &ChatOptions::default(),
+            |_| {},
+        )
+        .await
+        .unwrap();
+
+        let body: Value = serde_json::from_str(&server.requests()[0].body).unwrap();
+        let sent = body["messages"].as_array().unwrap();
+        let sent_roles: Vec<&str> = sent.iter().map(|m| m["role"].as_str().unwrap()).collect();
+        assert_eq!(
+            sent_roles,
+            [
+                "user",
+                "assistant",
+                "user",
+                "assistant",
+                "user",
+                "assistant"
+            ]
+        );
+        assert_eq!(
+            sent[1]["content"],
+            json!([{ "type": "tool_use", "id": "t1", "name": "Read", "input": { "path": "a.rs" } }])
+        );
+        let result = &sent[2]["content"][0];
+        assert_eq!(result["type"], "tool_result");
+        assert_eq!(result["tool_use_id"], "t1");
+        assert_eq!(result["content"], "fn main() {}");
+        assert_eq!(sent[3]["content"][0]["text"], "it is empty");
+    }
+}
@@ -0,0 +1,202 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+
+use crate::models::session::SessionIndexEntry;
+use crate::stats;
+
+/// Page size used when a query does not give one
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Inclusive "YYYY-MM-DD" bounds on the day a session started
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DateRange {
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionFilters {
+    /// Sessions must carry every one of these tags (case-insensitive)
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub min_rating: Option<u8>,
+    #[serde(default)]
+    pub date_range: Option<DateRange>,
+    /// Case-insensitive substring of the alias, first prompt, tags or session id
+    #[serde(default)]
+    pub search: Option<String>,
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionSort {
+    /// Most recently modified first
+    #[default]
+    Newest,
+    /// Least recently modified first
+    Oldest,
+    /// Most messages first
+    Messages,
+    /// Highest rated first, unrated last
+    Rating,
+    /// By alias (or first prompt), A–Z
+    Name,
+}
+
+/// A filtered, sorted, paginated session listing
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionQuery {
+    pub source: String,
+    /// None covers all projects
+    #[serde(default)]
+    pub project_id: Option<String>,
+    #[serde(default)]
+    pub filters: SessionFilters,
+    #[serde(default)]
+    pub sort: SessionSort,
+    /// Zero-based
+    #[serde(default)]
+    pub page: usize,
+    #[serde(default)]
+    pub page_size: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionPage {
+    /// Matching sessions across all pages
+    pub total: usize,
+    pub page: usize,
+    pub sessions: Vec<SessionIndexEntry>,
+}
+
+/// Keep sessions tagged with all of `tags`
+pub fn filter_by_tags(sessions: &mut Vec<SessionIndexEntry>, tags: &[String]) {
+    if tags.is_empty() {
+        return;
+    }
+    let wanted: Vec<String> = tags.iter().map(|t| t.to_lowercase()).collect();
+    sessions.retain(|s| {
+        let have: Vec<String> = s.tags.iter().flatten().map(|t| t.to_lowercase()).collect();
+        wanted.iter().all(|t| have.contains(t))
+    });
+}
+
+/// Keep sessions rated at least `min`; unrated sessions are dropped
+pub fn filter_by_min_rating(sessions: &mut Vec<SessionIndexEntry>, min: u8) {
+    sessions.retain(|s| s.rating.is_some_and(|r| r >= min));
+}
+
+/// Keep sessions that started within `range`
+pub fn filter_by_date_range(
+    sessions: &mut Vec<SessionIndexEntry>,
+    range: &DateRange,
+) -> Result<(), String> {
+    let from = range
+        .from
+        .as_deref()
+        .map(stats::parse_date_bound)
+        .transpose()?;
+    let to = range
+        .to
+        .as_deref()
+        .map(stats::parse_date_bound)
+        .transpose()?;
+    if from.is_none() && to.is_none() {
+        return Ok(());
+    }
+    sessions.retain(|s| match stats::session_start_date(s) {
+        Some(date) => !(from.is_some_and(|f| date < f) || to.is_some_and(|t| date > t)),
+        None => false,
+    });
+    Ok(())
+}
+
+/// Keep sessions whose alias, first prompt, tags or id contain `query`
+pub fn filter_by_search(sessions: &mut Vec<SessionIndexEntry>, query: &str) {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return;
+    }
+    let contains = |text: &str| text.to_lowercase().contains(&query);
+    sessions.retain(|s| {
+        s.alias.as_deref().is_some_and(contains)
+            || s.first_prompt.as_deref().is_some_and(contains)
+            || s.tags.iter().flatten().any(|t| contains(t))
+            || contains(&s.session_id)
+    });
+}
+
+/// Sort in place; ties keep the newest first
+pub fn sort_sessions(sessions: &mut [SessionIndexEntry], sort: SessionSort) {
+    sessions.sort_by(|a, b| b.modified.cmp(&a.modified));
+    match sort {
+        SessionSort::Newest => {}
+        SessionSort::Oldest => sessions.reverse(),
+        SessionSort::Messages => sessions.sort_by_key(|s| Reverse(s.message_count)),
+        SessionSort::Rating => sessions.sort_by_key(|s| Reverse(s.rating)),
+        SessionSort::Name => {
+            let name = |s: &SessionIndexEntry| {
+                s.alias
+                    .as_deref()
+                    .or(s.first_prompt.as_deref())
+                    .unwrap_or("")
+                    .to_lowercase()
+            };
+            sessions.sort_by_cached_key(name);
+        }
+    }
+}
+
+/// One zero-based page of `items` (empty past the end)
+pub fn paginate<T>(items: Vec<T>, page: usize, page_size: usize) -> Vec<T> {
+    items
+        .into_iter()
+        .skip(page.saturating_mul(page_size))
+        .take(page_size)
+        .collect()
+}
+
+/// Apply every filter in `filters`, in a fixed order
+pub fn apply_filters(
+    sessions: &mut Vec<SessionIndexEntry>,
+    filters: &SessionFilters,
+) -> Result<(), String> {
+    if !filters.include_archived {
+        sessions.retain(|s| !s.archived);
+    }
+    filter_by_tags(sessions, &filters.tags);
+    if let Some(min) = filters.min_rating {
+        filter_by_min_rating(sessions, min);
+    }
+    if let Some(range) = &filters.date_range {
+        filter_by_date_range(sessions, range)?;
+    }
+    if let Some(search) = &filters.search {
+        filter_by_search(sessions, search);
+    }
+    Ok(())
+}
+
+/// Run a combined query: list (with metadata merged), filter, sort, paginate.
+/// `total` counts all matches, before pagination.
+pub fn query_sessions(query: &SessionQuery) -> Result<SessionPage, String> {
+    let page_size = query.page_size.unwrap_or(DEFAULT_PAGE_SIZE).max(1);
+    let mut sessions = stats::list_sessions(&query.source, query.project_id.as_deref())?;
+    apply_filters(&mut sessions, &query.filters)?;
+    sort_sessions(&mut sessions, query.sort);
+
+    Ok(SessionPage {
+        total: sessions.len(),
+        page: query.page,
+        sessions: paginate(sessions, query.page, page_size),
+    })
+}
@@ -0,0 +1,575 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::data_dir;
+use crate::http;
+
+/// Default User-Agent sent on all outbound API requests
+pub const DEFAULT_USER_AGENT: &str = concat!("ai-session-viewer/", env!("CARGO_PKG_VERSION"));
+
+/// Default connect timeout for quick diagnostic calls (model fetching)
+pub const DEFAULT_DIAGNOSTIC_CONNECT_TIMEOUT_SECS: u64 = 5;
+/// Default total request timeout for quick diagnostic calls (model fetching)
+pub const DEFAULT_DIAGNOSTIC_TIMEOUT_SECS: u64 = 10;
+
+/// Anthropic API base URL used when nothing else is configured
+pub const ANTHROPIC_BASE_URL: &str = "https://api.anthropic.com";
+
+/// Default cap on API error bodies read into memory (4 MiB)
+pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 4 * 1024 * 1024;
+
+/// Default length of session previews and search snippets, in characters
+pub const DEFAULT_PREVIEW_MAX_CHARS: usize = 120;
+
+/// Default size above which a session file counts as large (20 MiB)
+pub const DEFAULT_LARGE_SESSION_BYTES: u64 = 20 * 1024 * 1024;
+
+/// Default number of days trashed sessions are kept before being purged
+pub const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+
+/// Constraints tags must meet before they are saved. Every rule is optional;
+/// the default accepts any tag.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagRules {
+    /// Longest tag allowed, in characters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    /// Regex character class every character of a tag must match, e.g. `[a-z0-9-]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_chars: Option<String>,
+    /// Tags that are never accepted (compared case-insensitively)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disallowed: Vec<String>,
+}
+
+/// Viewer-level settings (`.session-viewer-settings.json` in the data directory)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsFile {
+    pub version: u32,
+    /// Override for the User-Agent header (None = DEFAULT_USER_AGENT)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    /// Connect timeout for model fetching, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diagnostic_connect_timeout_secs: Option<u64>,
+    /// Total request timeout for model fetching, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diagnostic_timeout_secs: Option<u64>,
+    /// Cap on API error bodies read into memory, in bytes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_response_bytes: Option<usize>,
+    /// Worker threads for cross-project scans (None = number of cores)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrency: Option<usize>,
+    /// Lowercase tags when they are saved (None = keep casing)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lowercase_tags: Option<bool>,
+    /// Base URL used when neither the CLI config nor ANTHROPIC_BASE_URL sets one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_base_url: Option<String>,
+    /// Glob patterns (`*`, `**`, `?`) on decoded project paths to leave out of
+    /// project lists; ignored projects stay reachable by id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ignored_projects: Option<Vec<String>>,
+    /// NDJSON file each completed quick-chat exchange is appended to
+    /// (None = no logging)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chat_log_path: Option<String>,
+    /// Length of session previews and search snippets, in characters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview_max_chars: Option<usize>,
+    /// Session file size, in bytes, above which the UI warns before opening
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub large_session_bytes: Option<u64>,
+    /// Extra headers sent on Anthropic API requests (chat and model listing),
+    /// e.g. for gateways that route on an org header. Auth headers can't be set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_headers: Option<HashMap<String, String>>,
+    /// Days a trashed session is kept before it is purged at startup
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trash_retention_days: Option<u32>,
+    /// Constraints enforced when tags are saved (None = any tag)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag_rules: Option<TagRules>,
+}
+
+impl Default for SettingsFile {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            user_agent: None,
+            diagnostic_connect_timeout_secs: None,
+            diagnostic_timeout_secs: None,
+            max_response_bytes: None,
+            max_concurrency: None,
+            lowercase_tags: None,
+            default_base_url: None,
+            ignored_projects: None,
+            chat_log_path: None,
+            preview_max_chars: None,
+            large_session_bytes: None,
+            custom_headers: None,
+            trash_retention_days: None,
+            tag_rules: None,
+        }
+    }
+}
+
+/// A problem found in the settings file by `validate_settings`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsWarning {
+    /// Offending key (None for problems with the file as a whole)
+    pub key: Option<String>,
+    pub message: String,
+}
+
+impl SettingsWarning {
+    fn new(key: Option<&str>, message: impl Into<String>) -> Self {
+        Self {
+            key: key.map(String::from),
+            message: message.into(),
+        }
+    }
+}
+
+/// Keys `SettingsFile` understands (camelCase, as stored)
+const KNOWN_KEYS: &[&str] = &[
+    "version",
+    "userAgent",
+    "diagnosticConnectTimeoutSecs",
+    "diagnosticTimeoutSecs",
+    "maxResponseBytes",
+    "maxConcurrency",
+    "lowercaseTags",
+    "defaultBaseUrl",
+    "ignoredProjects",
+    "chatLogPath",
+    "previewMaxChars",
+    "largeSessionBytes",
+    "customHeaders",
+    "trashRetentionDays",
+    "tagRules",
+];
+
+fn settings_path() -> Result<PathBuf, String> {
+    Ok(data_dir::data_dir()?.join(".session-viewer-settings.json"))
+}
+
+/// Load settings; returns defaults if the file is missing or invalid
+pub fn load_settings() -> SettingsFile {
+    let path = match settings_path() {
+        Ok(p) => p,
+        Err(_) => return SettingsFile::default(),
+    };
+    fs::read_to_string(&path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+/// Save settings (atomic: write tmp + rename)
+pub fn save_settings(settings: &SettingsFile) -> Result<(), String> {
+    let path = settings_path()?;
+    let json = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, &json).map_err(|e| format!("Failed to write settings tmp: {}", e))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to rename settings file: {}", e))?;
+    Ok(())
+}
+
+/// Resolve the User-Agent for outbound requests (settings override → default)
+pub fn user_agent() -> String {
+    load_settings()
+        .user_agent
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string())
+}
+
+/// Resolve (connect, total) timeouts for diagnostic calls.
+///
+/// The connect timeout is the default of every client from `http::client()`.
+/// The total timeout is set per request, and the only diagnostic request is
+/// the `/v1/models` fetch; chat streams use their own, longer limits.
+pub fn diagnostic_timeouts() -> (Duration, Duration) {
+    let settings = load_settings();
+    let connect = settings
+        .diagnostic_connect_timeout_secs
+        .filter(|s| *s > 0)
+        .unwrap_or(DEFAULT_DIAGNOSTIC_CONNECT_TIMEOUT_SECS);
+    let total = settings
+        .diagnostic_timeout_secs
+        .filter(|s| *s > 0)
+        .unwrap_or(DEFAULT_DIAGNOSTIC_TIMEOUT_SECS);
+    (Duration::from_secs(connect), Duration::from_secs(total))
+}
+
+/// Resolve the cap on API error bodies (settings override → default)
+pub fn max_response_bytes() -> usize {
+    load_settings()
+        .max_response_bytes
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_MAX_RESPONSE_BYTES)
+}
+
+/// Resolve the preview/snippet length (settings override → default)
+pub fn preview_max_chars() -> usize {
+    load_settings()
+        .preview_max_chars
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_PREVIEW_MAX_CHARS)
+}
+
+/// Resolve the large-session threshold in bytes (settings override → default)
+pub fn large_session_bytes() -> u64 {
+    load_settings()
+        .large_session_bytes
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_LARGE_SESSION_BYTES)
+}
+
+/// Resolve how long trashed sessions are kept, in days (settings override → default)
+pub fn trash_retention_days() -> u32 {
+    load_settings()
+        .trash_retention_days
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS)
+}
+
+/// Configured tag constraints (the permissive default when unset)
+pub fn tag_rules() -> TagRules {
+    load_settings().tag_rules.unwrap_or_default()
+}
+
+/// Configured extra API request headers (empty when unset)
+pub fn custom_headers() -> HashMap<String, String> {
+    load_settings().custom_headers.unwrap_or_default()
+}
+
+/// Resolve the concurrency limit for cross-project scans (settings override → core count)
+pub fn max_concurrency() -> usize {
+    load_settings()
+        .max_concurrency
+        .filter(|n| *n > 0)
+        .unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+        })
+}
+
+/// Whether tags are lowercased on save (opt-in, off by default)
+pub fn lowercase_tags() -> bool {
+    load_settings().lowercase_tags.unwrap_or(false)
+}
+
+/// Resolve the last-resort API base URL (settings override → Anthropic)
+pub fn default_base_url() -> String {
+    load_settings()
+        .default_base_url
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| ANTHROPIC_BASE_URL.to_string())
+}
+
+/// Quick-chat transcript log file (opt-in; None when unset or blank)
+pub fn chat_log_path() -> Option<PathBuf> {
+    load_settings()
+        .chat_log_path
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Project ignore patterns (empty when none are configured)
+pub fn ignored_projects() -> Vec<String> {
+    load_settings().ignored_projects.unwrap_or_default()
+}
+
+/// Whether a decoded project path matches any of `patterns`
+pub fn is_project_ignored(patterns: &[String], path: &str) -> bool {
+    let path = path.replace('\\', "/");
+    let text: Vec<char> = path.trim_end_matches('/').chars().collect();
+    patterns.iter().any(|p| {
+        let p = p.trim().replace('\\', "/");
+        let pattern: Vec<char> = p.trim_end_matches('/').chars().collect();
+        glob_match(&pattern, &text)
+    })
+}
+
+/// Add a project ignore pattern (no-op if already present)
+pub fn add_ignored_project(pattern: &str) -> Result<(), String> {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return Err("Pattern must not be empty".to_string());
+    }
+    let mut settings = load_settings();
+    let patterns = settings.ignored_projects.get_or_insert_with(Vec::new);
+    if patterns.iter().any(|p| p == pattern) {
+        return Ok(());
+    }
+    patterns.push(pattern.to_string());
+    save_settings(&settings)
+}
+
+/// Remove a project ignore pattern (no-op if absent)
+pub fn remove_ignored_project(pattern: &str) -> Result<(), String> {
+    let mut settings = load_settings();
+    let patterns = match settings.ignored_projects.as_mut() {
+        Some(p) => p,
+        None => return Ok(()),
+    };
+    let len_before = patterns.len();
+    patterns.retain(|p| p != pattern.trim());
+    if patterns.len() == len_before {
+        return Ok(());
+    }
+    if patterns.is_empty() {
+        settings.ignored_projects = None;
+    }
+    save_settings(&settings)
+}
+
+/// Glob match: `**` spans path separators, `*` doesn't, `?` is one character
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            let rest = &pattern[2..];
+            (0..=text.len()).any(|i| glob_match(rest, &text[i..]))
+        }
+        Some('*') => {
+            let rest = &pattern[1..];
+            let max = text.iter().position(|c| *c == '/').unwrap_or(text.len());
+            (0..=max).any(|i| glob_match(rest, &text[i..]))
+        }
+        Some('?') => !text.is_empty() && text[0] != '/' && glob_match(&pattern[1..], &text[1..]),
+        Some(c) => text.first() == Some(c) && glob_match(&pattern[1..], &text[1..]),
+    }
+}
+
+/// Check the settings file without loading or rewriting it.
+///
+/// A missing file is fine (no warnings). A present file is checked for
+/// invalid JSON, unknown keys, wrong types and values that are ignored at
+/// runtime (zero timeouts, blank strings, non-HTTP URLs). When the file would
+/// not deserialize, a warning says that every setting falls back to defaults,
+/// so the UI can warn before saving over it.
+pub fn validate_settings() -> Result<Vec<SettingsWarning>, String> {
+    let path = settings_path()?;
+    let content = match fs::read_to_string(&path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read settings: {}", e)),
+    };
+
+    let value: Value = match serde_json::from_str(&content) {
+        Ok(v) => v,
+        Err(e) => {
+            return Ok(vec![SettingsWarning::new(
+                None,
+                format!("Not valid JSON ({}); all settings fall back to defaults", e),
+            )])
+        }
+    };
+    let obj = match value.as_object() {
+        Some(o) => o,
+        None => {
+            return Ok(vec![SettingsWarning::new(
+                None,
+                "Expected a JSON object; all settings fall back to defaults",
+            )])
+        }
+    };
+
+    let mut warnings = Vec::new();
+    for (key, value) in obj {
+        let key = key.as_str();
+        if !KNOWN_KEYS.contains(&key) {
+            warnings.push(SettingsWarning::new(Some(key), "Unknown setting (ignored)"));
+            continue;
+        }
+        if let Some(message) = check_setting(key, value) {
+            warnings.push(SettingsWarning::new(Some(key), message));
+        }
+    }
+
+    if let Err(e) = serde_json::from_value::<SettingsFile>(value.clone()) {
+        warnings.push(SettingsWarning::new(
+            None,
+            format!(
+                "Settings do not load ({}); all settings fall back to defaults",
+                e
+            ),
+        ));
+    }
+    Ok(warnings)
+}
+
+/// Problem with one known key's value, if any
+fn check_setting(key: &str, value: &Value) -> Option<String> {
+    match key {
+        "version" => match value.as_u64() {
+            Some(1) => None,
+            Some(v) => Some(format!("Unsupported version {}", v)),
+            None => Some("Must be a non-negative integer".to_string()),
+        },
+        "userAgent" => match value.as_str() {
+            Some(s) if s.trim().is_empty() => Some("Blank; the default is used".to_string()),
+            Some(_) => None,
+            None => Some("Must be a string".to_string()),
+        },
+        "diagnosticConnectTimeoutSecs"
+        | "diagnosticTimeoutSecs"
+        | "maxResponseBytes"
+        | "maxConcurrency"
+        | "previewMaxChars"
+        | "largeSessionBytes"
+        | "trashRetentionDays" => match value.as_u64() {
+            Some(0) => Some("Zero is ignored; the default is used".to_string()),
+            Some(_) => None,
+            None => Some("Must be a positive integer".to_string()),
+        },
+        "lowercaseTags" => match value {
+            Value::Bool(_) => None,
+            _ => Some("Must be true or false".to_string()),
+        },
+        "tagRules" => match serde_json::from_value::<TagRules>(value.clone()) {
+            Ok(rules) => rules.allowed_chars.and_then(|class| {
+                regex::Regex::new(&class)
+                    .err()
+                    .map(|e| format!("allowedChars is not a valid regex ({}); it is ignored", e))
+            }),
+            Err(e) => Some(format!("Invalid tag rules: {}", e)),
+        },
+        "ignoredProjects" => match value.as_array() {
+            Some(items) if items.iter().all(|v| v.is_string()) => None,
+            _ => Some("Must be a list of strings".to_string()),
+        },
+        "customHeaders" => match value.as_object() {
+            Some(headers) => {
+                let problems: Vec<String> = headers
+                    .iter()
+                    .filter_map(|(name, value)| match value.as_str() {
+                        Some(v) => http::check_custom_header(name, v).err(),
+                        None => Some(format!("{}: value must be a string", name)),
+                    })
+                    .collect();
+                if problems.is_empty() {
+                    None
+                } else {
+                    Some(format!("{} (ignored)", problems.join("; ")))
+                }
+            }
+            None => Some("Must be an object of header names to values".to_string()),
+        },
+        "chatLogPath" => match value.as_str() {
+            Some(s) if s.trim().is_empty() => Some("Blank; chat logging is off".to_string()),
+            Some(_) => None,
+            None => Some("Must be a string".to_string()),
+        },
+        "defaultBaseUrl" => match value.as_str().map(str::trim) {
+            Some("") => Some("Blank; the Anthropic API URL is used".to_string()),
+            Some(s) if s.starts_with("http://") || s.starts_with("https://") => None,
+            Some(_) => Some("Must start with http:// or https://".to_string()),
+            None => Some("Must be a string".to_string()),
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestHome;
+
+    fn write_settings(content: &str) {
+        fs::write(settings_path().unwrap(), content).unwrap();
+    }
+
+    /// Warnings as (key, message), sorted by key
+    fn warnings() -> Vec<(Option<String>, String)> {
+        let mut warnings: Vec<_> = validate_settings()
+            .unwrap()
+            .into_iter()
+            .map(|w| (w.key, w.message))
+            .collect();
+        warnings.sort();
+        warnings
+    }
+
+    fn keys(warnings: &[(Option<String>, String)]) -> Vec<Option<&str>> {
+        warnings.iter().map(|(k, _)| k.as_deref()).collect()
+    }
+
+    #[test]
+    fn missing_file_is_fine() {
+        let _home = TestHome::new();
+        assert!(warnings().is_empty());
+    }
+
+    #[test]
+    fn valid_file_has_no_warnings() {
+        let _home = TestHome::new();
+        write_settings(
+            r#"{"version": 1, "userAgent": "me/1.0", "maxConcurrency": 4,
+                "defaultBaseUrl": "https://proxy.example", "ignoredProjects": ["/tmp/*"]}"#,
+        );
+        assert!(warnings().is_empty());
+    }
+
+    #[test]
+    fn broken_file_reports_each_problem() {
+        let _home = TestHome::new();
+        let broken = r#"{
+            "version": 2,
+            "userAgnet": "typo/1.0",
+            "maxConcurrency": 0,
+            "defaultBaseUrl": "proxy.example",
+            "customHeaders": {"x-api-key": "sk-stolen"},
+            "lowercaseTags": "yes",
+            "tagRules": {"allowedChars": "[a-z"}
+        }"#;
+        write_settings(broken);
+
+        let warnings = warnings();
+        assert_eq!(
+            keys(&warnings),
+            [
+                None,
+                Some("customHeaders"),
+                Some("defaultBaseUrl"),
+                Some("lowercaseTags"),
+                Some("maxConcurrency"),
+                Some("tagRules"),
+                Some("userAgnet"),
+                Some("version"),
+            ]
+        );
+        // lowercaseTags has the wrong type, so nothing loads
+        assert!(warnings[0].1.contains("all settings fall back to defaults"));
+        assert_eq!(warnings[6].1, "Unknown setting (ignored)");
+        // Validation never rewrites the file
+        assert_eq!(
+            fs::read_to_string(settings_path().unwrap()).unwrap(),
+            broken
+        );
+    }
+
+    #[test]
+    fn invalid_json_is_a_single_file_warning() {
+        let _home = TestHome::new();
+        write_settings("{\"version\": 1,");
+        let warnings = warnings();
+        assert_eq!(keys(&warnings), [None]);
+        assert!(warnings[0].1.starts_with("Not valid JSON"));
+    }
+}
@@ -2,7 +2,10 @@ use lru::LruCache;
 use parking_lot::Mutex;
 use std::num::NonZeroUsize;
 
+use crate::model_list;
 use crate::models::message::DisplayMessage;
+use crate::project_info;
+use crate::session_cache;
 
 /// Application state shared across commands
 #[allow(dead_code)]
@@ -17,6 +20,16 @@ impl AppState {
             message_cache: Mutex::new(LruCache::new(NonZeroUsize::new(20).unwrap())),
         }
     }
+
+    /// Empty every in-memory cache: parsed messages, the API model list,
+    /// project info and session index entries. Safe to call repeatedly.
+    pub fn clear_all_caches(&self) -> Result<(), String> {
+        self.message_cache.lock().clear();
+        model_list::clear_cache();
+        project_info::clear_cache();
+        session_cache::clear_cache();
+        Ok(())
+    }
 }
 
 impl Default for AppState {
@@ -1,9 +1,17 @@
+use rayon::prelude::*;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::fs;
+use std::path::Path;
 
+use crate::metadata;
+use crate::models::message::Role;
+use crate::models::session::{SessionIndexEntry, SessionTokenUsage};
 use crate::models::stats::{DailyTokenEntry, StatsCache, TokenUsageSummary};
+use crate::parser::jsonl as claude_parser;
 use crate::parser::path_encoder::get_stats_cache_path;
-use crate::provider::codex;
+use crate::provider::{claude, codex, timestamps};
+use crate::search;
 
 pub fn get_stats(source: &str) -> Result<TokenUsageSummary, String> {
     match source {
@@ -13,6 +21,312 @@ pub fn get_stats(source: &str) -> Result<TokenUsageSummary, String> {
     }
 }
 
+/// Count messages per canonical role (user/assistant/tool/system) in one session.
+/// Empty sessions yield an empty map.
+pub fn message_role_counts(
+    file_path: &str,
+    source: &str,
+) -> Result<HashMap<String, usize>, String> {
+    let path = Path::new(file_path);
+    let messages = match source {
+        "claude" => claude::parse_all_messages(path)?,
+        "codex" => codex::parse_all_messages(path)?,
+        _ => return Err(format!("Unknown source: {}", source)),
+    };
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for msg in &messages {
+        *counts.entry(msg.role.as_str().to_string()).or_insert(0) += 1;
+    }
+    Ok(counts)
+}
+
+/// Rough characters per token used for token estimates
+const CHARS_PER_TOKEN: usize = 4;
+
+/// The longest turn of a session, from `longest_turn`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TurnInfo {
+    /// Position in the session's message list (as used by trim/split)
+    pub index: usize,
+    pub role: String,
+    /// Characters across all of the turn's blocks (text, thinking, tool input)
+    pub chars: usize,
+    /// `chars` / 4, rounded up
+    pub estimated_tokens: usize,
+}
+
+/// Find the assistant message with the most content in a session, for
+/// spotting verbose replies. Ties go to the earliest message.
+pub fn longest_turn(file_path: &str, source: &str) -> Result<TurnInfo, String> {
+    let path = Path::new(file_path);
+    let messages = match source {
+        "claude" => claude::parse_all_messages(path)?,
+        "codex" => codex::parse_all_messages(path)?,
+        _ => return Err(format!("Unknown source: {}", source)),
+    };
+    if messages.is_empty() {
+        return Err("Empty session: no messages".to_string());
+    }
+
+    messages
+        .iter()
+        .enumerate()
+        .filter(|(_, msg)| msg.role == Role::Assistant)
+        .map(|(index, msg)| {
+            let chars = msg
+                .content
+                .iter()
+                .map(|block| search::block_text(block).chars().count())
+                .sum::<usize>();
+            (index, msg, chars)
+        })
+        .min_by_key(|(index, _, chars)| (std::cmp::Reverse(*chars), *index))
+        .map(|(index, msg, chars)| TurnInfo {
+            index,
+            role: msg.role.as_str().to_string(),
+            chars,
+            estimated_tokens: chars.div_ceil(CHARS_PER_TOKEN),
+        })
+        .ok_or_else(|| "Session has no assistant messages".to_string())
+}
+
+/// Return the `limit` sessions with the highest total token usage, with
+/// `token_usage` populated. `project_id: None` searches across all projects.
+pub fn sessions_by_token_usage(
+    source: &str,
+    project_id: Option<&str>,
+    limit: usize,
+) -> Result<Vec<SessionIndexEntry>, String> {
+    let mut sessions = list_sessions(source, project_id)?;
+
+    sessions.par_iter_mut().for_each(|session| {
+        let path = Path::new(&session.file_path);
+        session.token_usage = match source {
+            "claude" => claude_parser::extract_token_usage(path),
+            _ => codex::extract_token_info(path).map(|t| SessionTokenUsage {
+                input_tokens: t.input_tokens,
+                output_tokens: t.output_tokens,
+                total_tokens: t.total_tokens,
+            }),
+        };
+    });
+
+    let total = |s: &SessionIndexEntry| s.token_usage.as_ref().map(|u| u.total_tokens).unwrap_or(0);
+    sessions.sort_by_key(|s| std::cmp::Reverse(total(s)));
+    sessions.truncate(limit);
+    Ok(sessions)
+}
+
+/// Number of sessions started per local day ("YYYY-MM-DD"), for an activity
+/// heatmap. `project_id: None` covers all projects; `from`/`to` are inclusive
+/// "YYYY-MM-DD" bounds. Uses only the session list, without parsing transcripts.
+pub fn activity_by_day(
+    source: &str,
+    project_id: Option<&str>,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<HashMap<String, usize>, String> {
+    let from = from.map(parse_date_bound).transpose()?;
+    let to = to.map(parse_date_bound).transpose()?;
+
+    let mut days: HashMap<String, usize> = HashMap::new();
+    for session in list_sessions(source, project_id)? {
+        let date = match session_start_date(&session) {
+            Some(d) => d,
+            None => continue,
+        };
+        if from.is_some_and(|f| date < f) || to.is_some_and(|t| date > t) {
+            continue;
+        }
+        *days.entry(date.format("%Y-%m-%d").to_string()).or_insert(0) += 1;
+    }
+    Ok(days)
+}
+
+/// Parse a "YYYY-MM-DD" range bound
+pub(crate) fn parse_date_bound(d: &str) -> Result<chrono::NaiveDate, String> {
+    chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date (expected YYYY-MM-DD): {}", d))
+}
+
+/// Local day a session started (falling back to its last modification)
+pub(crate) fn session_start_date(session: &SessionIndexEntry) -> Option<chrono::NaiveDate> {
+    let timestamp = session.created.as_deref().or(session.modified.as_deref())?;
+    timestamps::parse_timestamp(timestamp)
+        .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+        .map(|dt| dt.with_timezone(&chrono::Local).date_naive())
+}
+
+/// Age buckets of `session_age_histogram`, newest first
+pub const AGE_BUCKETS: [&str; 4] = ["today", "this week", "this month", "older"];
+
+/// Number of sessions per age bucket by file mtime, in `AGE_BUCKETS` order
+/// (empty buckets included). Weeks start on Monday, in local time.
+/// `project_id: None` covers all projects.
+pub fn session_age_histogram(
+    source: &str,
+    project_id: Option<&str>,
+) -> Result<Vec<(String, usize)>, String> {
+    let today = chrono::Local::now().date_naive();
+    let mut counts = [0usize; AGE_BUCKETS.len()];
+    for session in list_sessions(source, project_id)? {
+        let modified = match fs::metadata(&session.file_path).and_then(|m| m.modified()) {
+            Ok(t) => t,
+            Err(_) => continue,
+        };
+        let date = chrono::DateTime::<chrono::Local>::from(modified).date_naive();
+        counts[age_bucket(date, today)] += 1;
+    }
+
+    Ok(AGE_BUCKETS
+        .iter()
+        .zip(counts)
+        .map(|(name, count)| (name.to_string(), count))
+        .collect())
+}
+
+/// Index into `AGE_BUCKETS` for a session last modified on `date`
+fn age_bucket(date: chrono::NaiveDate, today: chrono::NaiveDate) -> usize {
+    use chrono::Datelike;
+
+    let week_start = today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+    let month_start = today.with_day(1).unwrap_or(today);
+    if date >= today {
+        0
+    } else if date >= week_start {
+        1
+    } else if date >= month_start {
+        2
+    } else {
+        3
+    }
+}
+
+/// Distinct models used in a project's sessions with the number of sessions
+/// that used each, most used first. `project_id: None` covers all projects.
+pub fn models_used(source: &str, project_id: Option<&str>) -> Result<Vec<(String, usize)>, String> {
+    let sessions = list_sessions(source, project_id)?;
+
+    let per_session: Vec<Vec<String>> = sessions
+        .par_iter()
+        .map(|session| {
+            let path = Path::new(&session.file_path);
+            match source {
+                "claude" => claude_parser::extract_models(path),
+                _ => codex::extract_models(path),
+            }
+        })
+        .collect();
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for model in per_session.into_iter().flatten() {
+        *counts.entry(model).or_insert(0) += 1;
+    }
+
+    let mut models: Vec<(String, usize)> = counts.into_iter().collect();
+    models.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    Ok(models)
+}
+
+/// Bucket key for sessions without tags in `sessions_grouped_by_tag`
+pub const UNTAGGED: &str = "untagged";
+
+/// Sessions of a project grouped by tag: a session is listed under each of
+/// its tags, and sessions without tags under `UNTAGGED`. Each bucket keeps the
+/// session list's newest-first order.
+pub fn sessions_grouped_by_tag(
+    source: &str,
+    project_id: &str,
+) -> Result<HashMap<String, Vec<SessionIndexEntry>>, String> {
+    let mut groups: HashMap<String, Vec<SessionIndexEntry>> = HashMap::new();
+    for session in list_sessions(source, Some(project_id))? {
+        let tags = session.tags.clone().unwrap_or_default();
+        if tags.is_empty() {
+            groups
+                .entry(UNTAGGED.to_string())
+                .or_default()
+                .push(session);
+            continue;
+        }
+        for tag in tags {
+            groups.entry(tag).or_default().push(session.clone());
+        }
+    }
+    Ok(groups)
+}
+
+/// Sessions of one project, or of all projects when `project_id` is None,
+/// with user metadata merged in
+pub(crate) fn list_sessions(
+    source: &str,
+    project_id: Option<&str>,
+) -> Result<Vec<SessionIndexEntry>, String> {
+    let sessions = match (source, project_id) {
+        ("claude", Some(pid)) => {
+            let mut sessions = claude::get_sessions(pid, None)?;
+            metadata::merge_session_meta("claude", pid, &mut sessions);
+            sessions
+        }
+        ("claude", None) => claude::list_project_ids()
+            .into_iter()
+            .flat_map(|pid| {
+                let mut sessions = claude::get_sessions(&pid, None).unwrap_or_default();
+                metadata::merge_session_meta("claude", &pid, &mut sessions);
+                sessions
+            })
+            .collect(),
+        ("codex", pid) => {
+            let mut sessions = match pid {
+                Some(cwd) => codex::get_sessions(cwd)?,
+                None => codex::list_all_sessions()?,
+            };
+            metadata::merge_session_meta("codex", "", &mut sessions);
+            sessions
+        }
+        _ => return Err(format!("Unknown source: {}", source)),
+    };
+    Ok(sessions)
+}
+
+/// Sessions whose file changed after `since_epoch` (Unix seconds), with user
+/// metadata merged in, so clients can poll for updates without reloading
+/// every session. Unchanged files are never parsed.
+pub fn sessions_modified_since(
+    source: &str,
+    project_id: Option<&str>,
+    since_epoch: i64,
+) -> Result<Vec<SessionIndexEntry>, String> {
+    let sessions = match (source, project_id) {
+        ("claude", Some(pid)) => {
+            let mut sessions = claude::sessions_modified_since(pid, since_epoch)?;
+            metadata::merge_session_meta("claude", pid, &mut sessions);
+            sessions
+        }
+        ("claude", None) => {
+            let mut sessions: Vec<SessionIndexEntry> = claude::list_project_ids()
+                .into_iter()
+                .flat_map(|pid| {
+                    let mut sessions =
+                        claude::sessions_modified_since(&pid, since_epoch).unwrap_or_default();
+                    metadata::merge_session_meta("claude", &pid, &mut sessions);
+                    sessions
+                })
+                .collect();
+            sessions.sort_by(|a, b| b.modified.cmp(&a.modified));
+            sessions
+        }
+        ("codex", pid) => {
+            let mut sessions = codex::sessions_modified_since(pid, since_epoch);
+            metadata::merge_session_meta("codex", "", &mut sessions);
+            sessions
+        }
+        _ => return Err(format!("Unknown source: {}", source)),
+    };
+    Ok(sessions)
+}
+
 fn get_claude_stats() -> Result<TokenUsageSummary, String> {
     let path = get_stats_cache_path().ok_or("Could not find stats cache path")?;
 
@@ -89,3 +403,273 @@ fn get_claude_stats() -> Result<TokenUsageSummary, String> {
         message_count: total_messages,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        claude_assistant, claude_user, codex_meta, set_mtime, with_timestamp, TestHome,
+    };
+
+    /// Write a one-message session in `project` started at noon UTC on `day`
+    fn session_on(home: &TestHome, project: &str, session_id: &str, day: &str) {
+        let timestamp = format!("{}T12:00:00Z", day);
+        let record = with_timestamp(&claude_user(session_id, "hello"), &timestamp);
+        home.claude_session(project, session_id, &[record]);
+    }
+
+    fn day_counts(days: &[(&str, usize)]) -> HashMap<String, usize> {
+        days.iter().map(|(d, n)| (d.to_string(), *n)).collect()
+    }
+
+    fn local_day(day: &str) -> String {
+        let noon = chrono::DateTime::parse_from_rfc3339(&format!("{}T12:00:00Z", day)).unwrap();
+        noon.with_timezone(&chrono::Local)
+            .format("%Y-%m-%d")
+            .to_string()
+    }
+
+    #[test]
+    fn activity_buckets_sessions_by_day() {
+        let home = TestHome::new();
+        session_on(&home, "-home-user-alpha", "s1", "2025-03-01");
+        session_on(&home, "-home-user-alpha", "s2", "2025-03-01");
+        session_on(&home, "-home-user-alpha", "s3", "2025-03-03");
+        session_on(&home, "-home-user-beta", "s4", "2025-03-03");
+        session_on(&home, "-home-user-beta", "s5", "2025-03-07");
+        let (d1, d3, d7) = (
+            local_day("2025-03-01"),
+            local_day("2025-03-03"),
+            local_day("2025-03-07"),
+        );
+
+        let all = activity_by_day("claude", None, None, None).unwrap();
+        assert_eq!(all, day_counts(&[(&d1, 2), (&d3, 2), (&d7, 1)]));
+
+        let alpha = activity_by_day("claude", Some("-home-user-alpha"), None, None).unwrap();
+        assert_eq!(alpha, day_counts(&[(&d1, 2), (&d3, 1)]));
+
+        let ranged = activity_by_day("claude", None, Some(&d3), Some(&d3)).unwrap();
+        assert_eq!(ranged, day_counts(&[(&d3, 2)]));
+    }
+
+    #[test]
+    fn activity_rejects_malformed_bounds() {
+        let _home = TestHome::new();
+        let err = activity_by_day("claude", None, Some("03/01/2025"), None).unwrap_err();
+        assert!(err.starts_with("Invalid date"), "{}", err);
+    }
+
+    const EPOCH: u64 = 1_700_000_000;
+
+    fn ids(sessions: &[SessionIndexEntry]) -> Vec<&str> {
+        let mut ids: Vec<&str> = sessions.iter().map(|s| s.session_id.as_str()).collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn only_sessions_touched_after_since_are_returned() {
+        let home = TestHome::new();
+        let project = "-home-user-alpha";
+        let quiet = home.claude_session(project, "quiet", &[claude_user("u1", "hi")]);
+        let busy = home.claude_session(project, "busy", &[claude_user("u2", "hi")]);
+        let other = home.claude_session("-home-user-beta", "other", &[claude_user("u3", "hi")]);
+        for path in [&quiet, &busy, &other] {
+            set_mtime(path, EPOCH);
+        }
+        let since = (EPOCH + 60) as i64;
+
+        assert!(sessions_modified_since("claude", Some(project), since)
+            .unwrap()
+            .is_empty());
+
+        set_mtime(&busy, EPOCH + 120);
+        let changed = sessions_modified_since("claude", Some(project), since).unwrap();
+        assert_eq!(ids(&changed), ["busy"]);
+        assert_eq!(
+            ids(&sessions_modified_since("claude", None, since).unwrap()),
+            ["busy"]
+        );
+
+        set_mtime(&other, EPOCH + 120);
+        assert_eq!(
+            ids(&sessions_modified_since("claude", None, since).unwrap()),
+            ["busy", "other"]
+        );
+    }
+
+    #[test]
+    fn codex_sessions_touched_after_since_are_returned() {
+        let home = TestHome::new();
+        let quiet = home.codex_session("quiet", &[codex_meta("quiet", "/work/a")]);
+        let busy = home.codex_session("busy", &[codex_meta("busy", "/work/b")]);
+        set_mtime(&quiet, EPOCH);
+        set_mtime(&busy, EPOCH + 120);
+        let since = (EPOCH + 60) as i64;
+
+        assert_eq!(
+            ids(&sessions_modified_since("codex", None, since).unwrap()),
+            ["busy"]
+        );
+        assert!(sessions_modified_since("codex", Some("/work/a"), since)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn sessions_are_listed_under_each_of_their_tags() {
+        let home = TestHome::new();
+        let project = "-home-user-alpha";
+        for (session_id, tags) in [
+            ("s1", vec!["rust", "ci"]),
+            ("s2", vec!["rust"]),
+            ("s3", vec!["ci", "docs"]),
+            ("s4", vec![]),
+            ("s5", vec![]),
+        ] {
+            home.claude_session(project, session_id, &[claude_user(session_id, "hi")]);
+            let tags = tags.into_iter().map(String::from).collect();
+            crate::metadata::update_session_meta("claude", project, session_id, None, tags, None)
+                .unwrap();
+        }
+
+        let groups = sessions_grouped_by_tag("claude", project).unwrap();
+        let mut tags: Vec<&str> = groups.keys().map(String::as_str).collect();
+        tags.sort();
+        assert_eq!(tags, ["ci", "docs", "rust", UNTAGGED]);
+        assert_eq!(ids(&groups["rust"]), ["s1", "s2"]);
+        assert_eq!(ids(&groups["ci"]), ["s1", "s3"]);
+        assert_eq!(ids(&groups["docs"]), ["s3"]);
+        assert_eq!(ids(&groups[UNTAGGED]), ["s4", "s5"]);
+    }
+
+    fn counts(models: &[(&str, usize)]) -> Vec<(String, usize)> {
+        models.iter().map(|(m, n)| (m.to_string(), *n)).collect()
+    }
+
+    #[test]
+    fn models_are_counted_once_per_session() {
+        let home = TestHome::new();
+        let (sonnet, opus) = ("claude-sonnet-4-6", "claude-opus-4-1");
+        home.claude_session(
+            "-home-user-alpha",
+            "s1",
+            &[
+                claude_user("u1", "hi"),
+                claude_assistant("a1", "one", sonnet),
+                claude_assistant("a2", "two", sonnet),
+                claude_assistant("a3", "three", opus),
+            ],
+        );
+        home.claude_session(
+            "-home-user-alpha",
+            "s2",
+            &[
+                claude_user("u2", "hi"),
+                claude_assistant("a4", "hey", sonnet),
+            ],
+        );
+        home.claude_session(
+            "-home-user-beta",
+            "s3",
+            &[claude_user("u3", "hi"), claude_assistant("a5", "yo", opus)],
+        );
+
+        assert_eq!(
+            models_used("claude", Some("-home-user-alpha")).unwrap(),
+            counts(&[(sonnet, 2), (opus, 1)])
+        );
+        assert_eq!(
+            models_used("claude", None).unwrap(),
+            counts(&[(opus, 2), (sonnet, 2)])
+        );
+    }
+
+    #[test]
+    fn ages_fall_into_calendar_buckets() {
+        let date = |d: &str| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap();
+        // A Wednesday, so the week started on Monday the 17th
+        let today = date("2025-03-19");
+        let bucket = |d: &str| AGE_BUCKETS[age_bucket(date(d), today)];
+
+        assert_eq!(bucket("2025-03-19"), "today");
+        assert_eq!(bucket("2025-03-18"), "this week");
+        assert_eq!(bucket("2025-03-17"), "this week");
+        assert_eq!(bucket("2025-03-16"), "this month");
+        assert_eq!(bucket("2025-03-01"), "this month");
+        assert_eq!(bucket("2025-02-28"), "older");
+        assert_eq!(bucket("2024-03-19"), "older");
+    }
+
+    #[test]
+    fn histogram_counts_sessions_by_mtime() {
+        let home = TestHome::new();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let year_ago = now - 400 * 24 * 60 * 60;
+        for (project, session_id, mtime) in [
+            ("-home-user-alpha", "fresh", now),
+            ("-home-user-alpha", "stale", year_ago),
+            ("-home-user-alpha", "ancient", year_ago - 60),
+            ("-home-user-beta", "fresh-beta", now),
+        ] {
+            let path = home.claude_session(project, session_id, &[claude_user("u1", "hi")]);
+            set_mtime(&path, mtime);
+        }
+        let histogram = |today: usize, older: usize| {
+            AGE_BUCKETS
+                .iter()
+                .zip([today, 0, 0, older])
+                .map(|(name, n)| (name.to_string(), n))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            session_age_histogram("claude", Some("-home-user-alpha")).unwrap(),
+            histogram(1, 2)
+        );
+        assert_eq!(
+            session_age_histogram("claude", None).unwrap(),
+            histogram(2, 2)
+        );
+    }
+
+    #[test]
+    fn longest_turn_picks_the_biggest_assistant_reply() {
+        let home = TestHome::new();
+        let model = "claude-sonnet-4-6";
+        let path = home.claude_session(
+            "-home-user-project",
+            "s1",
+            &[
+                // The user's message is longer, but only replies count
+                claude_user("u1", &"q".repeat(500)),
+                claude_assistant("a1", &"a".repeat(40), model),
+                claude_user("u2", "more"),
+                claude_assistant("a2", &"b".repeat(101), model),
+                claude_assistant("a3", &"c".repeat(101), model),
+            ],
+        );
+
+        let turn = longest_turn(path.to_str().unwrap(), "claude").unwrap();
+        assert_eq!(turn.index, 3);
+        assert_eq!(turn.role, "assistant");
+        assert_eq!(turn.chars, 101);
+        assert_eq!(turn.estimated_tokens, 26);
+    }
+
+    #[test]
+    fn longest_turn_of_an_empty_session_is_an_error() {
+        let home = TestHome::new();
+        let empty = home.claude_session("-home-user-project", "empty", &[]);
+        let err = longest_turn(empty.to_str().unwrap(), "claude").unwrap_err();
+        assert_eq!(err, "Empty session: no messages");
+
+        let questions = home.claude_session("-home-user-project", "q", &[claude_user("u1", "hi")]);
+        let err = longest_turn(questions.to_str().unwrap(), "claude").unwrap_err();
+        assert_eq!(err, "Session has no assistant messages");
+    }
+}
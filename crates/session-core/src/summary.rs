@@ -0,0 +1,180 @@
+use std::path::Path;
+
+use crate::cli_config;
+use crate::metadata;
+use crate::models::message::{DisplayContentBlock, DisplayMessage};
+use crate::provider::{claude, codex};
+use crate::quick_chat::{self, ChatMsg};
+use crate::session_ops::session_key;
+
+/// Transcript budget sent to the model (~30k tokens), well inside the context window
+const MAX_TRANSCRIPT_CHARS: usize = 120_000;
+
+const SUMMARY_PROMPT: &str = "Summarize the following coding session in one paragraph. \
+Describe what the user wanted, what was done, and how it ended. \
+Reply with the summary only.";
+
+/// Summarize a session with the chat API and cache the result as the session's note.
+///
+/// A previously cached note is returned without calling the API. The `model`
+/// is a full API model ID (e.g. "claude-sonnet-4-6") or a CLI alias such as "sonnet".
+pub async fn summarize_session(
+    file_path: &str,
+    source: &str,
+    model: &str,
+) -> Result<String, String> {
+    let path = Path::new(file_path);
+    if !path.exists() {
+        return Err(format!("File not found: {}", file_path));
+    }
+
+    let (project_id, session_id) = session_key(path, source)?;
+    let meta = metadata::load_metadata(source, &project_id);
+    if let Some(note) = meta.sessions.get(&session_id).and_then(|s| s.note.clone()) {
+        return Ok(note);
+    }
+
+    let (api_key, _) = cli_config::get_credentials("claude");
+    if api_key.is_empty() {
+        return Err(
+            "Cannot summarize offline: no API key found for Claude. Configure your CLI or set ANTHROPIC_API_KEY.".to_string(),
+        );
+    }
+
+    let messages = match source {
+        "claude" => claude::parse_all_messages(path)?,
+        "codex" => codex::parse_all_messages(path)?,
+        _ => return Err(format!("Unknown source: {}", source)),
+    };
+    let transcript = build_transcript(&messages);
+    if transcript.is_empty() {
+        return Err("Session has no text to summarize".to_string());
+    }
+
+    let prompt = format!(
+        "{}\n\n<transcript>\n{}\n</transcript>",
+        SUMMARY_PROMPT,
+        truncate_middle(&transcript, MAX_TRANSCRIPT_CHARS)
+    );
+    let summary = quick_chat::chat(
+        source,
+        vec![ChatMsg {
+            role: "user".to_string(),
+            content: prompt,
+            blocks: None,
+        }],
+        model,
+    )
+    .await?;
+
+    let summary = summary.trim().to_string();
+    if summary.is_empty() {
+        return Err("Model returned an empty summary".to_string());
+    }
+
+    metadata::set_session_note(source, &project_id, &session_id, Some(summary.clone()))?;
+    Ok(summary)
+}
+
+/// Plain-text transcript of the user/assistant text (tool traffic is omitted)
+fn build_transcript(messages: &[DisplayMessage]) -> String {
+    let mut out = String::new();
+    for msg in messages {
+        let text: Vec<&str> = msg
+            .content
+            .iter()
+            .filter_map(|b| match b {
+                DisplayContentBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        if text.is_empty() {
+            continue;
+        }
+        out.push_str(msg.role.as_str());
+        out.push_str(": ");
+        out.push_str(&text.join("\n"));
+        out.push_str("\n\n");
+    }
+    out.trim_end().to_string()
+}
+
+/// Keep the opening quarter and the closing part of an over-long transcript
+fn truncate_middle(s: &str, max_chars: usize) -> String {
+    let total = s.chars().count();
+    if total <= max_chars {
+        return s.to_string();
+    }
+
+    let head_len = max_chars / 4;
+    let tail_len = max_chars - head_len;
+    let head: String = s.chars().take(head_len).collect();
+    let tail: String = s.chars().skip(total - tail_len).collect();
+    format!(
+        "{}\n\n[... {} characters omitted ...]\n\n{}",
+        head,
+        total - max_chars,
+        tail
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{claude_assistant, claude_user, TestHome};
+
+    const PROJECT: &str = "-home-user-project";
+
+    #[test]
+    fn short_transcripts_are_kept_whole() {
+        assert_eq!(truncate_middle("hello", 5), "hello");
+        assert_eq!(truncate_middle("", 0), "");
+    }
+
+    #[test]
+    fn long_transcripts_keep_the_head_and_tail_by_characters() {
+        // 13 multibyte characters cut to 8: two from the start, six from the end
+        assert_eq!(
+            truncate_middle("日本語のテキストを要約する", 8),
+            "日本\n\n[... 5 characters omitted ...]\n\nトを要約する"
+        );
+    }
+
+    fn session(home: &TestHome) -> String {
+        home.claude_session(
+            PROJECT,
+            "s1",
+            &[
+                claude_user("u1", "fix the build"),
+                claude_assistant("a1", "fixed", "claude-sonnet-4-6"),
+            ],
+        )
+        .to_string_lossy()
+        .into_owned()
+    }
+
+    #[tokio::test]
+    async fn summarizing_without_credentials_fails_before_any_request() {
+        let home = TestHome::new();
+        let path = session(&home);
+
+        let err = summarize_session(&path, "claude", "sonnet")
+            .await
+            .unwrap_err();
+        assert!(err.starts_with("Cannot summarize offline"), "{}", err);
+        let meta = metadata::load_metadata("claude", PROJECT);
+        assert!(!meta.sessions.contains_key("s1"));
+    }
+
+    #[tokio::test]
+    async fn a_cached_note_is_returned_without_credentials() {
+        let home = TestHome::new();
+        let path = session(&home);
+        metadata::set_session_note("claude", PROJECT, "s1", Some("cached".to_string())).unwrap();
+
+        assert_eq!(
+            summarize_session(&path, "claude", "sonnet").await.unwrap(),
+            "cached"
+        );
+    }
+}
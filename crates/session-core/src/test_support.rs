@@ -0,0 +1,343 @@
+use parking_lot::{Mutex, MutexGuard};
+use std::ffi::OsString;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{data_dir, model_list, project_info, session_cache};
+
+/// Serializes tests that repoint process-wide state (HOME, env vars, caches)
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+/// Environment variables a `TestHome` clears and restores
+const ISOLATED_VARS: &[&str] = &[
+    "HOME",
+    data_dir::DATA_DIR_ENV,
+    "ANTHROPIC_API_KEY",
+    "ANTHROPIC_AUTH_TOKEN",
+    "ANTHROPIC_BASE_URL",
+    "ANTHROPIC_MODEL",
+];
+
+/// A fresh temporary directory, removed when dropped
+pub fn temp_dir() -> tempfile::TempDir {
+    tempfile::tempdir().expect("create temp dir")
+}
+
+/// A temporary home directory for the duration of a test.
+///
+/// HOME points at it, so `~/.claude`, `~/.codex` and the viewer's data files
+/// all resolve inside it, and the Anthropic env vars are cleared. Tests
+/// holding one run one at a time; the previous environment comes back and
+/// the in-memory caches are emptied when it is dropped.
+pub struct TestHome {
+    dir: tempfile::TempDir,
+    saved: Vec<(&'static str, Option<OsString>)>,
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl Default for TestHome {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestHome {
+    pub fn new() -> Self {
+        let guard = ENV_LOCK.lock();
+        let dir = temp_dir();
+        let saved = ISOLATED_VARS
+            .iter()
+            .map(|name| (*name, std::env::var_os(name)))
+            .collect();
+        for name in ISOLATED_VARS {
+            std::env::remove_var(name);
+        }
+        std::env::set_var("HOME", dir.path());
+        clear_caches();
+        Self {
+            dir,
+            saved,
+            _guard: guard,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// `~/.claude/projects/<encoded_name>`, created
+    pub fn claude_project(&self, encoded_name: &str) -> PathBuf {
+        let dir = self.path().join(".claude/projects").join(encoded_name);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Write `~/.claude/projects/<encoded_name>/<session_id>.jsonl`
+    pub fn claude_session(
+        &self,
+        encoded_name: &str,
+        session_id: &str,
+        lines: &[String],
+    ) -> PathBuf {
+        write_lines(
+            &self
+                .claude_project(encoded_name)
+                .join(format!("{}.jsonl", session_id)),
+            lines,
+        )
+    }
+
+    /// Write a Codex rollout file for `session_id` under `~/.codex/sessions`
+    pub fn codex_session(&self, session_id: &str, lines: &[String]) -> PathBuf {
+        let dir = self.path().join(".codex/sessions/2025/01/15");
+        fs::create_dir_all(&dir).unwrap();
+        write_lines(
+            &dir.join(format!("rollout-2025-01-15T10-00-00-{}.jsonl", session_id)),
+            lines,
+        )
+    }
+}
+
+impl Drop for TestHome {
+    fn drop(&mut self) {
+        for (name, value) in &self.saved {
+            match value {
+                Some(v) => std::env::set_var(name, v),
+                None => std::env::remove_var(name),
+            }
+        }
+        clear_caches();
+    }
+}
+
+fn clear_caches() {
+    model_list::clear_cache();
+    project_info::clear_cache();
+    session_cache::clear_cache();
+}
+
+/// Write `lines` as a JSONL file, creating parent directories
+pub fn write_lines(path: &Path, lines: &[String]) -> PathBuf {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    let mut content = lines.join("\n");
+    content.push('\n');
+    fs::write(path, content).unwrap();
+    path.to_path_buf()
+}
+
+/// A Claude `user` record with plain-text content
+pub fn claude_user(uuid: &str, text: &str) -> String {
+    serde_json::json!({
+        "type": "user",
+        "uuid": uuid,
+        "timestamp": "2025-01-15T10:00:00Z",
+        "cwd": "/home/user/project",
+        "message": { "role": "user", "content": text },
+    })
+    .to_string()
+}
+
+/// A Claude `assistant` record with one text block
+pub fn claude_assistant(uuid: &str, text: &str, model: &str) -> String {
+    serde_json::json!({
+        "type": "assistant",
+        "uuid": uuid,
+        "timestamp": "2025-01-15T10:00:05Z",
+        "message": {
+            "role": "assistant",
+            "model": model,
+            "content": [{ "type": "text", "text": text }],
+            "usage": { "input_tokens": 100, "output_tokens": 50 },
+        },
+    })
+    .to_string()
+}
+
+/// Set a file's modification time to `epoch_secs` (Unix seconds)
+pub fn set_mtime(path: &Path, epoch_secs: u64) {
+    let time = std::time::UNIX_EPOCH + std::time::Duration::from_secs(epoch_secs);
+    fs::File::options()
+        .write(true)
+        .open(path)
+        .and_then(|f| f.set_modified(time))
+        .unwrap();
+}
+
+/// `record` with its `timestamp` replaced
+pub fn with_timestamp(record: &str, timestamp: &str) -> String {
+    let mut record: serde_json::Value = serde_json::from_str(record).unwrap();
+    record["timestamp"] = timestamp.into();
+    record.to_string()
+}
+
+/// The `session_meta` record that opens a Codex rollout file
+pub fn codex_meta(session_id: &str, cwd: &str) -> String {
+    serde_json::json!({
+        "timestamp": "2025-01-15T10:00:00Z",
+        "type": "session_meta",
+        "payload": { "id": session_id, "cwd": cwd, "cli_version": "0.1.0" },
+    })
+    .to_string()
+}
+
+/// A Codex `response_item` message with one text block
+pub fn codex_message(role: &str, text: &str) -> String {
+    let block_type = if role == "user" {
+        "input_text"
+    } else {
+        "output_text"
+    };
+    serde_json::json!({
+        "timestamp": "2025-01-15T10:00:01Z",
+        "type": "response_item",
+        "payload": {
+            "type": "message",
+            "role": role,
+            "content": [{ "type": block_type, "text": text }],
+        },
+    })
+    .to_string()
+}
+
+/// One canned reply for `MockServer`: the raw bytes written back, and how
+/// long the server waits after reading the request before writing them
+pub struct MockResponse(String, Duration);
+
+impl MockResponse {
+    /// A complete response with a JSON body and the given extra headers
+    pub fn json(status: u16, body: &str, headers: &[(&str, &str)]) -> Self {
+        let mut head = format!(
+            "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+            status,
+            body.len()
+        );
+        for (name, value) in headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        Self(format!("{}\r\n{}", head, body), Duration::ZERO)
+    }
+
+    /// A `text/event-stream` reply carrying `events` in full
+    pub fn sse(events: &str) -> Self {
+        Self::event_stream(events, events.len())
+    }
+
+    /// A `text/event-stream` reply that announces more bytes than it sends, so
+    /// the client sees the connection drop partway through
+    pub fn sse_cut_off(events: &str) -> Self {
+        Self::event_stream(events, events.len() + 1024)
+    }
+
+    /// This reply, sent only after `delay`, e.g. to exercise client timeouts
+    pub fn delayed(self, delay: Duration) -> Self {
+        Self(self.0, delay)
+    }
+
+    fn event_stream(events: &str, content_length: usize) -> Self {
+        let raw = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            content_length, events
+        );
+        Self(raw, Duration::ZERO)
+    }
+}
+
+/// One SSE `data:` line per event, each followed by a blank line
+pub fn sse_events(events: &[serde_json::Value]) -> String {
+    events
+        .iter()
+        .map(|event| format!("data: {}\n\n", event))
+        .collect()
+}
+
+/// A request as `MockServer` received it
+pub struct MockRequest {
+    /// Request target, e.g. `/v1/models?after_id=...`
+    pub path: String,
+    /// Header names lowercased
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl MockRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// A local HTTP server that answers one connection per canned response, in
+/// order, and records what each request carried
+pub struct MockServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+}
+
+impl MockServer {
+    pub fn start(responses: Vec<MockResponse>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        std::thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = match listener.accept() {
+                    Ok(conn) => conn,
+                    Err(_) => return,
+                };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                seen.lock().push(read_request(&mut reader));
+                std::thread::sleep(response.1);
+                let _ = stream.write_all(response.0.as_bytes());
+            }
+        });
+        Self { url, requests }
+    }
+
+    /// Requests received so far, oldest first
+    pub fn requests(&self) -> Vec<MockRequest> {
+        std::mem::take(&mut *self.requests.lock())
+    }
+}
+
+fn read_request(reader: &mut impl BufRead) -> MockRequest {
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    let path = line
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or_default()
+        .to_string();
+    let mut headers = Vec::new();
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).unwrap();
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+        }
+    }
+    let length = headers
+        .iter()
+        .find(|(name, _)| name == "content-length")
+        .and_then(|(_, value)| value.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).unwrap();
+    MockRequest {
+        path,
+        headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    }
+}
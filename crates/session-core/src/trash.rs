@@ -0,0 +1,372 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::data_dir;
+use crate::metadata;
+use crate::parser::format::is_session_file;
+use crate::provider::codex;
+use crate::settings;
+
+/// A session file moved to the trash by `move_to_trash`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashEntry {
+    pub id: String,
+    pub source: String,
+    pub project_id: String,
+    pub session_id: String,
+    pub original_path: String,
+    /// Name of the file in the trash directory while trashed. Kept relative so
+    /// entries survive moving the data directory; older indexes stored the
+    /// absolute `trashedPath`, whose file name is used the same way.
+    #[serde(alias = "trashedPath")]
+    pub trashed_file: String,
+    /// RFC 3339
+    pub deleted_at: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TrashIndex {
+    version: u32,
+    entries: Vec<TrashEntry>,
+}
+
+impl TrashEntry {
+    /// Current location of the trashed file, under `trash_dir()`
+    pub fn trashed_path(&self) -> Result<PathBuf, String> {
+        let name = Path::new(&self.trashed_file)
+            .file_name()
+            .ok_or_else(|| format!("Invalid trashed file: {}", self.trashed_file))?;
+        Ok(trash_dir()?.join(name))
+    }
+}
+
+impl Default for TrashIndex {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            entries: Vec::new(),
+        }
+    }
+}
+
+/// `.session-viewer/trash` in the data directory: trashed files plus `index.json`
+fn trash_dir() -> Result<PathBuf, String> {
+    Ok(data_dir::data_dir()?.join(".session-viewer").join("trash"))
+}
+
+fn load_index() -> TrashIndex {
+    let path = match trash_dir() {
+        Ok(d) => d.join("index.json"),
+        Err(_) => return TrashIndex::default(),
+    };
+    fs::read_to_string(&path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_index(index: &TrashIndex) -> Result<(), String> {
+    let dir = trash_dir()?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create trash directory: {}", e))?;
+    let path = dir.join("index.json");
+    let json = serde_json::to_string_pretty(index)
+        .map_err(|e| format!("Failed to serialize trash index: {}", e))?;
+
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, &json).map_err(|e| format!("Failed to write trash index tmp: {}", e))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to rename trash index: {}", e))?;
+    Ok(())
+}
+
+/// Rename, falling back to copy + delete when the trash is on another device
+fn move_file(from: &Path, to: &Path) -> Result<(), String> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to).map_err(|e| format!("Failed to copy {}: {}", from.display(), e))?;
+    fs::remove_file(from).map_err(|e| format!("Failed to remove {}: {}", from.display(), e))
+}
+
+/// Move a session file into the trash. Its metadata (alias, tags, ...) is kept
+/// so a restore brings it back as it was; it is dropped when the entry is purged.
+pub fn move_to_trash(
+    file_path: &str,
+    source: &str,
+    project_id: &str,
+    session_id: &str,
+) -> Result<TrashEntry, String> {
+    let path = Path::new(file_path);
+    let size = fs::metadata(path)
+        .map_err(|_| format!("File not found: {}", file_path))?
+        .len();
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or("Invalid session file name")?;
+
+    let dir = trash_dir()?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create trash directory: {}", e))?;
+    let id = uuid::Uuid::new_v4().to_string();
+    let trashed_file = format!("{}-{}", id, file_name);
+    let trashed_path = dir.join(&trashed_file);
+    move_file(path, &trashed_path)?;
+
+    let entry = TrashEntry {
+        id,
+        source: source.to_string(),
+        project_id: project_id.to_string(),
+        session_id: session_id.to_string(),
+        original_path: file_path.to_string(),
+        trashed_file,
+        deleted_at: chrono::Utc::now().to_rfc3339(),
+        size,
+    };
+    let mut index = load_index();
+    index.entries.push(entry.clone());
+    if let Err(e) = save_index(&index) {
+        // Put the file back rather than leave it unlisted in the trash
+        let _ = move_file(&trashed_path, path);
+        return Err(e);
+    }
+    Ok(entry)
+}
+
+/// Trashed sessions of one source, most recently deleted first. A missing or
+/// unreadable trash is empty.
+pub fn list_trash(source: &str) -> Result<Vec<TrashEntry>, String> {
+    if source != "claude" && source != "codex" {
+        return Err(format!("Unknown source: {}", source));
+    }
+    let mut entries: Vec<TrashEntry> = load_index()
+        .entries
+        .into_iter()
+        .filter(|e| e.source == source)
+        .collect();
+    entries.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+    Ok(entries)
+}
+
+/// Move a trashed session back to its original path; returns that path.
+/// Refuses to overwrite a file that has since appeared there.
+pub fn restore_session(id: &str) -> Result<String, String> {
+    let mut index = load_index();
+    let pos = index
+        .entries
+        .iter()
+        .position(|e| e.id == id)
+        .ok_or_else(|| format!("Trash entry not found: {}", id))?;
+    let entry = &index.entries[pos];
+    let original = Path::new(&entry.original_path);
+    if original.exists() {
+        return Err(format!("{} already exists", entry.original_path));
+    }
+    if let Some(parent) = original.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to recreate {}: {}", parent.display(), e))?;
+    }
+    move_file(&entry.trashed_path()?, original)?;
+
+    let entry = index.entries.remove(pos);
+    save_index(&index)?;
+    Ok(entry.original_path)
+}
+
+/// Whether `path` is a session file named after `session_id` (Codex rollout
+/// files end in the id: `rollout-<timestamp>-<id>.jsonl`)
+fn is_file_of_session(path: &Path, source: &str, session_id: &str) -> bool {
+    let stem = match path.file_stem().and_then(|s| s.to_str()) {
+        Some(s) => s,
+        None => return false,
+    };
+    is_session_file(path, source)
+        && (stem == session_id || stem.ends_with(&format!("-{}", session_id)))
+}
+
+/// Whether a session with the entry's id is live in its project again
+/// (restored, or recreated by the CLI), so its metadata is in use
+fn is_live(entry: &TrashEntry, codex_files: &[PathBuf]) -> bool {
+    let original = Path::new(&entry.original_path);
+    if original.exists() {
+        return true;
+    }
+    match entry.source.as_str() {
+        "claude" => original
+            .parent()
+            .and_then(|dir| fs::read_dir(dir).ok())
+            .is_some_and(|files| {
+                files
+                    .flatten()
+                    .any(|f| is_file_of_session(&f.path(), "claude", &entry.session_id))
+            }),
+        "codex" => codex_files
+            .iter()
+            .any(|p| is_file_of_session(p, "codex", &entry.session_id)),
+        _ => false,
+    }
+}
+
+/// Delete trashed files for good for every entry matching `keep == false`.
+/// Their metadata goes too, unless the session is live again or another
+/// trash entry still holds it. Returns how many were purged.
+fn purge_where(keep: impl Fn(&TrashEntry) -> bool) -> Result<usize, String> {
+    let index = load_index();
+    let (kept, purged): (Vec<TrashEntry>, Vec<TrashEntry>) =
+        index.entries.into_iter().partition(keep);
+    if purged.is_empty() {
+        return Ok(0);
+    }
+
+    let codex_files = if purged.iter().any(|e| e.source == "codex") {
+        codex::scan_all_session_files()
+    } else {
+        Vec::new()
+    };
+    let same_session = |a: &TrashEntry, b: &TrashEntry| {
+        a.source == b.source && a.project_id == b.project_id && a.session_id == b.session_id
+    };
+    for entry in &purged {
+        if let Ok(path) = entry.trashed_path() {
+            let _ = fs::remove_file(path);
+        }
+        if kept.iter().any(|k| same_session(k, entry)) || is_live(entry, &codex_files) {
+            continue;
+        }
+        let _ = metadata::remove_session_meta(&entry.source, &entry.project_id, &entry.session_id);
+    }
+    save_index(&TrashIndex {
+        version: 1,
+        entries: kept,
+    })?;
+    Ok(purged.len())
+}
+
+/// Permanently delete everything in the trash (`None`) or one source's entries
+pub fn empty_trash(source: Option<&str>) -> Result<usize, String> {
+    purge_where(|e| source.is_some_and(|s| e.source != s))
+}
+
+/// Purge entries older than the `trashRetentionDays` setting (default 30).
+/// Run once at startup.
+pub fn purge_expired_trash() -> Result<usize, String> {
+    let cutoff =
+        chrono::Utc::now() - chrono::Duration::days(settings::trash_retention_days() as i64);
+    purge_where(|e| {
+        chrono::DateTime::parse_from_rfc3339(&e.deleted_at)
+            .map(|t| t >= cutoff)
+            .unwrap_or(true)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{claude_user, temp_dir, TestHome};
+
+    const PROJECT: &str = "-home-user-project";
+
+    fn trash(home: &TestHome, session_id: &str) -> TrashEntry {
+        let path = home.claude_session(PROJECT, session_id, &[claude_user("u1", "hello")]);
+        move_to_trash(path.to_str().unwrap(), "claude", PROJECT, session_id).unwrap()
+    }
+
+    /// Backdate a trash entry's deletion time by `days`
+    fn backdate(id: &str, days: i64) {
+        let mut index = load_index();
+        let entry = index.entries.iter_mut().find(|e| e.id == id).unwrap();
+        entry.deleted_at = (chrono::Utc::now() - chrono::Duration::days(days)).to_rfc3339();
+        save_index(&index).unwrap();
+    }
+
+    #[test]
+    fn trashed_sessions_are_listed_newest_first() {
+        let home = TestHome::new();
+        assert!(list_trash("claude").unwrap().is_empty());
+
+        let older = trash(&home, "older");
+        let newer = trash(&home, "newer");
+        backdate(&older.id, 1);
+
+        let listed = list_trash("claude").unwrap();
+        let ids: Vec<&str> = listed.iter().map(|e| e.session_id.as_str()).collect();
+        assert_eq!(ids, ["newer", "older"]);
+        assert_eq!(listed[0].size, newer.size);
+        assert!(listed[0].size > 0);
+        assert!(!Path::new(&newer.original_path).exists());
+        assert!(newer.trashed_path().unwrap().exists());
+        assert!(list_trash("codex").unwrap().is_empty());
+        assert!(list_trash("gemini").is_err());
+    }
+
+    #[test]
+    fn restore_puts_the_file_back() {
+        let home = TestHome::new();
+        let entry = trash(&home, "s1");
+
+        assert_eq!(restore_session(&entry.id).unwrap(), entry.original_path);
+        assert!(Path::new(&entry.original_path).exists());
+        assert!(list_trash("claude").unwrap().is_empty());
+        assert!(restore_session(&entry.id).is_err());
+    }
+
+    #[test]
+    fn restore_works_after_the_data_dir_moves() {
+        let home = TestHome::new();
+        let entry = trash(&home, "s1");
+        let dest = temp_dir();
+
+        data_dir::migrate_data_dir(dest.path().to_str().unwrap(), true).unwrap();
+        std::env::set_var(data_dir::DATA_DIR_ENV, dest.path());
+
+        assert!(entry.trashed_path().unwrap().starts_with(dest.path()));
+        assert_eq!(restore_session(&entry.id).unwrap(), entry.original_path);
+        assert!(Path::new(&entry.original_path).exists());
+    }
+
+    #[test]
+    fn entries_with_an_absolute_trashed_path_still_resolve() {
+        let home = TestHome::new();
+        let entry = trash(&home, "s1");
+        let mut index = load_index();
+        let stored = trash_dir().unwrap().join(&entry.trashed_file);
+        let mut json = serde_json::to_value(&index.entries[0]).unwrap();
+        let obj = json.as_object_mut().unwrap();
+        obj.remove("trashedFile");
+        obj.insert("trashedPath".into(), stored.to_string_lossy().into());
+        index.entries[0] = serde_json::from_value(json).unwrap();
+        save_index(&index).unwrap();
+
+        assert_eq!(restore_session(&entry.id).unwrap(), entry.original_path);
+    }
+
+    #[test]
+    fn expired_entries_are_purged_with_their_metadata() {
+        let home = TestHome::new();
+        let expired = trash(&home, "expired");
+        let recent = trash(&home, "recent");
+        metadata::update_session_meta(
+            "claude",
+            PROJECT,
+            "expired",
+            Some("old".to_string()),
+            Vec::new(),
+            None,
+        )
+        .unwrap();
+        backdate(&expired.id, 31);
+
+        assert_eq!(purge_expired_trash().unwrap(), 1);
+        let listed = list_trash("claude").unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, recent.id);
+        assert!(!expired.trashed_path().unwrap().exists());
+        let meta = metadata::load_metadata("claude", PROJECT);
+        assert!(!meta.sessions.contains_key("expired"));
+
+        assert_eq!(empty_trash(None).unwrap(), 1);
+        assert!(list_trash("claude").unwrap().is_empty());
+    }
+}
@@ -16,7 +16,10 @@ use futures_util::StreamExt;
 use clap::Parser;
 use config::Config;
 use std::sync::Arc;
+use tower_http::compression::predicate::{NotForContentType, Predicate, SizeAbove};
+use tower_http::compression::CompressionLayer;
 use tower_http::cors::CorsLayer;
+use tracing_subscriber::filter::LevelFilter;
 
 #[derive(Clone)]
 struct AppToken(Option<String>);
@@ -52,10 +55,52 @@ async fn check_auth(
     Ok(next.run(request).await)
 }
 
+/// Liveness plus data directory status, so the UI can tell "Claude not found"
+/// apart from "zero sessions".
+async fn health_handler() -> Json<serde_json::Value> {
+    let claude =
+        tokio::task::spawn_blocking(session_core::provider::claude::claude_data_dir_status)
+            .await
+            .unwrap_or(session_core::models::project::DataDirStatus::Missing);
+    Json(serde_json::json!({ "ok": true, "claude": claude }))
+}
+
 async fn detect_cli_handler() -> Json<Vec<session_core::cli::CliInstallation>> {
     Json(session_core::cli::discover_installations())
 }
 
+/// SSE: one `installation` event per CLI binary as its version probe finishes,
+/// then `[DONE]` once all probes complete or the discovery timeout expires.
+async fn detect_cli_stream_handler() -> axum::response::Sse<
+    impl futures_util::Stream<Item = Result<axum::response::sse::Event, std::convert::Infallible>>,
+> {
+    let (tx, rx) = tokio::sync::mpsc::channel::<String>(16);
+
+    tokio::task::spawn_blocking(move || {
+        session_core::cli::discover_installations_streaming(
+            session_core::cli::DISCOVERY_TIMEOUT,
+            |installation| {
+                if let Ok(json) = serde_json::to_string(installation) {
+                    let _ = tx.blocking_send(json);
+                }
+            },
+        );
+        let _ = tx.blocking_send("[DONE]".to_string());
+    });
+
+    let stream = tokio_stream::wrappers::ReceiverStream::new(rx).map(|msg| {
+        if msg == "[DONE]" {
+            Ok(axum::response::sse::Event::default().data("[DONE]"))
+        } else {
+            Ok(axum::response::sse::Event::default()
+                .event("installation")
+                .data(msg))
+        }
+    });
+
+    axum::response::Sse::new(stream)
+}
+
 #[derive(serde::Deserialize)]
 struct CliConfigQuery {
     source: String,
@@ -69,12 +114,30 @@ async fn cli_config_handler(
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
 }
 
+async fn explain_config_handler(
+    axum::extract::Query(query): axum::extract::Query<CliConfigQuery>,
+) -> Result<Json<session_core::cli_config::ConfigExplanation>, (StatusCode, String)> {
+    session_core::cli_config::explain_config(&query.source)
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+async fn config_writable_handler(
+    axum::extract::Query(query): axum::extract::Query<CliConfigQuery>,
+) -> Result<Json<session_core::cli_config::ConfigWritability>, (StatusCode, String)> {
+    session_core::cli_config::config_writable(&query.source)
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
 #[derive(serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct QuickChatRequest {
     source: String,
     messages: Vec<session_core::quick_chat::ChatMsg>,
     model: String,
+    #[serde(default)]
+    options: session_core::quick_chat::ChatOptions,
 }
 
 async fn quick_chat_handler(
@@ -88,6 +151,7 @@ async fn quick_chat_handler(
             &req.source,
             req.messages,
             &req.model,
+            &req.options,
             |chunk| {
                 let _ = tx.try_send(chunk.to_string());
             },
@@ -117,6 +181,31 @@ async fn quick_chat_handler(
     axum::response::Sse::new(stream)
 }
 
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportChatTemplateRequest {
+    messages: Vec<session_core::quick_chat::ChatMsg>,
+    model: String,
+    #[serde(default)]
+    options: session_core::quick_chat::ChatOptions,
+}
+
+async fn export_chat_template_handler(Json(req): Json<ExportChatTemplateRequest>) -> String {
+    session_core::quick_chat::export_chat_template(&req.messages, &req.model, &req.options)
+}
+
+async fn import_chat_template_handler(
+    body: String,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let (messages, model, options) = session_core::quick_chat::import_chat_template(&body)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    Ok(Json(serde_json::json!({
+        "messages": messages,
+        "model": model,
+        "options": options,
+    })))
+}
+
 #[derive(serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct ListModelsRequest {
@@ -125,23 +214,173 @@ struct ListModelsRequest {
     api_key: String,
     #[serde(default)]
     base_url: String,
+    #[serde(default)]
+    provider_filter: Option<String>,
+    #[serde(default)]
+    min_context: Option<u32>,
 }
 
 async fn list_models_handler(
     Json(req): Json<ListModelsRequest>,
 ) -> Result<Json<Vec<session_core::model_list::ModelInfo>>, (StatusCode, String)> {
-    session_core::model_list::list_models(&req.source, &req.api_key, &req.base_url)
+    session_core::model_list::list_models(
+        &req.source,
+        &req.api_key,
+        &req.base_url,
+        req.provider_filter.as_deref(),
+        req.min_context,
+    )
+    .await
+    .map(Json)
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListModelsPageRequest {
+    source: String,
+    #[serde(default)]
+    api_key: String,
+    #[serde(default)]
+    base_url: String,
+    #[serde(default)]
+    cursor: Option<String>,
+}
+
+async fn list_models_page_handler(
+    Json(req): Json<ListModelsPageRequest>,
+) -> Result<Json<session_core::model_list::ModelPage>, (StatusCode, String)> {
+    session_core::model_list::list_models_page(
+        &req.source,
+        &req.api_key,
+        &req.base_url,
+        req.cursor.as_deref(),
+    )
+    .await
+    .map(Json)
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AuditModelsQuery {
+    source: String,
+    project_id: String,
+}
+
+async fn audit_models_handler(
+    axum::extract::Query(params): axum::extract::Query<AuditModelsQuery>,
+) -> Result<Json<Vec<session_core::model_list::ModelAudit>>, (StatusCode, String)> {
+    session_core::model_list::audit_session_models(&params.source, &params.project_id)
         .await
         .map(Json)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
 }
 
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModelAvailabilityQuery {
+    file_path: String,
+    source: String,
+}
+
+async fn model_availability_handler(
+    axum::extract::Query(params): axum::extract::Query<ModelAvailabilityQuery>,
+) -> Result<Json<session_core::model_list::ModelAvailability>, (StatusCode, String)> {
+    session_core::model_list::check_session_model_available(&params.file_path, &params.source)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))
+}
+
+/// Resolved server configuration for debugging a deployment. The token is
+/// never included, only whether auth is on.
+async fn effective_config_handler(
+    axum::Extension(config): axum::Extension<Config>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let resolved = tokio::task::spawn_blocking(move || {
+        use session_core::settings;
+
+        let path = |p: Option<std::path::PathBuf>| p.map(|p| p.to_string_lossy().to_string());
+        let (connect_timeout, timeout) = settings::diagnostic_timeouts();
+        serde_json::json!({
+            "server": {
+                "host": config.host,
+                "port": config.port,
+                "auth": config.token.is_some(),
+                "cors": "permissive",
+                "gzipMinBytes": MIN_COMPRESS_BYTES,
+            },
+            "dataDirs": {
+                "claudeProjects": path(session_core::parser::path_encoder::get_projects_dir()),
+                "codexSessions": path(session_core::provider::codex::get_sessions_dir()),
+                "viewerData": path(session_core::data_dir::data_dir().ok()),
+            },
+            "caches": {
+                "projectInfoTtlSecs": session_core::project_info::PROJECT_INFO_TTL.as_secs(),
+                "modelListTtlSecs": session_core::model_list::MODEL_CACHE_TTL.as_secs(),
+            },
+            "settings": {
+                "userAgent": settings::user_agent(),
+                "diagnosticConnectTimeoutSecs": connect_timeout.as_secs(),
+                "diagnosticTimeoutSecs": timeout.as_secs(),
+                "maxResponseBytes": settings::max_response_bytes(),
+                "maxConcurrency": settings::max_concurrency(),
+                "lowercaseTags": settings::lowercase_tags(),
+                "defaultBaseUrl": settings::default_base_url(),
+                "ignoredProjects": settings::ignored_projects(),
+                "chatLogPath": path(settings::chat_log_path()),
+                "previewMaxChars": settings::preview_max_chars(),
+                "largeSessionBytes": settings::large_session_bytes(),
+                "trashRetentionDays": settings::trash_retention_days(),
+                "customHeaders": settings::custom_headers().into_keys().collect::<Vec<_>>(),
+            },
+        })
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(resolved))
+}
+
+/// Responses smaller than this are sent uncompressed
+const MIN_COMPRESS_BYTES: u16 = 1024;
+
+/// Gzip for REST and static responses when the client accepts it.
+///
+/// Anything sent as `text/event-stream` (`/api/quick-chat`,
+/// `/api/cli/detect/stream`, `/api/search/stream`) is excluded by content type
+/// so events aren't held back in the encoder, as are images and bodies under
+/// `MIN_COMPRESS_BYTES`. WebSocket routes are not wrapped at all.
+fn compression_layer() -> CompressionLayer<impl Predicate> {
+    let predicate = SizeAbove::new(MIN_COMPRESS_BYTES)
+        .and(NotForContentType::GRPC)
+        .and(NotForContentType::IMAGES)
+        .and(NotForContentType::SSE);
+    CompressionLayer::new().gzip(true).compress_when(predicate)
+}
+
 #[tokio::main]
 async fn main() {
-    tracing_subscriber::fmt::init();
+    // A plain level from RUST_LOG, e.g. RUST_LOG=debug for quick chat's debugRaw log
+    let level = std::env::var("RUST_LOG")
+        .ok()
+        .and_then(|v| v.parse::<LevelFilter>().ok())
+        .unwrap_or(LevelFilter::INFO);
+    tracing_subscriber::fmt().with_max_level(level).init();
 
     let config = Config::parse();
 
+    // Warm the model list cache in the background
+    session_core::model_list::prefetch_models("claude");
+
+    // Drop trashed sessions past the retention period
+    tokio::task::spawn_blocking(|| {
+        if let Err(e) = session_core::trash::purge_expired_trash() {
+            tracing::warn!("Failed to purge expired trash: {}", e);
+        }
+    });
+
     // Start file watcher
     let fs_tx = ws::start_file_watcher();
 
@@ -150,20 +389,201 @@ async fn main() {
     // API routes (with auth middleware)
     let api_routes = Router::new()
         .route("/api/projects", get(routes::projects::get_projects))
+        .route("/api/project", get(routes::projects::get_project_info))
+        .route(
+            "/api/project/alias",
+            get(routes::projects::get_project_alias).put(routes::projects::set_project_alias),
+        )
+        .route(
+            "/api/projects/disk-usage",
+            get(routes::projects::project_disk_usage),
+        )
+        .route(
+            "/api/projects/encode",
+            get(routes::projects::encode_project_id),
+        )
         .route("/api/sessions", get(routes::sessions::get_sessions))
         .route("/api/sessions", delete(routes::sessions::delete_session))
         .route(
             "/api/sessions/meta",
             put(routes::sessions::update_session_meta),
         )
+        .route(
+            "/api/sessions/meta",
+            delete(routes::sessions::clear_session_meta),
+        )
+        .route("/api/sessions/move", post(routes::sessions::move_session))
+        .route(
+            "/api/trash",
+            get(routes::sessions::list_trash).delete(routes::sessions::empty_trash),
+        )
+        .route(
+            "/api/trash/restore",
+            post(routes::sessions::restore_session),
+        )
+        .route(
+            "/api/sessions/append",
+            post(routes::sessions::append_user_message),
+        )
+        .route("/api/sessions/trim", post(routes::sessions::trim_session))
+        .route("/api/sessions/split", post(routes::sessions::split_session))
+        .route(
+            "/api/sessions/modified",
+            get(routes::sessions::sessions_modified_since),
+        )
+        .route(
+            "/api/sessions/by-tag",
+            get(routes::sessions::sessions_grouped_by_tag),
+        )
+        .route(
+            "/api/sessions/summarize",
+            post(routes::sessions::summarize_session),
+        )
+        .route(
+            "/api/sessions/pin",
+            post(routes::sessions::toggle_pinned_message),
+        )
+        .route(
+            "/api/sessions/rating",
+            put(routes::sessions::set_session_rating),
+        )
+        .route(
+            "/api/sessions/query",
+            post(routes::sessions::query_sessions),
+        )
+        .route(
+            "/api/sessions/running",
+            get(routes::sessions::running_cli_sessions),
+        )
+        .route(
+            "/api/sessions/hash",
+            get(routes::sessions::session_content_hash),
+        )
+        .route(
+            "/api/sessions/reconcile",
+            post(routes::sessions::reconcile_metadata),
+        )
+        .route(
+            "/api/metadata/import",
+            post(routes::sessions::import_metadata),
+        )
         .route("/api/tags", get(routes::sessions::get_all_tags))
+        .route(
+            "/api/tags/rename",
+            get(routes::sessions::preview_rename_tag).post(routes::sessions::rename_tag),
+        )
+        .route(
+            "/api/tags/merge-casing",
+            post(routes::sessions::merge_tag_casing),
+        )
+        .route("/api/tags/delete", post(routes::sessions::delete_tag))
+        .route("/api/tags/validate", post(routes::sessions::validate_tags))
+        .route(
+            "/api/tags/auto",
+            post(routes::sessions::apply_auto_tag_rules),
+        )
+        .route("/api/aliases", get(routes::sessions::list_aliases))
+        .route(
+            "/api/aliases/duplicates",
+            get(routes::sessions::find_duplicate_aliases),
+        )
+        .route(
+            "/api/aliases/search",
+            get(routes::sessions::find_session_by_alias_fuzzy),
+        )
         .route("/api/cross-tags", get(routes::sessions::get_cross_project_tags))
+        .route("/api/tags/top", get(routes::sessions::top_tags))
+        .route(
+            "/api/tags/all-sources",
+            get(routes::sessions::get_all_tags_all_sources),
+        )
+        .route(
+            "/api/tags/all-sources/counts",
+            get(routes::sessions::tag_counts_all_sources),
+        )
+        .route(
+            "/api/tags/recent",
+            get(routes::sessions::get_tags_with_recency),
+        )
         .route("/api/messages", get(routes::messages::get_messages))
+        .route(
+            "/api/messages/check",
+            get(routes::messages::check_session_file),
+        )
+        .route(
+            "/api/messages/validate",
+            get(routes::messages::validate_session),
+        )
+        .route(
+            "/api/messages/size",
+            get(routes::messages::session_size_info),
+        )
+        .route(
+            "/api/messages/chat-msg",
+            get(routes::messages::message_to_chat_msg),
+        )
+        .route(
+            "/api/messages/chat-msgs",
+            get(routes::messages::load_session_as_messages),
+        )
+        .route(
+            "/api/messages/code-blocks",
+            get(routes::messages::extract_code_blocks),
+        )
         .route("/api/search", get(routes::search::global_search))
+        .route(
+            "/api/search/path",
+            get(routes::search::find_sessions_mentioning_path),
+        )
+        .route(
+            "/api/search/stream",
+            get(routes::search::global_search_stream),
+        )
         .route("/api/stats", get(routes::stats::get_stats))
+        .route(
+            "/api/stats/role-counts",
+            get(routes::stats::message_role_counts),
+        )
+        .route(
+            "/api/stats/top-sessions",
+            get(routes::stats::sessions_by_token_usage),
+        )
+        .route("/api/stats/activity", get(routes::stats::activity_by_day))
+        .route("/api/stats/models", get(routes::stats::models_used))
+        .route("/api/stats/ages", get(routes::stats::session_age_histogram))
+        .route("/api/stats/cost", get(routes::stats::session_cost))
+        .route("/api/stats/longest-turn", get(routes::stats::longest_turn))
+        .route("/api/stats/project-cost", get(routes::stats::project_cost))
         .route("/api/bookmarks", get(routes::bookmarks::list_bookmarks))
         .route("/api/bookmarks", post(routes::bookmarks::add_bookmark))
         .route("/api/bookmarks/{id}", delete(routes::bookmarks::remove_bookmark))
+        .route(
+            "/api/bookmarks/{id}/context",
+            get(routes::bookmarks::export_bookmark_context),
+        )
+        .route(
+            "/api/bookmarks/audit",
+            get(routes::bookmarks::audit_bookmarks),
+        )
+        .route(
+            "/api/bookmarks/move",
+            post(routes::bookmarks::move_bookmarks),
+        )
+        .route("/api/settings", get(routes::settings::get_settings))
+        .route("/api/settings", put(routes::settings::save_settings))
+        .route("/api/config/effective", get(effective_config_handler))
+        .route(
+            "/api/settings/validate",
+            get(routes::settings::validate_settings),
+        )
+        .route(
+            "/api/settings/ignored-projects",
+            post(routes::settings::add_ignored_project),
+        )
+        .route(
+            "/api/settings/ignored-projects",
+            delete(routes::settings::remove_ignored_project),
+        )
         .layer(middleware::from_fn(check_auth));
 
     // WebSocket route (with auth via query param or header)
@@ -178,22 +598,43 @@ async fn main() {
     // CLI detection + models + config route (with auth)
     let cli_routes = Router::new()
         .route("/api/cli/detect", get(detect_cli_handler))
+        .route("/api/cli/detect/stream", get(detect_cli_stream_handler))
         .route("/api/cli/config", get(cli_config_handler))
+        .route("/api/cli/config/explain", get(explain_config_handler))
+        .route("/api/cli/config/writable", get(config_writable_handler))
         .route("/api/models", post(list_models_handler))
+        .route("/api/models/page", post(list_models_page_handler))
+        .route("/api/models/audit", get(audit_models_handler))
+        .route("/api/models/availability", get(model_availability_handler))
         .route("/api/quick-chat", post(quick_chat_handler))
+        .route(
+            "/api/chat-template/export",
+            post(export_chat_template_handler),
+        )
+        .route(
+            "/api/chat-template/import",
+            post(import_chat_template_handler),
+        )
         .layer(middleware::from_fn(check_auth));
 
+    // Health check (no auth needed)
+    let health_routes = Router::new().route("/api/health", get(health_handler));
+
     // Static file fallback (no auth needed)
     let static_routes = Router::new().fallback(static_files::static_handler);
 
+    // WebSocket routes are merged after compression so upgrades pass untouched
     let app = Router::new()
         .merge(api_routes)
         .merge(cli_routes)
+        .merge(health_routes)
+        .merge(static_routes)
+        .layer(compression_layer())
         .merge(ws_routes)
         .merge(chat_ws_routes)
-        .merge(static_routes)
         .layer(CorsLayer::permissive())
-        .layer(axum::Extension(app_token));
+        .layer(axum::Extension(app_token))
+        .layer(axum::Extension(config.clone()));
 
     let addr = format!("{}:{}", config.host, config.port);
     let listener = tokio::net::TcpListener::bind(&addr)
@@ -211,3 +652,62 @@ async fn main() {
         .await
         .expect("Server error");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::header::{ACCEPT_ENCODING, CONTENT_ENCODING};
+    use axum::response::sse::{Event, Sse};
+    use http_body_util::BodyExt;
+    use std::convert::Infallible;
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+    use tokio_stream::wrappers::ReceiverStream;
+    use tower::ServiceExt;
+
+    fn gzip_request(uri: &str) -> axum::http::Request<Body> {
+        axum::http::Request::get(uri)
+            .header(ACCEPT_ENCODING, "gzip")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn compresses_large_json() {
+        let app = Router::new()
+            .route("/json", get(|| async { Json(vec!["session"; 500]) }))
+            .layer(compression_layer());
+
+        let response = app.oneshot(gzip_request("/json")).await.unwrap();
+        assert_eq!(response.headers()[CONTENT_ENCODING], "gzip");
+    }
+
+    #[tokio::test]
+    async fn sse_is_sent_uncompressed_one_event_at_a_time() {
+        let (tx, rx) = mpsc::channel::<Result<Event, Infallible>>(4);
+        let rx = Arc::new(parking_lot::Mutex::new(Some(rx)));
+        let app = Router::new()
+            .route(
+                "/stream",
+                get(move || async move {
+                    let rx = rx.lock().take().expect("stream requested once");
+                    Sse::new(ReceiverStream::new(rx))
+                }),
+            )
+            .layer(compression_layer());
+
+        let response = app.oneshot(gzip_request("/stream")).await.unwrap();
+        assert!(response.headers().get(CONTENT_ENCODING).is_none());
+
+        // The sender stays open, so the event must arrive before the stream ends
+        let mut body = response.into_body();
+        tx.send(Ok(Event::default().data("first"))).await.unwrap();
+        let frame = tokio::time::timeout(Duration::from_secs(5), body.frame())
+            .await
+            .expect("event was held back")
+            .unwrap()
+            .unwrap();
+        assert_eq!(frame.into_data().unwrap(), "data: first\n\n");
+    }
+}
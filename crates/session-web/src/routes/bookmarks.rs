@@ -2,7 +2,7 @@ use axum::extract::{Path, Query};
 use axum::http::StatusCode;
 use axum::response::Json;
 use serde::Deserialize;
-use session_core::bookmarks::{self, Bookmark};
+use session_core::bookmarks::{self, Bookmark, BookmarkAudit, MoveSummary};
 
 #[derive(Deserialize)]
 pub struct ListQuery {
@@ -15,10 +15,23 @@ pub async fn list_bookmarks(
     Json(bookmarks::list_bookmarks(params.source.as_deref()))
 }
 
+#[derive(Deserialize)]
+pub struct AddQuery {
+    #[serde(default = "default_verify")]
+    pub verify: bool,
+}
+
+fn default_verify() -> bool {
+    true
+}
+
 pub async fn add_bookmark(
+    Query(params): Query<AddQuery>,
     Json(bookmark): Json<Bookmark>,
 ) -> Result<Json<Bookmark>, (StatusCode, String)> {
-    bookmarks::add_bookmark(bookmark)
+    tokio::task::spawn_blocking(move || bookmarks::add_bookmark(bookmark, params.verify))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
         .map(Json)
         .map_err(|e| (StatusCode::BAD_REQUEST, e))
 }
@@ -30,3 +43,52 @@ pub async fn remove_bookmark(
         .map(Json)
         .map_err(|e| (StatusCode::NOT_FOUND, e))
 }
+
+#[derive(Deserialize)]
+pub struct MoveBody {
+    pub ids: Vec<String>,
+    #[serde(default)]
+    pub folder: Option<String>,
+}
+
+/// `POST /api/bookmarks/move` — `{ ids, folder? }` → `{ moved, missing }`
+pub async fn move_bookmarks(
+    Json(body): Json<MoveBody>,
+) -> Result<Json<MoveSummary>, (StatusCode, String)> {
+    tokio::task::spawn_blocking(move || {
+        bookmarks::move_bookmarks(&body.ids, body.folder.as_deref())
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map(Json)
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+#[derive(Deserialize)]
+pub struct ContextQuery {
+    pub source: String,
+    #[serde(default)]
+    pub before: usize,
+    #[serde(default)]
+    pub after: usize,
+}
+
+pub async fn export_bookmark_context(
+    Path(id): Path<String>,
+    Query(params): Query<ContextQuery>,
+) -> Result<String, (StatusCode, String)> {
+    tokio::task::spawn_blocking(move || {
+        bookmarks::export_bookmark_context(&id, params.before, params.after, &params.source)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::NOT_FOUND, e))
+}
+
+pub async fn audit_bookmarks() -> Result<Json<Vec<BookmarkAudit>>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(bookmarks::audit_bookmarks)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(result))
+}
@@ -2,8 +2,11 @@ use axum::extract::Query;
 use axum::response::Json;
 use axum::http::StatusCode;
 use serde::Deserialize;
+use session_core::export::{self, CodeBlock};
 use session_core::models::message::PaginatedMessages;
 use session_core::provider::{claude, codex};
+use session_core::quick_chat::ChatMsg;
+use session_core::session_ops::{self, CheckedMessages, SizeInfo, ValidationReport};
 use std::path::Path;
 
 #[derive(Deserialize)]
@@ -49,3 +52,114 @@ pub async fn get_messages(
 
     Ok(Json(result))
 }
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckQuery {
+    pub source: String,
+    pub file_path: String,
+}
+
+pub async fn check_session_file(
+    Query(params): Query<CheckQuery>,
+) -> Result<Json<CheckedMessages>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(move || {
+        session_ops::parse_session_checked(&params.file_path, &params.source)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(result))
+}
+
+pub async fn validate_session(
+    Query(params): Query<CheckQuery>,
+) -> Result<Json<ValidationReport>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(move || {
+        session_ops::validate_session(&params.file_path, &params.source)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(result))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeInfoQuery {
+    pub file_path: String,
+}
+
+pub async fn session_size_info(
+    Query(params): Query<SizeInfoQuery>,
+) -> Result<Json<SizeInfo>, (StatusCode, String)> {
+    let result =
+        tokio::task::spawn_blocking(move || session_ops::session_size_info(&params.file_path))
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .map_err(|e| (StatusCode::NOT_FOUND, e))?;
+
+    Ok(Json(result))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatMsgQuery {
+    pub source: String,
+    pub file_path: String,
+    pub message_id: String,
+}
+
+pub async fn message_to_chat_msg(
+    Query(params): Query<ChatMsgQuery>,
+) -> Result<Json<ChatMsg>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(move || {
+        session_ops::message_to_chat_msg(&params.file_path, &params.source, &params.message_id)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::NOT_FOUND, e))?;
+
+    Ok(Json(result))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionChatQuery {
+    pub source: String,
+    pub file_path: String,
+    #[serde(default)]
+    pub include_tools: bool,
+}
+
+pub async fn load_session_as_messages(
+    Query(params): Query<SessionChatQuery>,
+) -> Result<Json<Vec<ChatMsg>>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(move || {
+        session_ops::load_session_as_messages(
+            &params.file_path,
+            &params.source,
+            params.include_tools,
+        )
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(result))
+}
+
+pub async fn extract_code_blocks(
+    Query(params): Query<CheckQuery>,
+) -> Result<Json<Vec<CodeBlock>>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(move || {
+        export::extract_code_blocks(&params.file_path, &params.source)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(result))
+}
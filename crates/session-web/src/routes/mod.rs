@@ -3,4 +3,5 @@ pub mod messages;
 pub mod projects;
 pub mod search;
 pub mod sessions;
+pub mod settings;
 pub mod stats;
@@ -2,7 +2,7 @@ use axum::extract::Query;
 use axum::response::Json;
 use axum::http::StatusCode;
 use serde::Deserialize;
-use session_core::models::project::ProjectEntry;
+use session_core::models::project::{ProjectEntry, ProjectInfo};
 use session_core::provider::{claude, codex};
 
 #[derive(Deserialize)]
@@ -25,3 +25,79 @@ pub async fn get_projects(
 
     Ok(Json(result))
 }
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectInfoQuery {
+    pub source: String,
+    #[serde(alias = "project_id")]
+    pub project_id: String,
+}
+
+pub async fn get_project_info(
+    Query(params): Query<ProjectInfoQuery>,
+) -> Result<Json<ProjectInfo>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(move || {
+        session_core::project_info::get_project_info(&params.source, &params.project_id)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(result))
+}
+
+pub async fn project_disk_usage(
+    Query(params): Query<ProjectsQuery>,
+) -> Result<Json<Vec<(String, u64)>>, (StatusCode, String)> {
+    let source = params.source;
+    let result =
+        tokio::task::spawn_blocking(move || session_core::disk_usage::project_disk_usage(&source))
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(result))
+}
+
+pub async fn get_project_alias(
+    Query(params): Query<ProjectInfoQuery>,
+) -> Result<Json<Option<String>>, (StatusCode, String)> {
+    let alias = tokio::task::spawn_blocking(move || {
+        session_core::project_alias::get_project_alias(&params.source, &params.project_id)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(alias))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectAliasBody {
+    pub source: String,
+    pub project_id: String,
+    pub alias: Option<String>,
+}
+
+pub async fn set_project_alias(
+    Json(body): Json<ProjectAliasBody>,
+) -> Result<Json<()>, (StatusCode, String)> {
+    tokio::task::spawn_blocking(move || {
+        session_core::project_alias::set_project_alias(&body.source, &body.project_id, body.alias)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    Ok(Json(()))
+}
+
+#[derive(Deserialize)]
+pub struct EncodeQuery {
+    pub path: String,
+}
+
+pub async fn encode_project_id(Query(params): Query<EncodeQuery>) -> String {
+    session_core::parser::path_encoder::encode_project_id(&params.path)
+}
@@ -1,8 +1,11 @@
 use axum::extract::Query;
+use axum::response::sse::{Event, Sse};
 use axum::response::Json;
 use axum::http::StatusCode;
+use futures_util::{Stream, StreamExt};
 use serde::Deserialize;
-use session_core::search::SearchResult;
+use session_core::search::{PathMention, SearchResult};
+use std::convert::Infallible;
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -11,6 +14,9 @@ pub struct SearchQuery {
     pub query: String,
     #[serde(default = "default_max_results")]
     pub max_results: usize,
+    /// Typo-tolerant ranked matching (ignored by the streaming endpoint)
+    #[serde(default)]
+    pub fuzzy: bool,
 }
 
 fn default_max_results() -> usize {
@@ -23,9 +29,10 @@ pub async fn global_search(
     let source = params.source;
     let query = params.query;
     let max_results = params.max_results;
+    let fuzzy = params.fuzzy;
 
     let result = tokio::task::spawn_blocking(move || {
-        session_core::search::global_search(&source, &query, max_results)
+        session_core::search::global_search(&source, &query, max_results, fuzzy)
     })
     .await
     .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
@@ -33,3 +40,59 @@ pub async fn global_search(
 
     Ok(Json(result))
 }
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathMentionQuery {
+    pub source: String,
+    pub project_id: String,
+    pub path: String,
+}
+
+pub async fn find_sessions_mentioning_path(
+    Query(params): Query<PathMentionQuery>,
+) -> Result<Json<Vec<PathMention>>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(move || {
+        session_core::search::find_sessions_mentioning_path(
+            &params.source,
+            &params.project_id,
+            &params.path,
+        )
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    Ok(Json(result))
+}
+
+/// SSE variant of `global_search`: a `match` event per result as it is found,
+/// an `error` event on failure, then `[DONE]`. Closing the connection drops the
+/// receiver, which stops the scan.
+pub async fn global_search_stream(
+    Query(params): Query<SearchQuery>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let (tx, rx) = tokio::sync::mpsc::channel::<Event>(32);
+
+    tokio::task::spawn_blocking(move || {
+        let result = session_core::search::global_search_streaming(
+            &params.source,
+            &params.query,
+            params.max_results,
+            || tx.is_closed(),
+            |m| match serde_json::to_string(&m) {
+                Ok(json) => tx
+                    .blocking_send(Event::default().event("match").data(json))
+                    .is_ok(),
+                Err(_) => true,
+            },
+        );
+        if let Err(e) = result {
+            let err_json = serde_json::json!({ "error": e }).to_string();
+            let _ = tx.blocking_send(Event::default().event("error").data(err_json));
+        }
+        let _ = tx.blocking_send(Event::default().data("[DONE]"));
+    });
+
+    Sse::new(tokio_stream::wrappers::ReceiverStream::new(rx).map(Ok))
+}
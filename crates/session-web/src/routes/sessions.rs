@@ -1,39 +1,116 @@
 use axum::extract::Query;
-use axum::http::StatusCode;
-use axum::response::Json;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Json, Response};
 use serde::Deserialize;
+use session_core::http;
 use session_core::metadata;
-use session_core::models::session::SessionIndexEntry;
+use session_core::models::session::{CompactSessions, SessionIndexEntry};
+use session_core::project_info;
 use session_core::provider::{claude, codex};
+use session_core::session_query::{self, SessionPage, SessionQuery};
+use session_core::trash::{self, TrashEntry};
+use std::collections::HashMap;
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionsQuery {
     pub source: String,
     pub project_id: String,
+    #[serde(default)]
+    pub include_archived: bool,
+    /// Only return sessions rated at least this (unrated sessions are dropped)
+    #[serde(default)]
+    pub min_rating: Option<u8>,
+    /// Fill in `contentHash` (hashes every listed file; cached by mtime)
+    #[serde(default)]
+    pub with_hash: bool,
+    /// Only return sessions with no metadata entry (no alias, tags, rating...)
+    #[serde(default)]
+    pub only_unlabeled: bool,
+    /// Respond with `CompactSessions` instead of a list of objects
+    #[serde(default)]
+    pub compact: bool,
 }
 
+/// Media type that selects the compact session list (same as `?compact=true`)
+const COMPACT_MEDIA_TYPE: &str = "application/vnd.session-viewer.compact+json";
+
+/// List sessions. The default body is an array of `SessionIndexEntry` objects;
+/// with `?compact=true` or `Accept: application/vnd.session-viewer.compact+json`
+/// it is a `CompactSessions` (`{ "fields": [...], "rows": [[...], ...] }`), whose
+/// column order is `COMPACT_SESSION_FIELDS`.
+///
+/// Responses carry `ETag` and `Last-Modified` derived from the project's files
+/// (metadata included), the ETag also covering the preview length and tag
+/// case settings; a matching `If-None-Match`, or failing that a
+/// satisfied `If-Modified-Since`, gets `304 Not Modified` without a re-scan.
 pub async fn get_sessions(
+    headers: HeaderMap,
     Query(params): Query<SessionsQuery>,
-) -> Result<Json<Vec<SessionIndexEntry>>, (StatusCode, String)> {
+) -> Result<Response, (StatusCode, String)> {
+    let compact = params.compact
+        || headers
+            .get(header::ACCEPT)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.contains(COMPACT_MEDIA_TYPE))
+            .unwrap_or(false);
     let source = params.source;
     let project_id = params.project_id;
+    let include_archived = params.include_archived;
+    let min_rating = params.min_rating;
+    let with_hash = params.with_hash;
+    let only_unlabeled = params.only_unlabeled;
+
+    let (src, pid) = (source.clone(), project_id.clone());
+    let fingerprint =
+        tokio::task::spawn_blocking(move || project_info::sessions_fingerprint(&src, &pid))
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    let variant = format!(
+        "{}{}{}{}{}",
+        if compact { "c" } else { "f" },
+        if include_archived { "a" } else { "" },
+        if with_hash { "h" } else { "" },
+        if only_unlabeled { "u" } else { "" },
+        min_rating.map(|r| format!("r{}", r)).unwrap_or_default()
+    );
+    let cache_headers = [
+        (header::ETAG, fingerprint.etag(&variant)),
+        (
+            header::LAST_MODIFIED,
+            http::format_http_date(fingerprint.last_modified_secs()),
+        ),
+    ];
+    if is_not_modified(
+        &headers,
+        &cache_headers[0].1,
+        fingerprint.last_modified_secs(),
+    ) {
+        return Ok((StatusCode::NOT_MODIFIED, cache_headers).into_response());
+    }
+
     let result = tokio::task::spawn_blocking(move || {
         let mut sessions = match source.as_str() {
-            "claude" => claude::get_sessions(&project_id)?,
+            "claude" => claude::get_sessions(&project_id, None)?,
             "codex" => codex::get_sessions(&project_id)?,
             _ => return Err(format!("Unknown source: {}", source)),
         };
 
         // Merge metadata
-        let meta = metadata::load_metadata(&source, &project_id);
-        for session in &mut sessions {
-            if let Some(sm) = meta.sessions.get(&session.session_id) {
-                session.alias = sm.alias.clone();
-                if !sm.tags.is_empty() {
-                    session.tags = Some(sm.tags.clone());
-                }
-            }
+        let meta = metadata::merge_session_meta(&source, &project_id, &mut sessions);
+        if only_unlabeled {
+            sessions.retain(|s| !meta.sessions.contains_key(&s.session_id));
+        }
+
+        if !include_archived {
+            sessions.retain(|s| !s.archived);
+        }
+        if let Some(min) = min_rating {
+            session_query::filter_by_min_rating(&mut sessions, min);
+        }
+        if with_hash {
+            session_core::session_cache::fill_content_hashes(&mut sessions);
         }
 
         Ok(sessions)
@@ -42,9 +119,48 @@ pub async fn get_sessions(
     .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
     .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
 
+    if compact {
+        let body = CompactSessions::from_entries(&result)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+        return Ok((cache_headers, Json(body)).into_response());
+    }
+
+    Ok((cache_headers, Json(result)).into_response())
+}
+
+/// `POST /api/sessions/query` — filters, sort and pagination in one body:
+/// `{ source, projectId?, filters: { tags, minRating, dateRange: { from, to },
+/// search, includeArchived }, sort, page, pageSize }` → `{ total, page, sessions }`
+pub async fn query_sessions(
+    Json(query): Json<SessionQuery>,
+) -> Result<Json<SessionPage>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(move || session_query::query_sessions(&query))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
     Ok(Json(result))
 }
 
+/// Whether the request's validators match: `If-None-Match` wins when present
+/// (weak comparison, `*` matches anything), else `If-Modified-Since`.
+fn is_not_modified(headers: &HeaderMap, etag: &str, last_modified: i64) -> bool {
+    if let Some(inm) = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+    {
+        return inm
+            .split(',')
+            .map(|t| t.trim().trim_start_matches("W/"))
+            .any(|t| t == "*" || t == etag);
+    }
+    headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(http::parse_http_date)
+        .is_some_and(|since| last_modified <= since)
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DeleteQuery {
@@ -55,6 +171,9 @@ pub struct DeleteQuery {
     pub project_id: Option<String>,
     #[serde(default)]
     pub session_id: Option<String>,
+    /// Move the file to the trash instead of deleting it (needs `source`)
+    #[serde(default)]
+    pub to_trash: bool,
 }
 
 pub async fn delete_session(
@@ -64,11 +183,22 @@ pub async fn delete_session(
     let source = params.source;
     let project_id = params.project_id;
     let session_id = params.session_id;
+    let to_trash = params.to_trash;
     tokio::task::spawn_blocking(move || {
         let path = std::path::Path::new(&file_path);
         if !path.exists() {
             return Err(format!("File not found: {}", file_path));
         }
+        if to_trash {
+            let source = source.ok_or("source is required to trash a session")?;
+            return trash::move_to_trash(
+                &file_path,
+                &source,
+                &project_id.unwrap_or_default(),
+                &session_id.unwrap_or_default(),
+            )
+            .map(|_| ());
+        }
         std::fs::remove_file(path).map_err(|e| format!("Failed to delete session: {}", e))?;
 
         // Clean up metadata if identifiers provided
@@ -85,6 +215,201 @@ pub async fn delete_session(
     Ok(Json(()))
 }
 
+#[derive(Deserialize)]
+pub struct TrashQuery {
+    pub source: String,
+}
+
+pub async fn list_trash(
+    Query(params): Query<TrashQuery>,
+) -> Result<Json<Vec<TrashEntry>>, (StatusCode, String)> {
+    tokio::task::spawn_blocking(move || trash::list_trash(&params.source))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(Json)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))
+}
+
+#[derive(Deserialize)]
+pub struct RestoreBody {
+    pub id: String,
+}
+
+pub async fn restore_session(
+    Json(body): Json<RestoreBody>,
+) -> Result<Json<String>, (StatusCode, String)> {
+    tokio::task::spawn_blocking(move || trash::restore_session(&body.id))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(Json)
+        .map_err(|e| (StatusCode::CONFLICT, e))
+}
+
+#[derive(Deserialize)]
+pub struct EmptyTrashQuery {
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+pub async fn empty_trash(
+    Query(params): Query<EmptyTrashQuery>,
+) -> Result<Json<usize>, (StatusCode, String)> {
+    tokio::task::spawn_blocking(move || trash::empty_trash(params.source.as_deref()))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveSessionBody {
+    pub file_path: String,
+    pub source: String,
+    pub target_project_id: String,
+}
+
+pub async fn move_session(
+    Json(body): Json<MoveSessionBody>,
+) -> Result<Json<String>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(move || {
+        session_core::session_ops::move_session(
+            &body.file_path,
+            &body.source,
+            &body.target_project_id,
+        )
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(result))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppendMessageBody {
+    pub file_path: String,
+    pub source: String,
+    pub text: String,
+}
+
+pub async fn append_user_message(
+    Json(body): Json<AppendMessageBody>,
+) -> Result<Json<()>, (StatusCode, String)> {
+    tokio::task::spawn_blocking(move || {
+        session_core::session_ops::append_user_message(&body.file_path, &body.source, &body.text)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(()))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModifiedSinceQuery {
+    pub source: String,
+    pub project_id: Option<String>,
+    pub since: i64,
+}
+
+pub async fn sessions_modified_since(
+    Query(params): Query<ModifiedSinceQuery>,
+) -> Result<Json<Vec<SessionIndexEntry>>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(move || {
+        session_core::stats::sessions_modified_since(
+            &params.source,
+            params.project_id.as_deref(),
+            params.since,
+        )
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(result))
+}
+
+pub async fn sessions_grouped_by_tag(
+    Query(params): Query<TagsQuery>,
+) -> Result<Json<std::collections::HashMap<String, Vec<SessionIndexEntry>>>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(move || {
+        session_core::stats::sessions_grouped_by_tag(&params.source, &params.project_id)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(result))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrimSessionBody {
+    pub file_path: String,
+    pub source: String,
+    pub start_index: usize,
+    pub end_index: usize,
+}
+
+pub async fn trim_session(
+    Json(body): Json<TrimSessionBody>,
+) -> Result<Json<String>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(move || {
+        session_core::session_ops::trim_session(
+            &body.file_path,
+            &body.source,
+            body.start_index,
+            body.end_index,
+        )
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    Ok(Json(result))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitSessionBody {
+    pub file_path: String,
+    pub source: String,
+    pub split_indices: Vec<usize>,
+}
+
+pub async fn split_session(
+    Json(body): Json<SplitSessionBody>,
+) -> Result<Json<Vec<String>>, (StatusCode, String)> {
+    let paths = tokio::task::spawn_blocking(move || {
+        session_core::session_ops::split_session(&body.file_path, &body.source, body.split_indices)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    Ok(Json(paths))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SummarizeBody {
+    pub file_path: String,
+    pub source: String,
+    pub model: String,
+}
+
+pub async fn summarize_session(
+    Json(body): Json<SummarizeBody>,
+) -> Result<Json<String>, (StatusCode, String)> {
+    session_core::summary::summarize_session(&body.file_path, &body.source, &body.model)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateMetaBody {
@@ -94,6 +419,8 @@ pub struct UpdateMetaBody {
     pub alias: Option<String>,
     #[serde(default)]
     pub tags: Vec<String>,
+    #[serde(default)]
+    pub archived: Option<bool>,
 }
 
 pub async fn update_session_meta(
@@ -106,6 +433,7 @@ pub async fn update_session_meta(
             &body.session_id,
             body.alias,
             body.tags,
+            body.archived,
         )
     })
     .await
@@ -115,6 +443,137 @@ pub async fn update_session_meta(
     Ok(Json(()))
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClearMetaBody {
+    pub source: String,
+    pub project_id: String,
+    pub session_id: String,
+}
+
+pub async fn clear_session_meta(
+    Json(body): Json<ClearMetaBody>,
+) -> Result<Json<()>, (StatusCode, String)> {
+    tokio::task::spawn_blocking(move || {
+        metadata::clear_session_meta(&body.source, &body.project_id, &body.session_id)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(()))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TogglePinBody {
+    pub source: String,
+    pub project_id: String,
+    pub session_id: String,
+    pub message_id: String,
+}
+
+pub async fn toggle_pinned_message(
+    Json(body): Json<TogglePinBody>,
+) -> Result<Json<bool>, (StatusCode, String)> {
+    let pinned = tokio::task::spawn_blocking(move || {
+        metadata::toggle_pinned_message(
+            &body.source,
+            &body.project_id,
+            &body.session_id,
+            &body.message_id,
+        )
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(pinned))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RatingBody {
+    pub source: String,
+    pub project_id: String,
+    pub session_id: String,
+    /// 1–5, or null to clear
+    #[serde(default)]
+    pub rating: Option<u8>,
+}
+
+pub async fn set_session_rating(
+    Json(body): Json<RatingBody>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    tokio::task::spawn_blocking(move || {
+        metadata::set_session_rating(
+            &body.source,
+            &body.project_id,
+            &body.session_id,
+            body.rating,
+        )
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileBody {
+    pub source: String,
+    pub project_id: String,
+    #[serde(default)]
+    pub rekey: bool,
+}
+
+pub async fn reconcile_metadata(
+    Json(body): Json<ReconcileBody>,
+) -> Result<Json<metadata::ReconcileReport>, (StatusCode, String)> {
+    let report = tokio::task::spawn_blocking(move || {
+        metadata::reconcile_metadata(&body.source, &body.project_id, body.rekey)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(report))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportMetadataBody {
+    pub source: String,
+    pub project_id: String,
+    pub metadata: metadata::MetadataFile,
+    #[serde(default)]
+    pub policy: metadata::ConflictPolicy,
+    /// Preview the import without writing anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+pub async fn import_metadata(
+    Json(body): Json<ImportMetadataBody>,
+) -> Result<Json<metadata::ImportSummary>, (StatusCode, String)> {
+    let summary = tokio::task::spawn_blocking(move || {
+        metadata::import_metadata(
+            &body.source,
+            &body.project_id,
+            body.metadata,
+            body.policy,
+            body.dry_run,
+        )
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(summary))
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TagsQuery {
@@ -134,12 +593,226 @@ pub async fn get_all_tags(
     Ok(Json(tags))
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameTagParams {
+    pub source: String,
+    pub project_id: String,
+    pub old: String,
+    pub new: String,
+}
+
+pub async fn preview_rename_tag(
+    Query(params): Query<RenameTagParams>,
+) -> Result<Json<metadata::RenamePreview>, (StatusCode, String)> {
+    let preview = tokio::task::spawn_blocking(move || {
+        metadata::preview_rename_tag(&params.source, &params.project_id, &params.old, &params.new)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    Ok(Json(preview))
+}
+
+pub async fn rename_tag(
+    Json(body): Json<RenameTagParams>,
+) -> Result<Json<metadata::RenamePreview>, (StatusCode, String)> {
+    let renamed = tokio::task::spawn_blocking(move || {
+        metadata::rename_tag(&body.source, &body.project_id, &body.old, &body.new)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    Ok(Json(renamed))
+}
+
+pub async fn merge_tag_casing(
+    Json(body): Json<TagsQuery>,
+) -> Result<Json<usize>, (StatusCode, String)> {
+    let replaced = tokio::task::spawn_blocking(move || {
+        metadata::merge_tag_casing(&body.source, &body.project_id)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(replaced))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteTagBody {
+    pub source: String,
+    pub project_id: String,
+    pub tag: String,
+}
+
+pub async fn delete_tag(
+    Json(body): Json<DeleteTagBody>,
+) -> Result<Json<usize>, (StatusCode, String)> {
+    let changed = tokio::task::spawn_blocking(move || {
+        metadata::delete_tag(&body.source, &body.project_id, &body.tag)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(changed))
+}
+
+#[derive(Deserialize)]
+pub struct ValidateTagsBody {
+    pub tags: Vec<String>,
+}
+
+/// Violations of the configured tag rules; an empty list means every tag passes
+pub async fn validate_tags(Json(body): Json<ValidateTagsBody>) -> Json<Vec<String>> {
+    Json(
+        metadata::validate_tags(&body.tags)
+            .err()
+            .unwrap_or_default(),
+    )
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoTagBody {
+    pub source: String,
+    pub project_id: String,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+pub async fn apply_auto_tag_rules(
+    Json(body): Json<AutoTagBody>,
+) -> Result<Json<usize>, (StatusCode, String)> {
+    let tagged = tokio::task::spawn_blocking(move || {
+        session_core::auto_tag::apply_auto_tag_rules(&body.source, &body.project_id, body.dry_run)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    Ok(Json(tagged))
+}
+
+pub async fn list_aliases(
+    Query(params): Query<TagsQuery>,
+) -> Result<Json<Vec<(String, String)>>, (StatusCode, String)> {
+    let aliases = tokio::task::spawn_blocking(move || {
+        metadata::list_aliases(&params.source, &params.project_id)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(aliases))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AliasSearchQuery {
+    pub source: String,
+    pub project_id: String,
+    pub query: String,
+    #[serde(default = "default_alias_limit")]
+    pub limit: usize,
+}
+
+fn default_alias_limit() -> usize {
+    10
+}
+
+pub async fn find_session_by_alias_fuzzy(
+    Query(params): Query<AliasSearchQuery>,
+) -> Result<Json<Vec<(String, String, u32)>>, (StatusCode, String)> {
+    let matches = tokio::task::spawn_blocking(move || {
+        metadata::find_session_by_alias_fuzzy(
+            &params.source,
+            &params.project_id,
+            &params.query,
+            params.limit,
+        )
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(matches))
+}
+
+pub async fn find_duplicate_aliases(
+    Query(params): Query<TagsQuery>,
+) -> Result<Json<std::collections::HashMap<String, Vec<String>>>, (StatusCode, String)> {
+    let duplicates = tokio::task::spawn_blocking(move || {
+        metadata::find_duplicate_aliases(&params.source, &params.project_id)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(duplicates))
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CrossTagsQuery {
     pub source: String,
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentHashQuery {
+    pub file_path: String,
+}
+
+/// Hex SHA-256 of a session file
+pub async fn session_content_hash(
+    Query(params): Query<ContentHashQuery>,
+) -> Result<Json<String>, (StatusCode, String)> {
+    let hash = tokio::task::spawn_blocking(move || {
+        session_core::session_cache::session_content_hash(&params.file_path)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::NOT_FOUND, e))?;
+
+    Ok(Json(hash))
+}
+
+/// Tags of every project of every source: source → project → tags
+pub async fn get_all_tags_all_sources(
+) -> Result<Json<HashMap<String, HashMap<String, Vec<String>>>>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(metadata::get_all_tags_all_sources)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(result))
+}
+
+/// Number of sessions carrying each tag across every source
+pub async fn tag_counts_all_sources() -> Result<Json<HashMap<String, usize>>, (StatusCode, String)>
+{
+    let result = tokio::task::spawn_blocking(metadata::tag_counts_all_sources)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(result))
+}
+
+/// Session ids that a running CLI process appears to be working on
+pub async fn running_cli_sessions(
+    Query(params): Query<CrossTagsQuery>,
+) -> Result<Json<Vec<String>>, (StatusCode, String)> {
+    let source = params.source;
+    let ids = tokio::task::spawn_blocking(move || {
+        session_core::live_sessions::detect_running_cli_sessions(&source)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(ids))
+}
+
 pub async fn get_cross_project_tags(
     Query(params): Query<CrossTagsQuery>,
 ) -> Result<Json<std::collections::HashMap<String, Vec<String>>>, (StatusCode, String)> {
@@ -151,3 +824,269 @@ pub async fn get_cross_project_tags(
 
     Ok(Json(result))
 }
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagRecencyQuery {
+    pub source: String,
+    /// All projects of the source when omitted
+    pub project_id: Option<String>,
+}
+
+pub async fn get_tags_with_recency(
+    Query(params): Query<TagRecencyQuery>,
+) -> Result<Json<Vec<(String, i64)>>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(move || match params.project_id {
+        Some(pid) => metadata::get_tags_with_recency(&params.source, &pid),
+        None => metadata::get_recent_tags(&params.source),
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(result))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopTagsQuery {
+    pub source: String,
+    pub limit: usize,
+}
+
+pub async fn top_tags(
+    Query(params): Query<TopTagsQuery>,
+) -> Result<Json<Vec<(String, usize)>>, (StatusCode, String)> {
+    let result =
+        tokio::task::spawn_blocking(move || metadata::top_tags(&params.source, params.limit))
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use axum::routing::get;
+    use axum::Router;
+    use http_body_util::BodyExt;
+    use session_core::test_support::{claude_user, with_timestamp, TestHome};
+    use tower::ServiceExt;
+
+    const PROJECT: &str = "-home-user-project";
+    const URI: &str = "/api/sessions?source=claude&projectId=-home-user-project";
+
+    async fn list(uri: &str, conditional: Option<(header::HeaderName, &str)>) -> Response {
+        let mut request = Request::get(uri);
+        if let Some((name, value)) = conditional {
+            request = request.header(name, value);
+        }
+        Router::new()
+            .route("/api/sessions", get(get_sessions))
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    fn validator(response: &Response, name: header::HeaderName) -> String {
+        response.headers()[name].to_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn conditional_requests_get_304_until_metadata_changes() {
+        let home = TestHome::new();
+        home.claude_session(PROJECT, "s1", &[claude_user("u1", "hello")]);
+
+        let first = list(URI, None).await;
+        assert_eq!(first.status(), StatusCode::OK);
+        let etag = validator(&first, header::ETAG);
+        let last_modified = validator(&first, header::LAST_MODIFIED);
+
+        let cached = list(URI, Some((header::IF_NONE_MATCH, &etag))).await;
+        assert_eq!(cached.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(validator(&cached, header::ETAG), etag);
+        assert!(cached
+            .into_body()
+            .collect()
+            .await
+            .unwrap()
+            .to_bytes()
+            .is_empty());
+
+        let cached = list(URI, Some((header::IF_MODIFIED_SINCE, &last_modified))).await;
+        assert_eq!(cached.status(), StatusCode::NOT_MODIFIED);
+
+        metadata::update_session_meta(
+            "claude",
+            PROJECT,
+            "s1",
+            Some("renamed".to_string()),
+            Vec::new(),
+            None,
+        )
+        .unwrap();
+
+        let fresh = list(URI, Some((header::IF_NONE_MATCH, &etag))).await;
+        assert_eq!(fresh.status(), StatusCode::OK);
+        assert_ne!(validator(&fresh, header::ETAG), etag);
+        let body = fresh.into_body().collect().await.unwrap().to_bytes();
+        let sessions: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(sessions[0]["alias"], "renamed");
+    }
+
+    #[tokio::test]
+    async fn listing_settings_change_the_etag() {
+        let home = TestHome::new();
+        home.claude_session(PROJECT, "s1", &[claude_user("u1", "hello")]);
+        let etag = validator(&list(URI, None).await, header::ETAG);
+
+        session_core::settings::save_settings(&session_core::settings::SettingsFile {
+            preview_max_chars: Some(40),
+            ..Default::default()
+        })
+        .unwrap();
+        let shorter = list(URI, Some((header::IF_NONE_MATCH, &etag))).await;
+        assert_eq!(shorter.status(), StatusCode::OK);
+        let etag = validator(&shorter, header::ETAG);
+
+        session_core::settings::save_settings(&session_core::settings::SettingsFile {
+            preview_max_chars: Some(40),
+            lowercase_tags: Some(true),
+            ..Default::default()
+        })
+        .unwrap();
+        let lowercased = list(URI, Some((header::IF_NONE_MATCH, &etag))).await;
+        assert_eq!(lowercased.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn min_rating_keeps_sessions_rated_high_enough() {
+        let home = TestHome::new();
+        for (session_id, rating) in [
+            ("low", Some(2)),
+            ("high", Some(4)),
+            ("top", Some(5)),
+            ("unrated", None),
+        ] {
+            home.claude_session(PROJECT, session_id, &[claude_user(session_id, "hello")]);
+            metadata::set_session_rating("claude", PROJECT, session_id, rating).unwrap();
+        }
+
+        let response = list(&format!("{}&minRating=4", URI), None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let sessions: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+        let mut rated: Vec<(&str, u64)> = sessions
+            .iter()
+            .map(|s| {
+                (
+                    s["sessionId"].as_str().unwrap(),
+                    s["rating"].as_u64().unwrap(),
+                )
+            })
+            .collect();
+        rated.sort();
+        assert_eq!(rated, [("high", 4), ("top", 5)]);
+    }
+
+    #[tokio::test]
+    async fn only_unlabeled_leaves_out_sessions_with_any_metadata() {
+        let home = TestHome::new();
+        for session_id in ["rated", "inbox"] {
+            home.claude_session(PROJECT, session_id, &[claude_user(session_id, "hello")]);
+        }
+        metadata::set_session_rating("claude", PROJECT, "rated", Some(5)).unwrap();
+
+        let response = list(&format!("{}&onlyUnlabeled=true", URI), None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let sessions: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+        let ids: Vec<&str> = sessions
+            .iter()
+            .map(|s| s["sessionId"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, ["inbox"]);
+
+        let all = list(URI, None).await;
+        let body = all.into_body().collect().await.unwrap().to_bytes();
+        let sessions: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(sessions.len(), 2);
+    }
+
+    async fn query(body: serde_json::Value) -> (StatusCode, serde_json::Value) {
+        let request = Request::post("/api/sessions/query")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = Router::new()
+            .route("/api/sessions/query", axum::routing::post(query_sessions))
+            .oneshot(request)
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn combined_query_filters_sorts_and_pages() {
+        let home = TestHome::new();
+        // id, tag, rating, start day, first prompt
+        for (id, tag, rating, day, prompt) in [
+            ("best", "rust", 5, "2025-03-02", "parser rewrite"),
+            ("good", "rust", 4, "2025-03-03", "parser tests"),
+            ("low", "rust", 3, "2025-03-04", "parser docs"),
+            ("docs", "docs", 5, "2025-03-05", "parser guide"),
+            ("late", "rust", 5, "2025-04-10", "parser cleanup"),
+            ("lexer", "rust", 4, "2025-03-06", "lexer rewrite"),
+        ] {
+            let record = with_timestamp(&claude_user(id, prompt), &format!("{}T12:00:00Z", day));
+            home.claude_session(PROJECT, id, &[record]);
+            metadata::update_session_meta("claude", PROJECT, id, None, vec![tag.to_string()], None)
+                .unwrap();
+            metadata::set_session_rating("claude", PROJECT, id, Some(rating)).unwrap();
+        }
+        let page = |page: usize| {
+            serde_json::json!({
+                "source": "claude",
+                "projectId": PROJECT,
+                "filters": {
+                    "tags": ["Rust"],
+                    "minRating": 4,
+                    "dateRange": { "from": "2025-03-01", "to": "2025-03-31" },
+                    "search": "PARSER",
+                },
+                "sort": "rating",
+                "page": page,
+                "pageSize": 1,
+            })
+        };
+        let ids = |body: &serde_json::Value| -> Vec<String> {
+            body["sessions"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|s| s["sessionId"].as_str().unwrap().to_string())
+                .collect()
+        };
+
+        let (status, first) = query(page(0)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            (first["total"].as_u64(), first["page"].as_u64()),
+            (Some(2), Some(0))
+        );
+        assert_eq!(ids(&first), ["best"]);
+
+        let (_, second) = query(page(1)).await;
+        assert_eq!(ids(&second), ["good"]);
+        let (_, past_the_end) = query(page(2)).await;
+        assert!(ids(&past_the_end).is_empty());
+
+        let mut bad = page(0);
+        bad["filters"]["dateRange"]["from"] = "March".into();
+        assert_eq!(query(bad).await.0, StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,59 @@
+use axum::http::StatusCode;
+use axum::response::Json;
+use serde::Deserialize;
+use session_core::settings::{self, SettingsFile, SettingsWarning};
+
+pub async fn get_settings() -> Result<Json<SettingsFile>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(settings::load_settings)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(result))
+}
+
+pub async fn save_settings(
+    Json(body): Json<SettingsFile>,
+) -> Result<Json<()>, (StatusCode, String)> {
+    tokio::task::spawn_blocking(move || settings::save_settings(&body))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(()))
+}
+
+pub async fn validate_settings() -> Result<Json<Vec<SettingsWarning>>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(settings::validate_settings)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(result))
+}
+
+#[derive(Deserialize)]
+pub struct IgnoredProjectBody {
+    pub pattern: String,
+}
+
+pub async fn add_ignored_project(
+    Json(body): Json<IgnoredProjectBody>,
+) -> Result<Json<()>, (StatusCode, String)> {
+    tokio::task::spawn_blocking(move || settings::add_ignored_project(&body.pattern))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    Ok(Json(()))
+}
+
+pub async fn remove_ignored_project(
+    Json(body): Json<IgnoredProjectBody>,
+) -> Result<Json<()>, (StatusCode, String)> {
+    tokio::task::spawn_blocking(move || settings::remove_ignored_project(&body.pattern))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(()))
+}
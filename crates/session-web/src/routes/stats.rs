@@ -2,7 +2,10 @@ use axum::extract::Query;
 use axum::response::Json;
 use axum::http::StatusCode;
 use serde::Deserialize;
+use session_core::cost::CostEstimate;
+use session_core::models::session::SessionIndexEntry;
 use session_core::models::stats::TokenUsageSummary;
+use session_core::stats::TurnInfo;
 
 #[derive(Deserialize)]
 pub struct StatsQuery {
@@ -22,3 +25,159 @@ pub async fn get_stats(
 
     Ok(Json(result))
 }
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleCountsQuery {
+    pub file_path: String,
+    pub source: String,
+}
+
+pub async fn message_role_counts(
+    Query(params): Query<RoleCountsQuery>,
+) -> Result<Json<std::collections::HashMap<String, usize>>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(move || {
+        session_core::stats::message_role_counts(&params.file_path, &params.source)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(result))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenUsageQuery {
+    pub source: String,
+    pub project_id: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+fn default_limit() -> usize {
+    20
+}
+
+pub async fn sessions_by_token_usage(
+    Query(params): Query<TokenUsageQuery>,
+) -> Result<Json<Vec<SessionIndexEntry>>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(move || {
+        session_core::stats::sessions_by_token_usage(
+            &params.source,
+            params.project_id.as_deref(),
+            params.limit,
+        )
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(result))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityQuery {
+    pub source: String,
+    pub project_id: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+pub async fn activity_by_day(
+    Query(params): Query<ActivityQuery>,
+) -> Result<Json<std::collections::HashMap<String, usize>>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(move || {
+        session_core::stats::activity_by_day(
+            &params.source,
+            params.project_id.as_deref(),
+            params.from.as_deref(),
+            params.to.as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(result))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopeQuery {
+    pub source: String,
+    pub project_id: Option<String>,
+}
+
+pub async fn models_used(
+    Query(params): Query<ScopeQuery>,
+) -> Result<Json<Vec<(String, usize)>>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(move || {
+        session_core::stats::models_used(&params.source, params.project_id.as_deref())
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(result))
+}
+
+pub async fn session_age_histogram(
+    Query(params): Query<ScopeQuery>,
+) -> Result<Json<Vec<(String, usize)>>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(move || {
+        session_core::stats::session_age_histogram(&params.source, params.project_id.as_deref())
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(result))
+}
+
+pub async fn session_cost(
+    Query(params): Query<RoleCountsQuery>,
+) -> Result<Json<CostEstimate>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(move || {
+        session_core::cost::session_cost(&params.file_path, &params.source)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    Ok(Json(result))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectCostQuery {
+    pub source: String,
+    pub project_id: String,
+}
+
+pub async fn project_cost(
+    Query(params): Query<ProjectCostQuery>,
+) -> Result<Json<CostEstimate>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(move || {
+        session_core::cost::project_cost(&params.source, &params.project_id)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(result))
+}
+
+pub async fn longest_turn(
+    Query(params): Query<RoleCountsQuery>,
+) -> Result<Json<TurnInfo>, (StatusCode, String)> {
+    let result = tokio::task::spawn_blocking(move || {
+        session_core::stats::longest_turn(&params.file_path, &params.source)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    Ok(Json(result))
+}
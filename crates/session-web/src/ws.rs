@@ -1,7 +1,7 @@
 use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
 use axum::response::Response;
 use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
-use std::sync::Arc;
+use std::sync::{mpsc, Arc};
 use std::time::{Duration, Instant};
 use tokio::sync::broadcast;
 
@@ -23,7 +23,7 @@ pub fn start_file_watcher() -> FsChangeTx {
     let tx_clone = tx.clone();
 
     std::thread::spawn(move || {
-        let (notify_tx, notify_rx) = std::sync::mpsc::channel();
+        let (notify_tx, notify_rx) = mpsc::channel();
 
         let mut watcher = match RecommendedWatcher::new(notify_tx, Config::default()) {
             Ok(w) => w,
@@ -52,10 +52,20 @@ pub fn start_file_watcher() -> FsChangeTx {
         }
 
         let mut last_emit = Instant::now() - DEBOUNCE_DURATION;
+        let mut last_flush = Instant::now();
+
+        loop {
+            // Wake up at least once per window so queued reindexes are
+            // flushed even after the writes stop
+            match notify_rx.recv_timeout(DEBOUNCE_DURATION) {
+                Ok(Ok(event)) => {
+                    // Directory events matter too (new/removed projects), so this
+                    // runs before the session-file filter and the debounce
+                    for path in &event.paths {
+                        session_core::project_info::invalidate_path(path);
+                        session_core::session_cache::queue_reindex(path);
+                    }
 
-        for event in notify_rx {
-            match event {
-                Ok(event) => {
                     let relevant = event.paths.iter().any(|p| {
                         p.extension()
                             .map(|e| e == "jsonl" || e == "json")
@@ -73,9 +83,16 @@ pub fn start_file_watcher() -> FsChangeTx {
                         last_emit = Instant::now();
                     }
                 }
-                Err(e) => {
+                Ok(Err(e)) => {
                     tracing::warn!("Watch error: {}", e);
                 }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+
+            if last_flush.elapsed() >= DEBOUNCE_DURATION {
+                session_core::session_cache::flush_reindex();
+                last_flush = Instant::now();
             }
         }
     });
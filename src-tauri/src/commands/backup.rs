@@ -0,0 +1,15 @@
+use session_core::backup::{self, BackupSummary, RestoreSummary};
+
+#[tauri::command]
+pub async fn backup_all(source: String, dest_path: String) -> Result<BackupSummary, String> {
+    tokio::task::spawn_blocking(move || backup::backup_all(&source, &dest_path))
+        .await
+        .map_err(|e| format!("backup_all task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn restore_all(archive_path: String) -> Result<RestoreSummary, String> {
+    tokio::task::spawn_blocking(move || backup::restore_all(&archive_path))
+        .await
+        .map_err(|e| format!("restore_all task failed: {}", e))?
+}
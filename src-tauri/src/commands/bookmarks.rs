@@ -1,4 +1,4 @@
-use session_core::bookmarks::{self, Bookmark};
+use session_core::bookmarks::{self, Bookmark, BookmarkAudit, MoveSummary};
 
 #[tauri::command]
 pub fn list_bookmarks(source: Option<String>) -> Result<Vec<Bookmark>, String> {
@@ -6,11 +6,34 @@ pub fn list_bookmarks(source: Option<String>) -> Result<Vec<Bookmark>, String> {
 }
 
 #[tauri::command]
-pub fn add_bookmark(bookmark: Bookmark) -> Result<Bookmark, String> {
-    bookmarks::add_bookmark(bookmark)
+pub fn add_bookmark(bookmark: Bookmark, verify: Option<bool>) -> Result<Bookmark, String> {
+    bookmarks::add_bookmark(bookmark, verify.unwrap_or(true))
 }
 
 #[tauri::command]
 pub fn remove_bookmark(id: String) -> Result<(), String> {
     bookmarks::remove_bookmark(&id)
 }
+
+/// Refile bookmarks into a folder in one save; reports the moved count and
+/// the ids that matched no bookmark
+#[tauri::command]
+pub fn move_bookmarks(ids: Vec<String>, folder: Option<String>) -> Result<MoveSummary, String> {
+    bookmarks::move_bookmarks(&ids, folder.as_deref())
+}
+
+#[tauri::command]
+pub fn export_bookmark_context(
+    id: String,
+    before: usize,
+    after: usize,
+    source: String,
+) -> Result<String, String> {
+    bookmarks::export_bookmark_context(&id, before, after, &source)
+}
+
+/// Every bookmark with whether its session file and message still exist
+#[tauri::command]
+pub fn audit_bookmarks() -> Result<Vec<BookmarkAudit>, String> {
+    Ok(bookmarks::audit_bookmarks())
+}
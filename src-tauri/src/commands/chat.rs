@@ -8,9 +8,9 @@ use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::{Child, Command};
 
 use session_core::cli;
-use session_core::cli_config::{self, CliConfig};
-use session_core::model_list::{self, ModelInfo};
-use session_core::quick_chat::{self, ChatMsg};
+use session_core::cli_config::{self, CliConfig, ConfigExplanation, ConfigWritability};
+use session_core::model_list::{self, ModelAudit, ModelAvailability, ModelInfo, ModelPage};
+use session_core::quick_chat::{self, ChatMsg, ChatOptions};
 
 /// State to track active chat processes.
 pub struct ChatProcessState {
@@ -25,6 +25,19 @@ impl ChatProcessState {
     }
 }
 
+/// State to track in-flight `stream_chat_cmd` requests.
+pub struct ChatStreamState {
+    pub tasks: Mutex<HashMap<String, tokio::task::AbortHandle>>, // request_id -> task
+}
+
+impl ChatStreamState {
+    pub fn new() -> Self {
+        Self {
+            tasks: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
 #[tauri::command]
 pub async fn detect_cli() -> Result<Vec<cli::CliInstallation>, String> {
     tokio::task::spawn_blocking(cli::discover_installations)
@@ -32,6 +45,33 @@ pub async fn detect_cli() -> Result<Vec<cli::CliInstallation>, String> {
         .map_err(|e| format!("detect_cli task failed: {}", e))
 }
 
+/// Incremental discovery: emits `cli-discovered` per installation as its version
+/// probe completes, then `cli-discovery-done`. Also returns the full list.
+#[tauri::command]
+pub async fn detect_cli_streaming(app: AppHandle) -> Result<Vec<cli::CliInstallation>, String> {
+    let app_handle = app.clone();
+    let installations = tokio::task::spawn_blocking(move || {
+        cli::discover_installations_streaming(cli::DISCOVERY_TIMEOUT, |installation| {
+            let _ = app_handle.emit("cli-discovered", installation);
+        })
+    })
+    .await
+    .map_err(|e| format!("detect_cli_streaming task failed: {}", e))?;
+
+    let _ = app.emit("cli-discovery-done", installations.len());
+    Ok(installations)
+}
+
+#[tauri::command]
+pub fn explain_config(source: String) -> Result<ConfigExplanation, String> {
+    cli_config::explain_config(&source)
+}
+
+#[tauri::command]
+pub fn config_writable(source: String) -> Result<ConfigWritability, String> {
+    cli_config::config_writable(&source)
+}
+
 #[tauri::command]
 pub async fn get_cli_config(source: String) -> Result<CliConfig, String> {
     tokio::task::spawn_blocking(move || cli_config::read_cli_config(&source))
@@ -45,11 +85,13 @@ pub async fn quick_chat(
     source: String,
     messages: Vec<ChatMsg>,
     model: String,
+    options: Option<ChatOptions>,
 ) -> Result<(), String> {
     let app_handle = app.clone();
+    let options = options.unwrap_or_default();
 
     tokio::spawn(async move {
-        let result = quick_chat::stream_chat(&source, messages, &model, |chunk| {
+        let result = quick_chat::stream_chat(&source, messages, &model, &options, |chunk| {
             let _ = app_handle.emit("quick-chat-chunk", chunk);
         })
         .await;
@@ -74,13 +116,166 @@ pub async fn quick_chat(
     Ok(())
 }
 
+/// Stream a chat completion to the frontend as Tauri events and return the request id.
+///
+/// Every payload carries the `requestId` (the given one, or a generated UUID):
+/// - `chat://delta` `{ requestId, delta }` for each text chunk
+/// - `chat://usage` `{ requestId, inputTokens, outputTokens, apiRequestId }` once the reply
+///   completes (`apiRequestId` is the provider's `request-id`, for support tickets)
+/// - `chat://error` `{ requestId, error }` if the request fails
+/// - `chat://done` `{ requestId, success, cancelled }` always last
+///
+/// Cancel with `cancel_stream_chat(requestId)`.
+#[tauri::command]
+pub async fn stream_chat_cmd(
+    app: AppHandle,
+    source: String,
+    messages: Vec<ChatMsg>,
+    model: String,
+    options: Option<ChatOptions>,
+    request_id: Option<String>,
+) -> Result<String, String> {
+    let request_id = request_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let options = options.unwrap_or_default();
+    let app_handle = app.clone();
+    let id = request_id.clone();
+
+    let state = app.state::<ChatStreamState>();
+    // Hold the lock until the handle is stored so a fast task can't finish first
+    let mut tasks = state.tasks.lock();
+    if tasks.contains_key(&request_id) {
+        return Err(format!("Request already in progress: {}", request_id));
+    }
+
+    let task = tokio::spawn(async move {
+        let result =
+            quick_chat::stream_chat_with_usage(&source, messages, &model, &options, |delta| {
+                let _ = app_handle.emit(
+                    "chat://delta",
+                    serde_json::json!({ "requestId": id, "delta": delta }),
+                );
+            })
+            .await;
+
+        app_handle
+            .state::<ChatStreamState>()
+            .tasks
+            .lock()
+            .remove(&id);
+
+        let success = match result {
+            Ok(usage) => {
+                let _ = app_handle.emit(
+                    "chat://usage",
+                    serde_json::json!({
+                        "requestId": id,
+                        "inputTokens": usage.input_tokens,
+                        "outputTokens": usage.output_tokens,
+                        "cacheCreationInputTokens": usage.cache_creation_input_tokens,
+                        "cacheReadInputTokens": usage.cache_read_input_tokens,
+                        "apiRequestId": usage.request_id,
+                    }),
+                );
+                true
+            }
+            Err(e) => {
+                let _ = app_handle.emit(
+                    "chat://error",
+                    serde_json::json!({ "requestId": id, "error": e }),
+                );
+                false
+            }
+        };
+        let _ = app_handle.emit(
+            "chat://done",
+            serde_json::json!({ "requestId": id, "success": success, "cancelled": false }),
+        );
+    });
+    tasks.insert(request_id.clone(), task.abort_handle());
+
+    Ok(request_id)
+}
+
+/// Abort a `stream_chat_cmd` request. Returns false if it already finished.
+#[tauri::command]
+pub fn cancel_stream_chat(app: AppHandle, request_id: String) -> Result<bool, String> {
+    let handle = app
+        .state::<ChatStreamState>()
+        .tasks
+        .lock()
+        .remove(&request_id);
+
+    match handle {
+        Some(handle) => {
+            handle.abort();
+            let _ = app.emit(
+                "chat://done",
+                serde_json::json!({ "requestId": request_id, "success": false, "cancelled": true }),
+            );
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[tauri::command]
+pub fn export_chat_template(
+    messages: Vec<ChatMsg>,
+    model: String,
+    options: Option<ChatOptions>,
+) -> String {
+    quick_chat::export_chat_template(&messages, &model, &options.unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn import_chat_template(json: String) -> Result<(Vec<ChatMsg>, String, ChatOptions), String> {
+    quick_chat::import_chat_template(&json)
+}
+
 #[tauri::command]
 pub async fn list_models(
     source: String,
     api_key: String,
     base_url: String,
+    provider_filter: Option<String>,
+    min_context: Option<u32>,
 ) -> Result<Vec<ModelInfo>, String> {
-    model_list::list_models(&source, &api_key, &base_url).await
+    model_list::list_models(
+        &source,
+        &api_key,
+        &base_url,
+        provider_filter.as_deref(),
+        min_context,
+    )
+    .await
+}
+
+/// One page of models; pass `cursor` from the previous page to continue
+#[tauri::command]
+pub async fn list_models_page(
+    source: String,
+    api_key: String,
+    base_url: String,
+    cursor: Option<String>,
+) -> Result<ModelPage, String> {
+    model_list::list_models_page(&source, &api_key, &base_url, cursor.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn audit_session_models(
+    source: String,
+    project_id: String,
+) -> Result<Vec<ModelAudit>, String> {
+    model_list::audit_session_models(&source, &project_id).await
+}
+
+/// Whether a session's last model is still offered, checked before resuming it
+#[tauri::command]
+pub async fn check_session_model_available(
+    file_path: String,
+    source: String,
+) -> Result<ModelAvailability, String> {
+    model_list::check_session_model_available(&file_path, &source).await
 }
 
 #[tauri::command]
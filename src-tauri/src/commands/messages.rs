@@ -1,7 +1,10 @@
 use std::path::Path;
 
+use session_core::export::{self, CodeBlock};
 use session_core::models::message::PaginatedMessages;
 use session_core::provider::{claude, codex};
+use session_core::quick_chat::ChatMsg;
+use session_core::session_ops::{self, CheckedMessages, SizeInfo, ValidationReport};
 
 #[tauri::command]
 pub fn get_messages(
@@ -22,3 +25,57 @@ pub fn get_messages(
         _ => Err(format!("Unknown source: {}", source)),
     }
 }
+
+/// Strict parse that reports the first malformed line alongside the messages before it
+#[tauri::command]
+pub fn check_session_file(source: String, file_path: String) -> Result<CheckedMessages, String> {
+    session_ops::parse_session_checked(&file_path, &source)
+}
+
+/// Integrity report: bad lines, unpaired tool calls/results and message order
+#[tauri::command]
+pub fn validate_session(source: String, file_path: String) -> Result<ValidationReport, String> {
+    session_ops::validate_session(&file_path, &source)
+}
+
+/// File size and estimated message count, checked before opening a session
+#[tauri::command]
+pub fn session_size_info(file_path: String) -> Result<SizeInfo, String> {
+    session_ops::session_size_info(&file_path)
+}
+
+/// One message flattened to a quick-chat message, for seeding a new chat
+#[tauri::command]
+pub fn message_to_chat_msg(
+    source: String,
+    file_path: String,
+    message_id: String,
+) -> Result<ChatMsg, String> {
+    session_ops::message_to_chat_msg(&file_path, &source, &message_id)
+}
+
+/// A whole session as quick-chat messages; `include_tools` keeps tool calls
+/// and results as API tool blocks
+#[tauri::command]
+pub fn load_session_as_messages(
+    source: String,
+    file_path: String,
+    include_tools: Option<bool>,
+) -> Result<Vec<ChatMsg>, String> {
+    session_ops::load_session_as_messages(&file_path, &source, include_tools.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn extract_code_blocks(source: String, file_path: String) -> Result<Vec<CodeBlock>, String> {
+    export::extract_code_blocks(&file_path, &source)
+}
+
+/// Write each code block of a session to `dest_dir`; returns the file paths
+#[tauri::command]
+pub fn write_code_blocks(
+    source: String,
+    file_path: String,
+    dest_dir: String,
+) -> Result<Vec<String>, String> {
+    export::write_code_blocks(&file_path, &source, &dest_dir)
+}
@@ -1,9 +1,11 @@
+pub mod backup;
 pub mod bookmarks;
 pub mod chat;
 pub mod messages;
 pub mod projects;
 pub mod search;
 pub mod sessions;
+pub mod settings;
 pub mod stats;
 pub mod terminal;
 pub mod updater;
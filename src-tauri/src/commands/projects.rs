@@ -1,4 +1,4 @@
-use session_core::models::project::ProjectEntry;
+use session_core::models::project::{DataDirStatus, ProjectEntry, ProjectInfo};
 use session_core::provider::{claude, codex};
 
 #[tauri::command]
@@ -9,3 +9,39 @@ pub fn get_projects(source: String) -> Result<Vec<ProjectEntry>, String> {
         _ => Err(format!("Unknown source: {}", source)),
     }
 }
+
+#[tauri::command]
+pub fn get_project_info(source: String, project_id: String) -> Result<ProjectInfo, String> {
+    session_core::project_info::get_project_info(&source, &project_id)
+}
+
+#[tauri::command]
+pub fn project_disk_usage(source: String) -> Result<Vec<(String, u64)>, String> {
+    session_core::disk_usage::project_disk_usage(&source)
+}
+
+#[tauri::command]
+pub fn claude_data_dir_status() -> DataDirStatus {
+    claude::claude_data_dir_status()
+}
+
+#[tauri::command]
+pub fn get_project_alias(source: String, project_id: String) -> Option<String> {
+    session_core::project_alias::get_project_alias(&source, &project_id)
+}
+
+/// Set or clear (`None`) a project's display alias
+#[tauri::command]
+pub fn set_project_alias(
+    source: String,
+    project_id: String,
+    alias: Option<String>,
+) -> Result<(), String> {
+    session_core::project_alias::set_project_alias(&source, &project_id, alias)
+}
+
+/// Project id (encoded directory name) for a real path, e.g. from a folder picker
+#[tauri::command]
+pub fn encode_project_id(path: String) -> String {
+    session_core::parser::path_encoder::encode_project_id(&path)
+}
@@ -1,10 +1,21 @@
-use session_core::search::SearchResult;
+use session_core::search::{PathMention, SearchResult};
 
 #[tauri::command]
 pub fn global_search(
     source: String,
     query: String,
     max_results: usize,
+    fuzzy: Option<bool>,
 ) -> Result<Vec<SearchResult>, String> {
-    session_core::search::global_search(&source, &query, max_results)
+    session_core::search::global_search(&source, &query, max_results, fuzzy.unwrap_or(false))
+}
+
+/// Sessions of a project that mention a file path in messages or tool calls
+#[tauri::command]
+pub fn find_sessions_mentioning_path(
+    source: String,
+    project_id: String,
+    needle: String,
+) -> Result<Vec<PathMention>, String> {
+    session_core::search::find_sessions_mentioning_path(&source, &project_id, &needle)
 }
@@ -1,42 +1,133 @@
+use std::collections::HashMap;
 use std::fs;
 
 use session_core::metadata;
 use session_core::models::session::SessionIndexEntry;
 use session_core::provider::{claude, codex};
+use session_core::session_cache;
+use session_core::session_query::{self, SessionPage, SessionQuery};
+use session_core::trash::{self, TrashEntry};
 
 #[tauri::command]
-pub fn get_sessions(source: String, project_id: String) -> Result<Vec<SessionIndexEntry>, String> {
+pub fn get_sessions(
+    source: String,
+    project_id: String,
+    include_archived: Option<bool>,
+    min_rating: Option<u8>,
+    with_hash: Option<bool>,
+    only_unlabeled: Option<bool>,
+) -> Result<Vec<SessionIndexEntry>, String> {
     let mut sessions = match source.as_str() {
-        "claude" => claude::get_sessions(&project_id)?,
+        "claude" => claude::get_sessions(&project_id, None)?,
         "codex" => codex::get_sessions(&project_id)?,
         _ => return Err(format!("Unknown source: {}", source)),
     };
 
-    // Merge metadata (alias/tags) into session entries
-    let meta = metadata::load_metadata(&source, &project_id);
-    for session in &mut sessions {
-        if let Some(sm) = meta.sessions.get(&session.session_id) {
-            session.alias = sm.alias.clone();
-            if !sm.tags.is_empty() {
-                session.tags = Some(sm.tags.clone());
-            }
-        }
+    // Merge metadata (alias/tags/archived/pins) into session entries
+    let meta = metadata::merge_session_meta(&source, &project_id, &mut sessions);
+    // Sessions with any alias, tag, rating, pin or note have a metadata entry
+    if only_unlabeled.unwrap_or(false) {
+        sessions.retain(|s| !meta.sessions.contains_key(&s.session_id));
+    }
+
+    if !include_archived.unwrap_or(false) {
+        sessions.retain(|s| !s.archived);
+    }
+    if let Some(min) = min_rating {
+        session_query::filter_by_min_rating(&mut sessions, min);
+    }
+    if with_hash.unwrap_or(false) {
+        session_cache::fill_content_hashes(&mut sessions);
+    }
+
+    Ok(sessions)
+}
+
+/// List Claude sessions from a custom projects root (e.g. a restored backup of
+/// `~/.claude/projects`), reading metadata from that root as well.
+#[tauri::command]
+pub fn get_sessions_from_dir(
+    projects_dir: String,
+    project_id: String,
+    include_archived: Option<bool>,
+    min_rating: Option<u8>,
+    with_hash: Option<bool>,
+    only_unlabeled: Option<bool>,
+) -> Result<Vec<SessionIndexEntry>, String> {
+    let root = std::path::Path::new(&projects_dir);
+    if !root.is_dir() {
+        return Err(format!("Projects directory not found: {}", projects_dir));
+    }
+
+    let mut sessions = claude::get_sessions(&project_id, Some(root))?;
+
+    let meta = metadata::merge_session_meta_in("claude", &project_id, Some(root), &mut sessions);
+    // Sessions with any alias, tag, rating, pin or note have a metadata entry
+    if only_unlabeled.unwrap_or(false) {
+        sessions.retain(|s| !meta.sessions.contains_key(&s.session_id));
+    }
+
+    if !include_archived.unwrap_or(false) {
+        sessions.retain(|s| !s.archived);
+    }
+    if let Some(min) = min_rating {
+        session_query::filter_by_min_rating(&mut sessions, min);
+    }
+    if with_hash.unwrap_or(false) {
+        session_cache::fill_content_hashes(&mut sessions);
     }
 
     Ok(sessions)
 }
 
+/// Hex SHA-256 of a session file, for detecting real content changes
+#[tauri::command]
+pub fn session_content_hash(file_path: String) -> Result<String, String> {
+    session_cache::session_content_hash(&file_path)
+}
+
+/// Session ids a running CLI process appears to be working on
+#[tauri::command]
+pub async fn detect_running_cli_sessions(source: String) -> Result<Vec<String>, String> {
+    tokio::task::spawn_blocking(move || {
+        session_core::live_sessions::detect_running_cli_sessions(&source)
+    })
+    .await
+    .map_err(|e| format!("detect_running_cli_sessions task failed: {}", e))
+}
+
+/// Filtered, sorted, paginated session listing in one call
+#[tauri::command]
+pub fn query_sessions(query: SessionQuery) -> Result<SessionPage, String> {
+    session_query::query_sessions(&query)
+}
+
+#[tauri::command]
+pub fn set_session_rating(
+    source: String,
+    project_id: String,
+    session_id: String,
+    rating: Option<u8>,
+) -> Result<(), String> {
+    metadata::set_session_rating(&source, &project_id, &session_id, rating)
+}
+
 #[tauri::command]
 pub fn delete_session(
     file_path: String,
     source: String,
     project_id: String,
     session_id: String,
+    to_trash: Option<bool>,
 ) -> Result<(), String> {
     let path = std::path::Path::new(&file_path);
     if !path.exists() {
         return Err(format!("File not found: {}", file_path));
     }
+    if to_trash.unwrap_or(false) {
+        // Metadata stays until the trash entry is purged
+        return trash::move_to_trash(&file_path, &source, &project_id, &session_id).map(|_| ());
+    }
     fs::remove_file(path).map_err(|e| format!("Failed to delete session: {}", e))?;
 
     // Clean up metadata
@@ -45,6 +136,91 @@ pub fn delete_session(
     Ok(())
 }
 
+/// Trashed sessions of a source, most recently deleted first
+#[tauri::command]
+pub fn list_trash(source: String) -> Result<Vec<TrashEntry>, String> {
+    trash::list_trash(&source)
+}
+
+/// Move a trashed session back to where it was; returns its path
+#[tauri::command]
+pub fn restore_session(id: String) -> Result<String, String> {
+    trash::restore_session(&id)
+}
+
+#[tauri::command]
+pub fn empty_trash(source: Option<String>) -> Result<usize, String> {
+    trash::empty_trash(source.as_deref())
+}
+
+#[tauri::command]
+pub fn move_session(
+    file_path: String,
+    source: String,
+    target_project_id: String,
+) -> Result<String, String> {
+    session_core::session_ops::move_session(&file_path, &source, &target_project_id)
+}
+
+#[tauri::command]
+pub fn export_project_ndjson(
+    source: String,
+    project_id: String,
+    dest_path: String,
+) -> Result<usize, String> {
+    session_core::export::export_project_ndjson(&source, &project_id, &dest_path)
+}
+
+#[tauri::command]
+pub fn append_user_message(file_path: String, source: String, text: String) -> Result<(), String> {
+    session_core::session_ops::append_user_message(&file_path, &source, &text)
+}
+
+#[tauri::command]
+pub fn sessions_modified_since(
+    source: String,
+    project_id: Option<String>,
+    since_epoch: i64,
+) -> Result<Vec<SessionIndexEntry>, String> {
+    session_core::stats::sessions_modified_since(&source, project_id.as_deref(), since_epoch)
+}
+
+#[tauri::command]
+pub fn sessions_grouped_by_tag(
+    source: String,
+    project_id: String,
+) -> Result<std::collections::HashMap<String, Vec<SessionIndexEntry>>, String> {
+    session_core::stats::sessions_grouped_by_tag(&source, &project_id)
+}
+
+#[tauri::command]
+pub fn trim_session(
+    file_path: String,
+    source: String,
+    start_index: usize,
+    end_index: usize,
+) -> Result<String, String> {
+    session_core::session_ops::trim_session(&file_path, &source, start_index, end_index)
+}
+
+#[tauri::command]
+pub fn split_session(
+    file_path: String,
+    source: String,
+    split_indices: Vec<usize>,
+) -> Result<Vec<String>, String> {
+    session_core::session_ops::split_session(&file_path, &source, split_indices)
+}
+
+#[tauri::command]
+pub async fn summarize_session(
+    file_path: String,
+    source: String,
+    model: String,
+) -> Result<String, String> {
+    session_core::summary::summarize_session(&file_path, &source, &model).await
+}
+
 #[tauri::command]
 pub fn update_session_meta(
     source: String,
@@ -52,8 +228,113 @@ pub fn update_session_meta(
     session_id: String,
     alias: Option<String>,
     tags: Vec<String>,
+    archived: Option<bool>,
+) -> Result<(), String> {
+    metadata::update_session_meta(&source, &project_id, &session_id, alias, tags, archived)
+}
+
+/// Clear a session's alias and tags (archived state, pins and note are kept)
+#[tauri::command]
+pub fn clear_session_meta(
+    source: String,
+    project_id: String,
+    session_id: String,
 ) -> Result<(), String> {
-    metadata::update_session_meta(&source, &project_id, &session_id, alias, tags)
+    metadata::clear_session_meta(&source, &project_id, &session_id)
+}
+
+/// Sessions a tag rename would touch, without writing anything
+#[tauri::command]
+pub fn preview_rename_tag(
+    source: String,
+    project_id: String,
+    old: String,
+    new: String,
+) -> Result<metadata::RenamePreview, String> {
+    metadata::preview_rename_tag(&source, &project_id, &old, &new)
+}
+
+#[tauri::command]
+pub fn rename_tag(
+    source: String,
+    project_id: String,
+    old: String,
+    new: String,
+) -> Result<metadata::RenamePreview, String> {
+    metadata::rename_tag(&source, &project_id, &old, &new)
+}
+
+/// Remove a tag from every session of a project; returns how many sessions changed
+#[tauri::command]
+pub fn delete_tag(source: String, project_id: String, tag: String) -> Result<usize, String> {
+    metadata::delete_tag(&source, &project_id, &tag)
+}
+
+/// Check tags against the configured tag rules; the error lists each violation
+#[tauri::command]
+pub fn validate_tags(tags: Vec<String>) -> Result<(), Vec<String>> {
+    metadata::validate_tags(&tags)
+}
+
+/// Tag sessions matching the auto-tag rules; returns how many sessions gained a tag
+#[tauri::command]
+pub async fn apply_auto_tag_rules(
+    source: String,
+    project_id: String,
+    dry_run: Option<bool>,
+) -> Result<usize, String> {
+    tokio::task::spawn_blocking(move || {
+        session_core::auto_tag::apply_auto_tag_rules(&source, &project_id, dry_run.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("apply_auto_tag_rules task failed: {}", e))?
+}
+
+/// Unify tags differing only by case; returns how many tags were rewritten
+#[tauri::command]
+pub fn merge_tag_casing(source: String, project_id: String) -> Result<usize, String> {
+    metadata::merge_tag_casing(&source, &project_id)
+}
+
+#[tauri::command]
+pub fn toggle_pinned_message(
+    source: String,
+    project_id: String,
+    session_id: String,
+    message_id: String,
+) -> Result<bool, String> {
+    metadata::toggle_pinned_message(&source, &project_id, &session_id, &message_id)
+}
+
+#[tauri::command]
+pub fn reconcile_metadata(
+    source: String,
+    project_id: String,
+    rekey: Option<bool>,
+) -> Result<metadata::ReconcileReport, String> {
+    metadata::reconcile_metadata(&source, &project_id, rekey.unwrap_or(false))
+}
+
+/// Import a metadata file (`.session-viewer-meta.json` format) into a project.
+/// With `dry_run` nothing is written and the summary previews the import.
+#[tauri::command]
+pub fn import_metadata(
+    source: String,
+    project_id: String,
+    path: String,
+    policy: Option<metadata::ConflictPolicy>,
+    dry_run: Option<bool>,
+) -> Result<metadata::ImportSummary, String> {
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let imported: metadata::MetadataFile =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid metadata file: {}", e))?;
+    metadata::import_metadata(
+        &source,
+        &project_id,
+        imported,
+        policy.unwrap_or_default(),
+        dry_run.unwrap_or(false),
+    )
 }
 
 #[tauri::command]
@@ -61,9 +342,67 @@ pub fn get_all_tags(source: String, project_id: String) -> Result<Vec<String>, S
     Ok(metadata::get_all_tags(&source, &project_id))
 }
 
+#[tauri::command]
+pub fn list_aliases(source: String, project_id: String) -> Result<Vec<(String, String)>, String> {
+    Ok(metadata::list_aliases(&source, &project_id))
+}
+
+#[tauri::command]
+pub fn find_session_by_alias_fuzzy(
+    source: String,
+    project_id: String,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<(String, String, u32)>, String> {
+    Ok(metadata::find_session_by_alias_fuzzy(
+        &source,
+        &project_id,
+        &query,
+        limit.unwrap_or(10),
+    ))
+}
+
+#[tauri::command]
+pub fn find_duplicate_aliases(
+    source: String,
+    project_id: String,
+) -> Result<std::collections::HashMap<String, Vec<String>>, String> {
+    Ok(metadata::find_duplicate_aliases(&source, &project_id))
+}
+
 #[tauri::command]
 pub fn get_cross_project_tags(
     source: String,
 ) -> Result<std::collections::HashMap<String, Vec<String>>, String> {
     Ok(metadata::get_all_cross_project_tags(&source))
 }
+
+/// Tags of every project of every source: source → project → tags
+#[tauri::command]
+pub fn get_all_tags_all_sources() -> Result<HashMap<String, HashMap<String, Vec<String>>>, String> {
+    Ok(metadata::get_all_tags_all_sources())
+}
+
+/// Number of sessions carrying each tag across every source
+#[tauri::command]
+pub fn tag_counts_all_sources() -> Result<HashMap<String, usize>, String> {
+    Ok(metadata::tag_counts_all_sources())
+}
+
+#[tauri::command]
+pub fn top_tags(source: String, limit: usize) -> Result<Vec<(String, usize)>, String> {
+    Ok(metadata::top_tags(&source, limit))
+}
+
+/// Tags with the mtime of their most recent session, newest first.
+/// Without a project id the list covers every project of the source.
+#[tauri::command]
+pub fn get_tags_with_recency(
+    source: String,
+    project_id: Option<String>,
+) -> Result<Vec<(String, i64)>, String> {
+    Ok(match project_id {
+        Some(pid) => metadata::get_tags_with_recency(&source, &pid),
+        None => metadata::get_recent_tags(&source),
+    })
+}
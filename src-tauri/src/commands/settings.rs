@@ -0,0 +1,52 @@
+use session_core::data_dir::{self, MigrationReport};
+use session_core::settings::{self, SettingsFile, SettingsWarning};
+use session_core::state::AppState;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub fn get_settings() -> Result<SettingsFile, String> {
+    Ok(settings::load_settings())
+}
+
+#[tauri::command]
+pub fn save_settings(settings: SettingsFile) -> Result<(), String> {
+    settings::save_settings(&settings)
+}
+
+#[tauri::command]
+pub fn add_ignored_project(pattern: String) -> Result<(), String> {
+    settings::add_ignored_project(&pattern)
+}
+
+#[tauri::command]
+pub fn remove_ignored_project(pattern: String) -> Result<(), String> {
+    settings::remove_ignored_project(&pattern)
+}
+
+#[tauri::command]
+pub fn validate_settings() -> Result<Vec<SettingsWarning>, String> {
+    settings::validate_settings()
+}
+
+/// Copy (or, with `remove_source`, move) the viewer's data files into `new_path`.
+/// Takes effect once `SESSION_VIEWER_DATA_DIR` points there and the app restarts.
+#[tauri::command]
+pub async fn migrate_data_dir(
+    new_path: String,
+    remove_source: Option<bool>,
+) -> Result<MigrationReport, String> {
+    tokio::task::spawn_blocking(move || {
+        data_dir::migrate_data_dir(&new_path, remove_source.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("migrate_data_dir task failed: {}", e))?
+}
+
+/// Clear all caches, then rerun CLI discovery so the installation list is
+/// rebuilt (emits the same events as `detect_cli_streaming`).
+#[tauri::command]
+pub async fn clear_all_caches(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    state.clear_all_caches()?;
+    super::chat::detect_cli_streaming(app).await?;
+    Ok(())
+}
@@ -1,6 +1,75 @@
+use session_core::cost::CostEstimate;
+use session_core::models::session::SessionIndexEntry;
 use session_core::models::stats::TokenUsageSummary;
+use session_core::stats::TurnInfo;
 
 #[tauri::command]
 pub fn get_stats(source: String) -> Result<TokenUsageSummary, String> {
     session_core::stats::get_stats(&source)
 }
+
+#[tauri::command]
+pub fn message_role_counts(
+    file_path: String,
+    source: String,
+) -> Result<std::collections::HashMap<String, usize>, String> {
+    session_core::stats::message_role_counts(&file_path, &source)
+}
+
+#[tauri::command]
+pub fn sessions_by_token_usage(
+    source: String,
+    project_id: Option<String>,
+    limit: usize,
+) -> Result<Vec<SessionIndexEntry>, String> {
+    session_core::stats::sessions_by_token_usage(&source, project_id.as_deref(), limit)
+}
+
+#[tauri::command]
+pub fn activity_by_day(
+    source: String,
+    project_id: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<std::collections::HashMap<String, usize>, String> {
+    session_core::stats::activity_by_day(
+        &source,
+        project_id.as_deref(),
+        from.as_deref(),
+        to.as_deref(),
+    )
+}
+
+/// Session counts per age bucket ("today", "this week", "this month", "older")
+#[tauri::command]
+pub fn session_age_histogram(
+    source: String,
+    project_id: Option<String>,
+) -> Result<Vec<(String, usize)>, String> {
+    session_core::stats::session_age_histogram(&source, project_id.as_deref())
+}
+
+#[tauri::command]
+pub fn models_used(
+    source: String,
+    project_id: Option<String>,
+) -> Result<Vec<(String, usize)>, String> {
+    session_core::stats::models_used(&source, project_id.as_deref())
+}
+
+/// Estimated USD cost of a session from its token usage and built-in prices
+#[tauri::command]
+pub fn session_cost(file_path: String, source: String) -> Result<CostEstimate, String> {
+    session_core::cost::session_cost(&file_path, &source)
+}
+
+#[tauri::command]
+pub fn project_cost(source: String, project_id: String) -> Result<CostEstimate, String> {
+    session_core::cost::project_cost(&source, &project_id)
+}
+
+/// The assistant message with the most content, for spotting verbose replies
+#[tauri::command]
+pub fn longest_turn(file_path: String, source: String) -> Result<TurnInfo, String> {
+    session_core::stats::longest_turn(&file_path, &source)
+}
@@ -4,6 +4,7 @@ use std::process::Command;
 
 use session_core::models::session::{SessionsIndex, SessionsIndexFileEntry};
 use session_core::parser::jsonl as claude_parser;
+use session_core::settings;
 
 #[tauri::command]
 pub fn resume_session(
@@ -157,7 +158,8 @@ fn ensure_session_in_index(session_id: &str, file_path: &str, project_path: &str
     }
 
     // Build an entry from the JSONL file metadata
-    let first_prompt = claude_parser::extract_first_prompt(session_file);
+    let first_prompt =
+        claude_parser::extract_first_prompt(session_file, settings::preview_max_chars());
     let metadata = claude_parser::extract_session_metadata(session_file);
     let (_, git_branch, cwd) = metadata.unwrap_or((String::new(), None, None));
 
@@ -1,11 +1,19 @@
 mod commands;
 mod watcher;
 
-use commands::chat::ChatProcessState;
+use commands::chat::{ChatProcessState, ChatStreamState};
 use session_core::state::AppState;
+use tracing_subscriber::filter::LevelFilter;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // session-core logs through tracing; RUST_LOG=debug shows quick chat's debugRaw log
+    let level = std::env::var("RUST_LOG")
+        .ok()
+        .and_then(|v| v.parse::<LevelFilter>().ok())
+        .unwrap_or(LevelFilter::INFO);
+    let _ = tracing_subscriber::fmt().with_max_level(level).try_init();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_fs::init())
@@ -13,34 +21,123 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .manage(AppState::new())
         .manage(ChatProcessState::new())
+        .manage(ChatStreamState::new())
         .invoke_handler(tauri::generate_handler![
             commands::projects::get_projects,
+            commands::projects::get_project_info,
+            commands::projects::project_disk_usage,
+            commands::projects::claude_data_dir_status,
+            commands::projects::get_project_alias,
+            commands::projects::set_project_alias,
+            commands::projects::encode_project_id,
             commands::sessions::get_sessions,
+            commands::sessions::get_sessions_from_dir,
+            commands::sessions::set_session_rating,
+            commands::sessions::query_sessions,
+            commands::sessions::detect_running_cli_sessions,
+            commands::sessions::session_content_hash,
             commands::sessions::delete_session,
+            commands::sessions::list_trash,
+            commands::sessions::restore_session,
+            commands::sessions::empty_trash,
+            commands::sessions::move_session,
+            commands::sessions::append_user_message,
+            commands::sessions::trim_session,
+            commands::sessions::split_session,
+            commands::sessions::sessions_modified_since,
+            commands::sessions::sessions_grouped_by_tag,
+            commands::sessions::export_project_ndjson,
+            commands::sessions::summarize_session,
             commands::sessions::update_session_meta,
+            commands::sessions::clear_session_meta,
+            commands::sessions::preview_rename_tag,
+            commands::sessions::rename_tag,
+            commands::sessions::merge_tag_casing,
+            commands::sessions::delete_tag,
+            commands::sessions::validate_tags,
+            commands::sessions::apply_auto_tag_rules,
+            commands::sessions::toggle_pinned_message,
+            commands::sessions::reconcile_metadata,
+            commands::sessions::import_metadata,
             commands::sessions::get_all_tags,
+            commands::sessions::list_aliases,
+            commands::sessions::find_duplicate_aliases,
+            commands::sessions::find_session_by_alias_fuzzy,
             commands::sessions::get_cross_project_tags,
+            commands::sessions::get_all_tags_all_sources,
+            commands::sessions::tag_counts_all_sources,
+            commands::sessions::top_tags,
+            commands::sessions::get_tags_with_recency,
             commands::messages::get_messages,
+            commands::messages::check_session_file,
+            commands::messages::validate_session,
+            commands::messages::session_size_info,
+            commands::messages::message_to_chat_msg,
+            commands::messages::load_session_as_messages,
+            commands::messages::extract_code_blocks,
+            commands::messages::write_code_blocks,
             commands::search::global_search,
+            commands::search::find_sessions_mentioning_path,
             commands::stats::get_stats,
+            commands::stats::message_role_counts,
+            commands::stats::sessions_by_token_usage,
+            commands::stats::activity_by_day,
+            commands::stats::session_age_histogram,
+            commands::stats::models_used,
+            commands::stats::session_cost,
+            commands::stats::project_cost,
+            commands::stats::longest_turn,
             commands::terminal::resume_session,
             commands::updater::get_install_type,
             commands::chat::detect_cli,
+            commands::chat::detect_cli_streaming,
             commands::chat::get_cli_config,
+            commands::chat::explain_config,
+            commands::chat::config_writable,
             commands::chat::list_models,
+            commands::chat::list_models_page,
+            commands::chat::audit_session_models,
+            commands::chat::check_session_model_available,
             commands::chat::start_chat,
             commands::chat::continue_chat,
             commands::chat::cancel_chat,
             commands::chat::quick_chat,
+            commands::chat::stream_chat_cmd,
+            commands::chat::cancel_stream_chat,
+            commands::chat::export_chat_template,
+            commands::chat::import_chat_template,
             commands::bookmarks::list_bookmarks,
             commands::bookmarks::add_bookmark,
             commands::bookmarks::remove_bookmark,
+            commands::bookmarks::move_bookmarks,
+            commands::bookmarks::export_bookmark_context,
+            commands::bookmarks::audit_bookmarks,
+            commands::settings::get_settings,
+            commands::settings::save_settings,
+            commands::settings::validate_settings,
+            commands::settings::add_ignored_project,
+            commands::settings::remove_ignored_project,
+            commands::settings::clear_all_caches,
+            commands::settings::migrate_data_dir,
+            commands::backup::backup_all,
+            commands::backup::restore_all,
         ])
         .setup(|app| {
             #[cfg(desktop)]
             app.handle()
                 .plugin(tauri_plugin_updater::Builder::new().build())?;
 
+            // Warm the model list so the first picker open doesn't wait on the API
+            tauri::async_runtime::spawn(async {
+                session_core::model_list::prefetch_models("claude");
+            });
+
+            tauri::async_runtime::spawn_blocking(|| {
+                if let Err(e) = session_core::trash::purge_expired_trash() {
+                    eprintln!("Warning: Failed to purge expired trash: {}", e);
+                }
+            });
+
             let handle = app.handle().clone();
             if let Err(e) = watcher::fs_watcher::start_watcher(handle) {
                 eprintln!("Warning: Failed to start file watcher: {}", e);
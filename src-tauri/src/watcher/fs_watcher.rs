@@ -55,10 +55,20 @@ pub fn start_watcher(app_handle: AppHandle) -> Result<(), String> {
         }
 
         let mut last_emit = Instant::now() - DEBOUNCE_DURATION;
+        let mut last_flush = Instant::now();
+
+        loop {
+            // Wake up at least once per window so queued reindexes are
+            // flushed even after the writes stop
+            match rx.recv_timeout(DEBOUNCE_DURATION) {
+                Ok(Ok(event)) => {
+                    // Directory events matter too (new/removed projects), so this
+                    // runs before the session-file filter and the debounce
+                    for path in &event.paths {
+                        session_core::project_info::invalidate_path(path);
+                        session_core::session_cache::queue_reindex(path);
+                    }
 
-        for event in rx {
-            match event {
-                Ok(event) => {
                     let relevant = event.paths.iter().any(|p| {
                         let is_meta = p
                             .file_name()
@@ -81,9 +91,16 @@ pub fn start_watcher(app_handle: AppHandle) -> Result<(), String> {
                         last_emit = Instant::now();
                     }
                 }
-                Err(e) => {
+                Ok(Err(e)) => {
                     eprintln!("Watch error: {}", e);
                 }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+
+            if last_flush.elapsed() >= DEBOUNCE_DURATION {
+                session_core::session_cache::flush_reindex();
+                last_flush = Instant::now();
             }
         }
     });
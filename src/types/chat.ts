@@ -6,6 +6,11 @@ export interface ModelInfo {
   created: number | null;
 }
 
+export interface ModelPage {
+  models: ModelInfo[];
+  nextCursor: string | null;
+}
+
 export interface CliInstallation {
   path: string;
   version: string | null;
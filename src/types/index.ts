@@ -96,4 +96,5 @@ export interface Bookmark {
   sessionTitle: string;
   projectName: string;
   createdAt: string;
+  folder?: string | null;
 }